@@ -0,0 +1,168 @@
+//!
+//! Tests that cover the pointer-sized integers, the 128-bit integers, and `char`.
+//!
+
+use ffi_common::core::{
+    datetime::{checked_vec_from_ffi, CheckedFromFFI},
+    error::get_last_err_msg,
+    option_char_free, option_char_init, option_u128_init,
+    string::{free_rust_string, string_from_c},
+    FFIchar, FFIi128, FFIu128,
+};
+
+#[derive(Debug, Clone, PartialEq, ffi_common::derive::FFI)]
+pub struct Measurement {
+    count: usize,
+    offset: isize,
+    total: u128,
+    delta: i128,
+    grade: char,
+    suffix: Option<char>,
+    largest: Option<u128>,
+    samples: Vec<u128>,
+    flags: Vec<char>,
+}
+
+#[ffi_common::derive::expose_impl(description("scalars"))]
+impl Measurement {
+    fn doubled_total(&self) -> u128 {
+        self.total * 2
+    }
+
+    fn next_grade(&self) -> Result<char, String> {
+        std::char::from_u32(u32::from(self.grade) + 1).ok_or_else(|| "No next grade".to_string())
+    }
+
+    fn shifted(&self, by: i128, grade: char) -> Self {
+        Self {
+            delta: self.delta + by,
+            grade,
+            ..self.clone()
+        }
+    }
+}
+
+fn measurement() -> Measurement {
+    Measurement {
+        count: usize::MAX,
+        offset: isize::MIN,
+        total: u128::MAX / 2 - 1,
+        delta: i128::MIN + 1,
+        grade: 'B',
+        suffix: Some('+'),
+        largest: None,
+        samples: vec![0, u128::from(u64::MAX) + 1],
+        flags: vec!['a', '🌽'],
+    }
+}
+
+#[test]
+fn scalar_fields_round_trip() {
+    use measurement_ffi::*;
+
+    let native = measurement();
+    unsafe {
+        let ptr = measurement_rust_ffi_init(
+            native.count,
+            native.offset,
+            native.total.into(),
+            native.delta.into(),
+            native.grade.into(),
+            option_char_init(true, '+'.into()) as *mut FFIchar,
+            option_u128_init(false, FFIu128::default()) as *mut FFIu128,
+            (&*native.samples).into(),
+            (&*native.flags).into(),
+        );
+        assert_eq!(&*ptr, &native);
+
+        assert_eq!(get_measurement_count(ptr), usize::MAX);
+        assert_eq!(get_measurement_offset(ptr), isize::MIN);
+        assert_eq!(u128::from(get_measurement_total(ptr)), native.total);
+        assert_eq!(i128::from(get_measurement_delta(ptr)), i128::MIN + 1);
+        assert_eq!(get_measurement_grade(ptr), FFIchar('B' as u32));
+
+        let suffix = get_optional_measurement_suffix(ptr);
+        assert_eq!(char::checked_from_ffi(&*suffix), Ok('+'));
+        option_char_free(suffix);
+        assert!(get_optional_measurement_largest(ptr).is_null());
+
+        let samples: Vec<u128> = get_measurement_samples(ptr).into();
+        assert_eq!(samples, native.samples);
+        let flags = checked_vec_from_ffi::<char, _>(get_measurement_flags(ptr));
+        assert_eq!(flags, Ok(native.flags.clone()));
+
+        measurement_rust_ffi_free(ptr);
+    }
+}
+
+#[test]
+fn scalar_arguments_and_returns() {
+    let ptr = Box::into_raw(Box::new(measurement()));
+    unsafe {
+        let doubled = scalars_measurement_ffi::scalars_measurement_ffi_doubled_total(ptr);
+        assert_eq!(
+            doubled,
+            FFIu128 {
+                high: u64::MAX,
+                low: u64::MAX - 3
+            }
+        );
+        assert_eq!(
+            scalars_measurement_ffi::scalars_measurement_ffi_next_grade(ptr),
+            FFIchar('C' as u32)
+        );
+
+        let shifted = scalars_measurement_ffi::scalars_measurement_ffi_shifted(
+            ptr,
+            FFIi128::from(-1),
+            FFIchar::from('A'),
+        );
+        assert_eq!((*shifted).delta, i128::MIN);
+        assert_eq!((*shifted).grade, 'A');
+
+        measurement_ffi::measurement_rust_ffi_free(shifted);
+        measurement_ffi::measurement_rust_ffi_free(ptr);
+    }
+}
+
+#[test]
+fn invalid_scalars_set_error() {
+    let ptr = Box::into_raw(Box::new(measurement()));
+    unsafe {
+        // A lone surrogate isn't a Unicode scalar, so it can't be converted to a `char`.
+        let shifted = scalars_measurement_ffi::scalars_measurement_ffi_shifted(
+            ptr,
+            FFIi128::from(0),
+            FFIchar(0xD800),
+        );
+        assert!(shifted.is_null());
+        let error = get_last_err_msg();
+        assert_eq!(
+            string_from_c(error),
+            "Invalid Unicode scalar value: 0xD800."
+        );
+        free_rust_string(error);
+
+        let flags = [FFIchar::from('a'), FFIchar(0x11_0000)];
+        let init = measurement_ffi::measurement_rust_ffi_init(
+            0,
+            0,
+            FFIu128::default(),
+            FFIi128::default(),
+            FFIchar::from('A'),
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            (&[][..]).into(),
+            ffi_common::core::ffi_array_char_init(flags.as_ptr(), 2),
+        );
+        assert!(init.is_null());
+        let error = get_last_err_msg();
+        assert_eq!(
+            string_from_c(error),
+            "Invalid Unicode scalar value: 0x110000."
+        );
+        free_rust_string(error);
+
+        measurement_ffi::measurement_rust_ffi_free(ptr);
+    }
+}
@@ -0,0 +1,123 @@
+//!
+//! Tests that cover routing the buffers we hand to the consumer through a registered allocator.
+//!
+//! The allocator can only be registered once per process, so everything that depends on it lives
+//! in this test binary.
+//!
+
+use ffi_common::core::{
+    allocator::ffi_register_allocator,
+    ffi_array_u16_free, ffi_array_u16_init, option_u16_free, option_u16_init,
+    string::{ffi_array_string_free, free_rust_string, string_from_c, FFIArrayString},
+    FFIArrayu16,
+};
+use std::{
+    alloc::{alloc, dealloc, Layout},
+    collections::HashSet,
+    ffi::c_void,
+    sync::{Mutex, Once},
+};
+
+/// Every allocation we've handed out that hasn't been freed yet.
+static LIVE: Mutex<Option<HashSet<usize>>> = Mutex::new(None);
+static REGISTER: Once = Once::new();
+
+// Allocations are prefixed with a 16-byte header that records their size, so that `test_free` can
+// rebuild the layout.
+const HEADER: usize = 16;
+
+unsafe extern "C" fn test_malloc(size: usize) -> *mut c_void {
+    let layout = Layout::from_size_align(size + HEADER, HEADER).unwrap();
+    let base = alloc(layout);
+    base.cast::<usize>().write(size);
+    let ptr = base.add(HEADER);
+    let _ = LIVE
+        .lock()
+        .unwrap()
+        .get_or_insert_with(HashSet::new)
+        .insert(ptr as usize);
+    ptr.cast()
+}
+
+unsafe extern "C" fn test_free(ptr: *mut c_void) {
+    assert!(
+        LIVE.lock()
+            .unwrap()
+            .as_mut()
+            .unwrap()
+            .remove(&(ptr as usize)),
+        "Freed a pointer that wasn't allocated by the registered allocator."
+    );
+    let base = ptr.cast::<u8>().sub(HEADER);
+    let size = base.cast::<usize>().read();
+    dealloc(
+        base,
+        Layout::from_size_align(size + HEADER, HEADER).unwrap(),
+    );
+}
+
+fn register() {
+    REGISTER.call_once(|| unsafe {
+        assert!(ffi_register_allocator(Some(test_malloc), Some(test_free)));
+    });
+}
+
+fn is_live<T>(ptr: *const T) -> bool {
+    LIVE.lock()
+        .unwrap()
+        .as_ref()
+        .is_some_and(|live| live.contains(&(ptr as usize)))
+}
+
+#[test]
+fn allocator_can_only_be_registered_once() {
+    register();
+    assert!(!unsafe { ffi_register_allocator(Some(test_malloc), Some(test_free)) });
+    assert!(!unsafe { ffi_register_allocator(None, None) });
+}
+
+#[test]
+fn strings_use_registered_allocator() {
+    register();
+    let string = ffi_common::core::ffi_string!("hooked");
+    assert!(is_live(string));
+    assert_eq!(unsafe { string_from_c(string) }, "hooked");
+    unsafe { free_rust_string(string) };
+    assert!(!is_live(string));
+}
+
+#[test]
+fn arrays_use_registered_allocator() {
+    register();
+    let values = vec![1_u16, 2, 3];
+    let array = unsafe { ffi_array_u16_init(values.as_ptr(), 3) };
+    assert!(is_live(array.ptr));
+    let ptr = array.ptr;
+    unsafe { ffi_array_u16_free(array) };
+    assert!(!is_live(ptr));
+
+    // Converting back into a `Vec` also releases the buffer through the registered allocator.
+    let array = FFIArrayu16::from(values.clone());
+    let ptr = array.ptr;
+    assert_eq!(Vec::<u16>::from(array), values);
+    assert!(!is_live(ptr));
+
+    let strings = FFIArrayString::from(&*vec!["a", "b"]);
+    let elements = unsafe { std::slice::from_raw_parts(strings.ptr, strings.len) }.to_vec();
+    assert!(is_live(strings.ptr));
+    assert!(elements.iter().all(|element| is_live(*element)));
+    let ptr = strings.ptr;
+    unsafe { ffi_array_string_free(strings) };
+    assert!(!is_live(ptr));
+    assert!(elements.iter().all(|element| !is_live(*element)));
+}
+
+#[test]
+fn options_use_registered_allocator() {
+    register();
+    let option = option_u16_init(true, 7);
+    assert!(is_live(option));
+    assert_eq!(unsafe { *option }, 7);
+    unsafe { option_u16_free(option) };
+    assert!(!is_live(option));
+}
@@ -0,0 +1,119 @@
+//!
+//! Tests that cover returning values into an `FFIArena` with the `arena` attributes, so that they're
+//! released together.
+//!
+
+use ffi_common::core::{
+    arena::{ffi_arena_free, ffi_arena_init},
+    string::string_from_c,
+};
+
+#[derive(Debug, Clone, PartialEq, ffi_common::derive::FFI)]
+pub struct Row {
+    label: String,
+}
+
+#[derive(Debug, Clone, ffi_common::derive::FFI)]
+#[ffi(arena, snapshot, ffi_mod_imports(row_ffi::FFIArrayRow))]
+pub struct Report {
+    title: String,
+    rows: Vec<Row>,
+    total: Option<u32>,
+    pages: u16,
+}
+
+#[ffi_common::derive::expose_impl(
+    description("arena"),
+    arena(row_labels, first_row),
+    ffi_imports(ffi_common::core::string::FFIArrayString)
+)]
+impl Report {
+    fn row_labels(&self, prefix: String) -> Vec<String> {
+        self.rows
+            .iter()
+            .map(|row| format!("{}{}", prefix, row.label))
+            .collect()
+    }
+
+    fn first_row(&self) -> Row {
+        self.rows[0].clone()
+    }
+}
+
+fn report() -> *const Report {
+    Box::into_raw(Box::new(Report {
+        title: "Yields".to_string(),
+        rows: vec![
+            Row {
+                label: "North".to_string(),
+            },
+            Row {
+                label: "South".to_string(),
+            },
+        ],
+        total: Some(12),
+        pages: 3,
+    }))
+}
+
+#[test]
+fn getters_return_into_arena() {
+    use report_ffi::*;
+
+    let report = report();
+    unsafe {
+        let arena = ffi_arena_init();
+        let title = get_report_title_in_arena(report, arena);
+        let rows = get_report_rows_in_arena(report, arena);
+        let total = get_optional_report_total_in_arena(report, arena);
+        let snapshot = get_report_snapshot_in_arena(report, arena);
+        assert_eq!((*arena).len(), 4);
+
+        assert_eq!(string_from_c(title), "Yields");
+        let rows = std::slice::from_raw_parts(rows.ptr, rows.len);
+        assert_eq!((*rows[1]).label, "South");
+        assert_eq!(*total, 12);
+        assert_eq!(string_from_c(snapshot.title), "Yields");
+        assert_eq!(snapshot.pages, 3);
+
+        // Everything above is released here, instead of piece by piece.
+        ffi_arena_free(arena);
+        report_rust_ffi_free(report);
+    }
+}
+
+#[test]
+fn methods_return_into_arena() {
+    let report = report();
+    unsafe {
+        let arena = ffi_arena_init();
+        let labels = arena_report_ffi::arena_report_ffi_row_labels_in_arena(
+            report,
+            ffi_common::core::ffi_string!("Field "),
+            arena,
+        );
+        let labels: Vec<String> = std::slice::from_raw_parts(labels.ptr, labels.len)
+            .iter()
+            .map(|label| string_from_c(*label))
+            .collect();
+        assert_eq!(labels, vec!["Field North", "Field South"]);
+
+        let first = arena_report_ffi::arena_report_ffi_first_row_in_arena(report, arena);
+        assert_eq!((*first).label, "North");
+        assert_eq!((*arena).len(), 2);
+
+        ffi_arena_free(arena);
+        report_ffi::report_rust_ffi_free(report);
+    }
+}
+
+#[test]
+fn null_arena_leaves_ownership_with_caller() {
+    let report = report();
+    unsafe {
+        let title = report_ffi::get_report_title_in_arena(report, std::ptr::null_mut());
+        assert_eq!(string_from_c(title), "Yields");
+        ffi_common::core::string::free_rust_string(title);
+        report_ffi::report_rust_ffi_free(report);
+    }
+}
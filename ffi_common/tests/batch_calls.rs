@@ -0,0 +1,217 @@
+//!
+//! Tests that cover reading fields from, and calling methods on, many instances in a single call
+//! with the `batch` attributes.
+//!
+
+use ffi_common::core::{
+    option_f64_free,
+    string::{ffi_array_string_free, free_rust_string, string_from_c, FFIArrayString},
+    tuple::FFITuple2,
+    FFIArrayf64,
+};
+use std::os::raw::c_char;
+
+#[derive(Debug, Clone, PartialEq, ffi_common::derive::FFI)]
+pub struct Tag {
+    label: String,
+}
+
+#[derive(Debug, Clone, ffi_common::derive::FFI)]
+#[ffi(ffi_mod_imports(tag_ffi::FFIArrayTag))]
+pub struct Field {
+    #[ffi(batch)]
+    name: String,
+    #[ffi(batch)]
+    acres: f64,
+    #[ffi(batch)]
+    tag: Tag,
+    #[ffi(batch)]
+    crop: Option<String>,
+    #[ffi(batch)]
+    yields: Vec<f64>,
+    #[ffi(batch)]
+    corner: (f64, f64),
+}
+
+#[ffi_common::derive::expose_impl(
+    description("batch"),
+    batch(rounded_acres, checked_name, best_yield, checked_yields),
+    ffi_imports(
+        ffi_common::core::option_f64_init,
+        ffi_common::core::FFIArrayu32,
+        ffi_common::core::string::FFIArrayString
+    )
+)]
+impl Field {
+    fn rounded_acres(&self) -> u32 {
+        self.acres.round() as u32
+    }
+
+    fn checked_name(&self) -> Result<String, String> {
+        if self.name.is_empty() {
+            Err("Field has no name".to_string())
+        } else {
+            Ok(self.name.clone())
+        }
+    }
+
+    fn best_yield(&self) -> Option<f64> {
+        self.yields.iter().copied().reduce(f64::max)
+    }
+
+    fn checked_yields(&self) -> Result<Vec<f64>, String> {
+        self.checked_name().map(|_| self.yields.clone())
+    }
+}
+
+fn fields() -> Vec<*const Field> {
+    vec![
+        Box::into_raw(Box::new(Field {
+            name: "North".to_string(),
+            acres: 40.4,
+            tag: Tag {
+                label: "corn".to_string(),
+            },
+            crop: Some("Corn".to_string()),
+            yields: vec![180.5, 192.0],
+            corner: (41.5, -93.6),
+        })),
+        Box::into_raw(Box::new(Field {
+            name: String::new(),
+            acres: 79.6,
+            tag: Tag {
+                label: "soy".to_string(),
+            },
+            crop: None,
+            yields: vec![],
+            corner: (42.0, -93.1),
+        })),
+    ]
+}
+
+fn free_fields(fields: Vec<*const Field>) {
+    for field in fields {
+        unsafe { field_ffi::field_rust_ffi_free(field) };
+    }
+}
+
+#[test]
+fn batch_getters_read_every_receiver() {
+    let fields = fields();
+    unsafe {
+        let names: Vec<String> =
+            field_ffi::get_field_name_batch(fields.as_ptr(), fields.len()).into();
+        assert_eq!(names, vec!["North".to_string(), String::new()]);
+
+        let acres: Vec<f64> =
+            field_ffi::get_field_acres_batch(fields.as_ptr(), fields.len()).into();
+        assert_eq!(acres, vec![40.4, 79.6]);
+
+        let tags: Vec<Tag> = field_ffi::get_field_tag_batch(fields.as_ptr(), fields.len()).into();
+        assert_eq!(
+            tags,
+            vec![
+                Tag {
+                    label: "corn".to_string()
+                },
+                Tag {
+                    label: "soy".to_string()
+                }
+            ]
+        );
+
+        let empty: Vec<f64> = field_ffi::get_field_acres_batch(std::ptr::null(), 0).into();
+        assert!(empty.is_empty());
+
+        // `Option` and `Vec` fields are read into an `FFINestedArray`.
+        let crops: Vec<*const c_char> =
+            field_ffi::get_optional_field_crop_batch(fields.as_ptr(), fields.len()).into();
+        assert_eq!(string_from_c(crops[0]), "Corn");
+        free_rust_string(crops[0]);
+        assert!(crops[1].is_null());
+
+        let yields: Vec<FFIArrayf64> =
+            field_ffi::get_field_yields_batch(fields.as_ptr(), fields.len()).into();
+        let yields: Vec<Vec<f64>> = yields.into_iter().map(Vec::from).collect();
+        assert_eq!(yields, vec![vec![180.5, 192.0], vec![]]);
+
+        let corners: Vec<FFITuple2<f64, f64>> =
+            field_ffi::get_field_corner_batch(fields.as_ptr(), fields.len()).into();
+        assert_eq!((corners[1]._0, corners[1]._1), (42.0, -93.1));
+    }
+    free_fields(fields);
+}
+
+#[test]
+fn batch_methods_call_every_receiver() {
+    let fields = fields();
+    unsafe {
+        let rounded: Vec<u32> =
+            batch_field_ffi::batch_field_ffi_rounded_acres_batch(fields.as_ptr(), fields.len())
+                .into();
+        assert_eq!(rounded, vec![40, 80]);
+
+        let best: Vec<*const f64> =
+            batch_field_ffi::batch_field_ffi_best_yield_batch(fields.as_ptr(), fields.len()).into();
+        assert_eq!(*best[0], 192.0);
+        option_f64_free(best[0]);
+        assert!(best[1].is_null());
+    }
+    free_fields(fields);
+}
+
+#[test]
+fn batch_method_errors_are_reported_per_element() {
+    let fields = fields();
+    unsafe {
+        let mut errors = FFIArrayString {
+            ptr: std::ptr::null(),
+            len: 0,
+            cap: 0,
+        };
+        let names = batch_field_ffi::batch_field_ffi_checked_name_batch(
+            fields.as_ptr(),
+            fields.len(),
+            &mut errors,
+        );
+        let error_messages = std::slice::from_raw_parts(errors.ptr, errors.len);
+        assert!(error_messages[0].is_null());
+        assert_eq!(
+            ffi_common::core::string::string_from_c(error_messages[1]),
+            "Field has no name"
+        );
+        let name_values = std::slice::from_raw_parts(names.ptr, names.len);
+        assert_eq!(
+            ffi_common::core::string::string_from_c(name_values[0]),
+            "North"
+        );
+        assert!(name_values[1].is_null());
+
+        ffi_array_string_free(names);
+        ffi_array_string_free(errors);
+
+        // Errors can also be discarded.
+        let names = batch_field_ffi::batch_field_ffi_checked_name_batch(
+            fields.as_ptr(),
+            fields.len(),
+            std::ptr::null_mut(),
+        );
+        ffi_array_string_free(names);
+
+        // Failures of methods that return a `Vec` are empty arrays.
+        let mut errors = FFIArrayString::from(None::<&[String]>);
+        let yields: Vec<FFIArrayf64> = batch_field_ffi::batch_field_ffi_checked_yields_batch(
+            fields.as_ptr(),
+            fields.len(),
+            &mut errors,
+        )
+        .into();
+        let yields: Vec<Vec<f64>> = yields.into_iter().map(Vec::from).collect();
+        assert_eq!(yields, vec![vec![180.5, 192.0], vec![]]);
+        let error_messages = std::slice::from_raw_parts(errors.ptr, errors.len);
+        assert!(error_messages[0].is_null());
+        assert_eq!(string_from_c(error_messages[1]), "Field has no name");
+        ffi_array_string_free(errors);
+    }
+    free_fields(fields);
+}
@@ -0,0 +1,60 @@
+//!
+//! Tests that cover borrowing nested FFI type fields through the derived FFI without cloning them.
+//!
+
+#[derive(Debug, Clone, PartialEq, ffi_common::derive::FFI)]
+pub struct Leaf {
+    value: u32,
+}
+
+#[derive(Debug, Clone, ffi_common::derive::FFI)]
+pub struct Branch {
+    #[ffi(borrow)]
+    leaf: Leaf,
+    #[ffi(borrow)]
+    spare: Option<Leaf>,
+}
+
+#[derive(Debug, Clone, ffi_common::derive::FFI)]
+pub struct Tree {
+    #[ffi(borrow)]
+    branch: Branch,
+}
+
+#[test]
+fn borrowed_fields_point_into_the_owner() {
+    let tree = Box::into_raw(Box::new(Tree {
+        branch: Branch {
+            leaf: Leaf { value: 7 },
+            spare: Some(Leaf { value: 8 }),
+        },
+    }));
+    unsafe {
+        let owner = &*tree;
+        let branch = tree_ffi::get_borrowed_tree_branch(tree);
+        assert_eq!(branch, &owner.branch as *const Branch);
+
+        let leaf = branch_ffi::get_borrowed_branch_leaf(branch);
+        assert_eq!(leaf, &owner.branch.leaf as *const Leaf);
+        // Only the leaf value is copied.
+        assert_eq!(leaf_ffi::get_leaf_value(leaf), 7);
+
+        let spare = branch_ffi::get_borrowed_branch_spare(branch);
+        assert_eq!(spare, owner.branch.spare.as_ref().unwrap() as *const Leaf);
+        assert_eq!(leaf_ffi::get_leaf_value(spare), 8);
+
+        tree_ffi::tree_rust_ffi_free(tree);
+    }
+}
+
+#[test]
+fn borrowed_none_is_null() {
+    let branch = Box::into_raw(Box::new(Branch {
+        leaf: Leaf { value: 1 },
+        spare: None,
+    }));
+    unsafe {
+        assert!(branch_ffi::get_borrowed_branch_spare(branch).is_null());
+        branch_ffi::branch_rust_ffi_free(branch);
+    }
+}
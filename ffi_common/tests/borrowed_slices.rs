@@ -0,0 +1,77 @@
+//!
+//! Tests that cover borrowing primitive collection fields through the derived FFI without copying
+//! their data.
+//!
+
+use ffi_common::core::{ffi_array_f64_init, ffi_array_u8_init, FFIArrayf64, FFIArrayu8};
+use std::convert::TryInto;
+
+#[derive(Debug, Clone, ffi_common::derive::FFI)]
+pub struct SensorReading {
+    #[ffi(borrow)]
+    samples: Vec<f64>,
+    #[ffi(borrow)]
+    image: Option<Vec<u8>>,
+}
+
+#[test]
+fn borrowed_slices_point_into_the_owner() {
+    use sensor_reading_ffi::*;
+
+    let samples = vec![1.5, 2.5, 3.5];
+    let image = vec![0xDE, 0xAD, 0xBE, 0xEF];
+    unsafe {
+        let reading = sensor_reading_rust_ffi_init(
+            ffi_array_f64_init(samples.as_ptr(), samples.len().try_into().unwrap()),
+            ffi_array_u8_init(image.as_ptr(), image.len().try_into().unwrap()),
+        );
+        let owner = &*reading;
+
+        let borrowed_samples = get_borrowed_sensor_reading_samples(reading);
+        assert_eq!(borrowed_samples.ptr, owner.samples.as_ptr());
+        assert_eq!(
+            std::slice::from_raw_parts(borrowed_samples.ptr, borrowed_samples.len),
+            &*samples
+        );
+
+        let borrowed_image = get_borrowed_sensor_reading_image(reading);
+        assert_eq!(
+            borrowed_image.ptr,
+            owner.image.as_ref().map(Vec::as_ptr).unwrap()
+        );
+        assert_eq!(
+            std::slice::from_raw_parts(borrowed_image.ptr, borrowed_image.len),
+            &*image
+        );
+
+        // The copying getters are still available, and don't alias the owner's buffer.
+        let copied: FFIArrayf64 = get_sensor_reading_samples(reading);
+        assert_ne!(copied.ptr, borrowed_samples.ptr);
+        assert_eq!(Vec::<f64>::from(copied), samples);
+
+        sensor_reading_rust_ffi_free(reading);
+    }
+}
+
+#[test]
+fn borrowed_none_is_null() {
+    use sensor_reading_ffi::*;
+
+    let samples: Vec<f64> = vec![];
+    unsafe {
+        let reading = sensor_reading_rust_ffi_init(
+            ffi_array_f64_init(samples.as_ptr(), 0),
+            ffi_array_u8_init(std::ptr::null(), 0),
+        );
+        let borrowed_samples = get_borrowed_sensor_reading_samples(reading);
+        assert!(!borrowed_samples.ptr.is_null());
+        assert_eq!(borrowed_samples.len, 0);
+
+        let borrowed_image = get_borrowed_sensor_reading_image(reading);
+        assert!(borrowed_image.ptr.is_null());
+        assert_eq!(borrowed_image.len, 0);
+
+        assert!(Option::<Vec<u8>>::from(FFIArrayu8::from(None)).is_none());
+        sensor_reading_rust_ffi_free(reading);
+    }
+}
@@ -0,0 +1,286 @@
+//!
+//! Tests that cover time zone aware date times, calendar dates and times, and durations.
+//!
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveTime, TimeDelta, TimeZone, Utc};
+use ffi_common::core::{
+    collections::FFIMap,
+    datetime::{
+        checked_vec_from_ffi, option_CalendarComponents_free, option_CalendarComponents_init,
+        time_stamp_free, CalendarComponents, CheckedFromFFI, FFIArrayCalendarComponents, TimeStamp,
+    },
+    error::get_last_err_msg,
+    option_f64_free,
+    string::{free_rust_string, string_from_c, FFIArrayString},
+    FFIArrayf64,
+};
+use std::{collections::BTreeMap, time::Duration};
+
+#[derive(Debug, Clone, PartialEq, ffi_common::derive::FFI)]
+pub struct Planting {
+    recorded_at: DateTime<Utc>,
+    local_time: DateTime<FixedOffset>,
+    planted_on: NaiveDate,
+    irrigated_at: Option<NaiveTime>,
+    harvest_dates: Vec<NaiveDate>,
+    #[ffi(duration)]
+    growing_time: TimeDelta,
+    #[ffi(duration)]
+    soak_time: Option<Duration>,
+    #[ffi(duration)]
+    intervals: Vec<Duration>,
+}
+
+#[derive(Debug, Clone, PartialEq, ffi_common::derive::FFI)]
+pub struct Schedule {
+    deadlines: BTreeMap<NaiveDate, String>,
+}
+
+/// Types that share their names with `std::time::Duration` and `chrono::DateTime` are exposed like
+/// any other type, unless they're marked as durations.
+///
+mod lessons {
+    #[derive(Debug, Clone, PartialEq, ffi_common::derive::FFI)]
+    pub struct Duration {
+        pub minutes: u32,
+    }
+
+    #[derive(Debug, Clone, PartialEq, ffi_common::derive::FFI)]
+    pub struct Lesson {
+        pub length: Duration,
+    }
+}
+
+#[ffi_common::derive::expose_impl(description("seasons"), duration_types(TimeDelta, Duration))]
+impl Planting {
+    fn days_until(&self, date: NaiveDate) -> TimeDelta {
+        date - self.planted_on
+    }
+
+    fn first_harvest(&self) -> Result<NaiveDate, String> {
+        self.harvest_dates
+            .first()
+            .copied()
+            .ok_or_else(|| "No harvest dates".to_string())
+    }
+
+    fn delayed(&self, by: Duration, until: DateTime<Utc>) -> Self {
+        Self {
+            recorded_at: until,
+            planted_on: self.planted_on + TimeDelta::from_std(by).unwrap(),
+            ..self.clone()
+        }
+    }
+}
+
+fn planting() -> Planting {
+    let offset = FixedOffset::west_opt(6 * 3600).unwrap();
+    Planting {
+        recorded_at: Utc.with_ymd_and_hms(2021, 4, 15, 12, 0, 0).unwrap(),
+        local_time: offset.with_ymd_and_hms(2021, 4, 15, 6, 0, 0).unwrap(),
+        planted_on: NaiveDate::from_ymd_opt(2021, 4, 15).unwrap(),
+        irrigated_at: NaiveTime::from_hms_milli_opt(5, 30, 0, 250),
+        harvest_dates: vec![
+            NaiveDate::from_ymd_opt(2021, 8, 30).unwrap(),
+            NaiveDate::from_ymd_opt(2021, 9, 15).unwrap(),
+        ],
+        growing_time: TimeDelta::days(120),
+        soak_time: None,
+        intervals: vec![Duration::from_millis(1_500), Duration::from_secs(3_600)],
+    }
+}
+
+#[test]
+fn date_and_duration_fields_round_trip() {
+    use planting_ffi::*;
+
+    let native = planting();
+    unsafe {
+        let ptr = planting_rust_ffi_init(
+            Box::into_raw(Box::new(TimeStamp::from(&native.recorded_at))),
+            (&native.local_time).into(),
+            (&native.planted_on).into(),
+            option_CalendarComponents_init(true, native.irrigated_at.as_ref().unwrap().into())
+                as *mut CalendarComponents,
+            (&*native.harvest_dates).into(),
+            native.growing_time.num_seconds() as f64,
+            std::ptr::null_mut(),
+            (&*native.intervals).into(),
+        );
+        assert_eq!(&*ptr, &native);
+
+        let recorded_at = get_planting_recorded_at(ptr);
+        assert_eq!(
+            DateTime::<Utc>::checked_from_ffi(&*recorded_at),
+            Ok(native.recorded_at)
+        );
+        time_stamp_free(recorded_at as *mut TimeStamp);
+
+        let local_time = get_planting_local_time(ptr);
+        assert_eq!(local_time.offset_secs, -6 * 3600);
+        assert_eq!((local_time.hour, local_time.day), (6, 15));
+        assert_eq!(
+            DateTime::<FixedOffset>::checked_from_ffi(&local_time),
+            Ok(native.local_time)
+        );
+
+        let planted_on = get_planting_planted_on(ptr);
+        assert!(planted_on.has_date && !planted_on.has_time && !planted_on.has_offset);
+        assert_eq!(
+            NaiveDate::checked_from_ffi(&planted_on),
+            Ok(native.planted_on)
+        );
+
+        let irrigated_at = get_optional_planting_irrigated_at(ptr);
+        assert_eq!((*irrigated_at).nanosecond, 250_000_000);
+        assert_eq!(
+            NaiveTime::checked_from_ffi(&*irrigated_at).ok(),
+            native.irrigated_at
+        );
+        option_CalendarComponents_free(irrigated_at);
+
+        let harvest_dates = checked_vec_from_ffi::<NaiveDate, _>(get_planting_harvest_dates(ptr));
+        assert_eq!(harvest_dates, Ok(native.harvest_dates.clone()));
+
+        assert!((get_planting_growing_time(ptr) - 120.0 * 86_400.0).abs() < f64::EPSILON);
+        let soak_time = get_optional_planting_soak_time(ptr);
+        assert!(soak_time.is_null());
+        option_f64_free(soak_time);
+
+        let intervals: Vec<f64> = get_planting_intervals(ptr).into();
+        assert_eq!(intervals, vec![1.5, 3_600.0]);
+
+        planting_rust_ffi_free(ptr);
+    }
+}
+
+#[test]
+fn date_and_duration_arguments_and_returns() {
+    use seasons_planting_ffi::*;
+
+    let ptr = Box::into_raw(Box::new(planting()));
+    unsafe {
+        let harvest = NaiveDate::from_ymd_opt(2021, 4, 25).unwrap();
+        let days = seasons_planting_ffi_days_until(ptr, (&harvest).into());
+        assert!((days - 10.0 * 86_400.0).abs() < f64::EPSILON);
+
+        let first_harvest = seasons_planting_ffi_first_harvest(ptr);
+        assert_eq!(
+            NaiveDate::checked_from_ffi(&first_harvest),
+            Ok(NaiveDate::from_ymd_opt(2021, 8, 30).unwrap())
+        );
+
+        let until = Utc.with_ymd_and_hms(2021, 5, 1, 0, 0, 0).unwrap();
+        let delayed = seasons_planting_ffi_delayed(
+            ptr,
+            2.0 * 86_400.0,
+            Box::into_raw(Box::new(TimeStamp::from(&until))),
+        );
+        assert_eq!((*delayed).recorded_at, until);
+        assert_eq!(
+            (*delayed).planted_on,
+            NaiveDate::from_ymd_opt(2021, 4, 17).unwrap()
+        );
+
+        planting_ffi::planting_rust_ffi_free(delayed);
+        planting_ffi::planting_rust_ffi_free(ptr);
+    }
+
+    let array = FFIArrayf64::from(&*vec![TimeDelta::minutes(-1)]);
+    assert_eq!(Vec::<f64>::from(array), vec![-60.0]);
+}
+
+#[test]
+fn invalid_dates_set_error() {
+    let native = planting();
+    let invalid_date = CalendarComponents {
+        has_date: true,
+        year: 2021,
+        month: 13,
+        day: 1,
+        ..CalendarComponents::default()
+    };
+    unsafe {
+        let ptr = planting_ffi::planting_rust_ffi_init(
+            Box::into_raw(Box::new(TimeStamp::from(&native.recorded_at))),
+            (&native.local_time).into(),
+            invalid_date,
+            std::ptr::null_mut(),
+            (&*native.harvest_dates).into(),
+            0.0,
+            std::ptr::null_mut(),
+            (&*native.intervals).into(),
+        );
+        assert!(ptr.is_null());
+        let error = get_last_err_msg();
+        assert_eq!(
+            string_from_c(error),
+            "Invalid date: year 2021, month 13, day 1."
+        );
+        free_rust_string(error);
+
+        let ptr = Box::into_raw(Box::new(native));
+        let days = seasons_planting_ffi::seasons_planting_ffi_days_until(ptr, invalid_date);
+        assert!(days.abs() < f64::EPSILON);
+        let error = get_last_err_msg();
+        assert_eq!(
+            string_from_c(error),
+            "Invalid date: year 2021, month 13, day 1."
+        );
+        free_rust_string(error);
+        planting_ffi::planting_rust_ffi_free(ptr);
+    }
+}
+
+#[test]
+fn types_named_like_durations_are_boxed_by_default() {
+    use lessons::{duration_ffi, lesson_ffi, Duration, Lesson};
+
+    unsafe {
+        let length = duration_ffi::duration_rust_ffi_init(45) as *mut Duration;
+        let lesson = lesson_ffi::lesson_rust_ffi_init(length);
+        assert_eq!(
+            &*lesson,
+            &Lesson {
+                length: Duration { minutes: 45 }
+            }
+        );
+
+        let length = lesson_ffi::get_lesson_length(lesson);
+        assert_eq!(duration_ffi::get_duration_minutes(length), 45);
+        duration_ffi::duration_rust_ffi_free(length as *mut Duration);
+        lesson_ffi::lesson_rust_ffi_free(lesson);
+    }
+}
+
+#[test]
+fn invalid_map_keys_set_error() {
+    let deadline = NaiveDate::from_ymd_opt(2021, 9, 1).unwrap();
+    unsafe {
+        let ptr = schedule_ffi::schedule_rust_ffi_init(
+            (&BTreeMap::from([(deadline, "Harvest".to_string())])).into(),
+        );
+        assert_eq!(
+            (*ptr).deadlines,
+            BTreeMap::from([(deadline, "Harvest".to_string())])
+        );
+        schedule_ffi::schedule_rust_ffi_free(ptr);
+
+        let invalid_deadlines = FFIMap {
+            keys: FFIArrayCalendarComponents::from(vec![CalendarComponents {
+                month: 2,
+                day: 30,
+                ..CalendarComponents::from(&deadline)
+            }]),
+            values: FFIArrayString::from(&["Harvest".to_string()][..]),
+        };
+        let ptr = schedule_ffi::schedule_rust_ffi_init(invalid_deadlines);
+        assert!(ptr.is_null());
+        let error = get_last_err_msg();
+        assert_eq!(
+            string_from_c(error),
+            "Invalid date: year 2021, month 2, day 30."
+        );
+        free_rust_string(error);
+    }
+}
@@ -0,0 +1,72 @@
+//!
+//! Tests that cover exposing enums with an integer `repr` and explicit discriminants.
+//!
+
+use ffi_common::core::{
+    error::get_last_err_msg,
+    string::{free_rust_string, string_from_c},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, ffi_common::derive::FFI)]
+#[repr(u8)]
+pub enum Status {
+    Active = 1,
+    Pending,
+    Archived = 7,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ffi_common::derive::FFI)]
+#[repr(i32)]
+pub enum Offset {
+    Behind = -1,
+    Even,
+    Ahead,
+}
+
+#[derive(Debug, Clone, PartialEq, ffi_common::derive::FFI)]
+pub struct Task {
+    #[ffi(raw)]
+    status: Status,
+    #[ffi(raw)]
+    offset: Offset,
+}
+
+#[test]
+fn from_raw_returns_matching_variant() {
+    unsafe {
+        let status = status_ffi::status_from_raw(2);
+        assert_eq!(*status, Status::Pending);
+        status_ffi::option_Status_free(status);
+
+        let offset = offset_ffi::offset_from_raw(-1);
+        assert_eq!(*offset, Offset::Behind);
+        offset_ffi::option_Offset_free(offset);
+    }
+}
+
+#[test]
+fn from_raw_reports_invalid_values() {
+    unsafe {
+        assert!(status_ffi::status_from_raw(3).is_null());
+        let error = get_last_err_msg();
+        assert_eq!(
+            string_from_c(error),
+            "`3` isn't a valid raw value for `Status`."
+        );
+        free_rust_string(error);
+    }
+}
+
+#[test]
+fn integer_repr_fields_round_trip() {
+    use task_ffi::*;
+
+    unsafe {
+        let offset = offset_ffi::offset_from_raw(1);
+        let ptr = task_rust_ffi_init(Status::Archived, *offset);
+        offset_ffi::option_Offset_free(offset);
+        assert_eq!(get_task_status(ptr), Status::Archived);
+        assert_eq!(get_task_offset(ptr), Offset::Ahead);
+        task_rust_ffi_free(ptr);
+    }
+}
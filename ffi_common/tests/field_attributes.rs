@@ -0,0 +1,80 @@
+//!
+//! Tests that cover the `skip`, `readonly`, `rename`, and `default` field attributes.
+//!
+
+use ffi_common::core::{
+    ffi_string,
+    string::{free_rust_string, string_from_c},
+};
+use std::cell::Cell;
+
+fn initial_revision() -> u32 {
+    1
+}
+
+/// A document with some bookkeeping that isn't exposed to consumers.
+///
+#[derive(Debug, Clone, PartialEq, ffi_common::derive::FFI)]
+pub struct Document {
+    #[ffi(rename = "title")]
+    internal_title: String,
+    #[ffi(readonly, default = "initial_revision")]
+    revision: u32,
+    #[ffi(readonly)]
+    edit_count: u16,
+    #[ffi(skip)]
+    cached_length: Cell<Option<usize>>,
+    #[ffi(skip, default = "initial_revision")]
+    last_saved_revision: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, ffi_common::derive::FFI)]
+pub struct Percentage(#[ffi(rename = "value")] u8, #[ffi(skip)] bool);
+
+#[test]
+fn defaulted_fields_are_filled_in_by_the_initializer() {
+    unsafe {
+        // Only `title` is an argument; `readonly` and `skip` fields are filled in with their
+        // defaults.
+        let ptr = document_ffi::document_rust_ffi_init(ffi_string!("Notes"));
+        assert_eq!(
+            &*ptr,
+            &Document {
+                internal_title: "Notes".to_string(),
+                revision: 1,
+                edit_count: 0,
+                cached_length: Cell::new(None),
+                last_saved_revision: 1,
+            }
+        );
+        document_ffi::document_rust_ffi_free(ptr);
+    }
+}
+
+#[test]
+fn readonly_and_renamed_fields_have_getters() {
+    let document = Document {
+        internal_title: "Notes".to_string(),
+        revision: 4,
+        edit_count: 12,
+        cached_length: Cell::new(Some(5)),
+        last_saved_revision: 3,
+    };
+    unsafe {
+        let title = document_ffi::get_document_title(&document);
+        assert_eq!(string_from_c(title), "Notes");
+        free_rust_string(title);
+        assert_eq!(document_ffi::get_document_revision(&document), 4);
+        assert_eq!(document_ffi::get_document_edit_count(&document), 12);
+    }
+}
+
+#[test]
+fn tuple_struct_fields_can_be_renamed_and_skipped() {
+    unsafe {
+        let ptr = percentage_ffi::percentage_rust_ffi_init(42);
+        assert_eq!(&*ptr, &Percentage(42, false));
+        assert_eq!(percentage_ffi::get_percentage_value(ptr), 42);
+        percentage_ffi::percentage_rust_ffi_free(ptr);
+    }
+}
@@ -0,0 +1,207 @@
+//!
+//! Tests that cover exposing fixed-size arrays through the derived FFI.
+//!
+
+use ffi_common::core::{
+    error::get_last_err_msg,
+    fixed_array::FFIFixedArray,
+    string::{free_rust_string, string_from_c, FFIArrayString},
+    FFIArrayf32, FFIu128,
+};
+
+#[derive(Debug, Clone, PartialEq, ffi_common::derive::FFI)]
+pub struct Beacon {
+    name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, ffi_common::derive::FFI)]
+#[ffi(ffi_mod_imports(beacon_ffi::FFIArrayBeacon))]
+pub struct Transect {
+    origin: [f64; 3],
+    checksums: [u128; 2],
+    labels: [String; 2],
+    beacons: [Beacon; 2],
+    weights: Option<[f32; 4]>,
+}
+
+#[derive(Debug, Clone, PartialEq, ffi_common::derive::FFI)]
+pub enum Heading {
+    Fixed([f64; 2]),
+    Unknown,
+}
+
+#[ffi_common::derive::expose_impl(description("transects"))]
+impl Transect {
+    fn center(&self) -> [f64; 3] {
+        self.origin.map(|coordinate| coordinate / 2.0)
+    }
+
+    fn offset(&self, by: &[f64; 3]) -> Self {
+        let mut origin = self.origin;
+        for (coordinate, offset) in origin.iter_mut().zip(by) {
+            *coordinate += offset;
+        }
+        Self {
+            origin,
+            ..self.clone()
+        }
+    }
+
+    fn with_labels(&self, labels: [String; 2]) -> Self {
+        Self {
+            labels,
+            ..self.clone()
+        }
+    }
+
+    fn checked_weights(&self) -> Result<[f32; 4], String> {
+        self.weights.ok_or_else(|| "No weights".to_string())
+    }
+}
+
+unsafe fn last_error() -> String {
+    let error = get_last_err_msg();
+    let message = string_from_c(error);
+    free_rust_string(error);
+    message
+}
+
+fn beacon(name: &str) -> Beacon {
+    Beacon {
+        name: name.to_string(),
+    }
+}
+
+fn transect() -> Transect {
+    Transect {
+        origin: [1.0, -2.0, 4.5],
+        checksums: [u128::MAX, 7],
+        labels: ["start".to_string(), "end".to_string()],
+        beacons: [beacon("north"), beacon("south")],
+        weights: Some([0.25, 0.5, 0.75, 1.0]),
+    }
+}
+
+#[test]
+fn fixed_array_fields_round_trip() {
+    use beacon_ffi::FFIArrayBeacon;
+    use transect_ffi::*;
+
+    let native = transect();
+    unsafe {
+        let ptr = transect_rust_ffi_init(
+            FFIFixedArray::from(native.origin),
+            FFIFixedArray::from(native.checksums.map(FFIu128::from)),
+            FFIArrayString::from(&native.labels[..]),
+            FFIArrayBeacon::from(&native.beacons[..]),
+            FFIArrayf32::from(native.weights.as_ref().map(|w| &w[..])),
+        );
+        assert_eq!(&*ptr, &native);
+
+        let origin: FFIFixedArray<f64, 3> = get_transect_origin(ptr);
+        assert_eq!(origin.elements, native.origin);
+
+        let checksums = get_transect_checksums(ptr).elements.map(u128::from);
+        assert_eq!(checksums, native.checksums);
+
+        let labels: Vec<String> = get_transect_labels(ptr).into();
+        assert_eq!(labels, native.labels);
+
+        let beacons: Vec<Beacon> = get_transect_beacons(ptr).into();
+        assert_eq!(beacons, native.beacons);
+
+        let weights: Option<Vec<f32>> = get_optional_transect_weights(ptr).into();
+        assert_eq!(weights.as_deref(), native.weights.as_ref().map(|w| &w[..]));
+
+        transect_rust_ffi_free(ptr);
+
+        let unweighted = transect_rust_ffi_init(
+            FFIFixedArray::from(native.origin),
+            FFIFixedArray::from(native.checksums.map(FFIu128::from)),
+            FFIArrayString::from(&native.labels[..]),
+            FFIArrayBeacon::from(&native.beacons[..]),
+            FFIArrayf32::from(None),
+        );
+        assert_eq!((*unweighted).weights, None);
+        assert!(get_optional_transect_weights(unweighted).ptr.is_null());
+        transect_rust_ffi_free(unweighted);
+    }
+}
+
+#[test]
+fn fixed_array_arguments_and_returns() {
+    use transects_transect_ffi::*;
+
+    let ptr = Box::into_raw(Box::new(transect()));
+    unsafe {
+        let center = transects_transect_ffi_center(ptr);
+        assert_eq!(center.elements, [0.5, -1.0, 2.25]);
+
+        let offset = transects_transect_ffi_offset(ptr, FFIFixedArray::from([1.0, 1.0, -0.5]));
+        assert_eq!((*offset).origin, [2.0, -1.0, 4.0]);
+
+        let labels = ["west".to_string(), "east".to_string()];
+        let relabeled = transects_transect_ffi_with_labels(ptr, FFIArrayString::from(&labels[..]));
+        assert_eq!((*relabeled).labels, labels);
+
+        let weights = transects_transect_ffi_checked_weights(ptr);
+        assert_eq!(weights.elements, [0.25, 0.5, 0.75, 1.0]);
+
+        (*ptr).weights = None;
+        let placeholder = transects_transect_ffi_checked_weights(ptr);
+        assert_eq!(placeholder.elements, [0.0; 4]);
+
+        transect_ffi::transect_rust_ffi_free(relabeled);
+        transect_ffi::transect_rust_ffi_free(offset);
+        transect_ffi::transect_rust_ffi_free(ptr);
+    }
+}
+
+#[test]
+fn fixed_array_arguments_of_the_wrong_length_set_error() {
+    use beacon_ffi::FFIArrayBeacon;
+
+    let native = transect();
+    let labels = ["west".to_string(), "middle".to_string(), "east".to_string()];
+    unsafe {
+        let ptr = transect_ffi::transect_rust_ffi_init(
+            FFIFixedArray::from(native.origin),
+            FFIFixedArray::from(native.checksums.map(FFIu128::from)),
+            FFIArrayString::from(&native.labels[..]),
+            FFIArrayBeacon::from(&native.beacons[..]),
+            FFIArrayf32::from(Some(&[0.5, 1.0][..])),
+        );
+        assert!(ptr.is_null());
+        assert_eq!(
+            last_error(),
+            "Expected an array of 4 elements, but received 2 elements."
+        );
+
+        let ptr = Box::into_raw(Box::new(native));
+        let relabeled = transects_transect_ffi::transects_transect_ffi_with_labels(
+            ptr,
+            FFIArrayString::from(&labels[..]),
+        );
+        assert!(relabeled.is_null());
+        assert_eq!(
+            last_error(),
+            "Expected an array of 2 elements, but received 3 elements."
+        );
+        transect_ffi::transect_rust_ffi_free(ptr);
+    }
+}
+
+#[test]
+fn fixed_array_enum_variants() {
+    use heading_ffi::*;
+
+    unsafe {
+        let fixed = heading_fixed_rust_ffi_init(FFIFixedArray::from([45.0, 0.5]));
+        assert_eq!(get_heading_variant(fixed), HeadingType::Fixed);
+        assert_eq!(
+            get_heading_fixed_unnamed_field_0(fixed).elements,
+            [45.0, 0.5]
+        );
+        rust_ffi_free_heading(fixed);
+    }
+}
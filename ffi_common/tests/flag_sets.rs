@@ -0,0 +1,58 @@
+//!
+//! Tests that cover exposing `ffi(flags(...))` bit-flag newtypes, which cross the FFI as the
+//! integer they wrap.
+//!
+
+use ffi_common::core::FFIArrayu32;
+
+/// What a user is allowed to do with a field.
+///
+#[derive(Debug, Clone, Copy, PartialEq, ffi_common::derive::FFI)]
+#[ffi(flags(READ, WRITE, SHARE))]
+#[repr(transparent)]
+pub struct Permissions(u32);
+
+impl Permissions {
+    pub const READ: Self = Self(1);
+    pub const WRITE: Self = Self(1 << 1);
+    pub const SHARE: Self = Self(1 << 2);
+}
+
+#[derive(Debug, Clone, PartialEq, ffi_common::derive::FFI)]
+pub struct Grant {
+    user: String,
+    permissions: Permissions,
+    history: Vec<Permissions>,
+}
+
+#[test]
+fn flag_constants_and_operations() {
+    use permissions_ffi::*;
+
+    assert_eq!(get_permissions_read(), 1);
+    assert_eq!(get_permissions_write(), 2);
+    assert_eq!(get_permissions_share(), 4);
+
+    let read_write = permissions_union(get_permissions_read(), get_permissions_write());
+    assert_eq!(read_write, 3);
+    assert_eq!(permissions_intersection(read_write, 6), 2);
+    assert!(permissions_contains(read_write, get_permissions_write()));
+    assert!(!permissions_contains(read_write, get_permissions_share()));
+}
+
+#[test]
+fn flag_fields_round_trip() {
+    use grant_ffi::*;
+
+    unsafe {
+        let ptr = grant_rust_ffi_init(
+            ffi_common::core::ffi_string!("ada"),
+            5,
+            FFIArrayu32::from(&*vec![1, 3]),
+        );
+        assert_eq!((*ptr).permissions, Permissions(5));
+        assert_eq!(get_grant_permissions(ptr), 5);
+        assert_eq!(Vec::<u32>::from(get_grant_history(ptr)), vec![1, 3]);
+        grant_rust_ffi_free(ptr);
+    }
+}
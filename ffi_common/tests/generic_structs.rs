@@ -0,0 +1,67 @@
+//!
+//! Tests that cover exposing instantiations of generic structs with `ffi(instantiate(...))`.
+//!
+
+use ffi_common::core::{ffi_string, string::string_from_c, FFIArrayUuid, FFIArrayf64, FFIUuid};
+use uuid::Uuid;
+
+/// A value with a unit.
+///
+#[derive(Debug, Clone, PartialEq, ffi_common::derive::FFI)]
+#[ffi(instantiate(Measurement<f64> as MeasurementF64, Measurement<Uuid> as MeasurementId))]
+pub struct Measurement<T> {
+    value: T,
+    unit: String,
+    history: Vec<T>,
+    previous: Option<T>,
+}
+
+#[derive(Debug, Clone, PartialEq, ffi_common::derive::FFI)]
+pub struct Sample {
+    depth: MeasurementF64,
+}
+
+#[test]
+fn each_instantiation_gets_an_ffi() {
+    unsafe {
+        let ptr = measurement_f64_ffi::measurement_f64_rust_ffi_init(
+            2.5,
+            ffi_string!("in"),
+            FFIArrayf64::from(&*vec![1.0, 2.0]),
+            std::ptr::null_mut(),
+        );
+        assert_eq!(
+            &*ptr,
+            &Measurement {
+                value: 2.5,
+                unit: "in".to_string(),
+                history: vec![1.0, 2.0],
+                previous: None,
+            }
+        );
+        assert_eq!(measurement_f64_ffi::get_measurement_f64_value(ptr), 2.5);
+        assert_eq!(
+            string_from_c(measurement_f64_ffi::get_measurement_f64_unit(ptr)),
+            "in"
+        );
+
+        // The sample takes ownership of the measurement.
+        let sample = sample_ffi::sample_rust_ffi_init(ptr.cast_mut());
+        assert_eq!((*sample).depth.value, 2.5);
+        sample_ffi::sample_rust_ffi_free(sample);
+
+        let id = Uuid::new_v4();
+        let ptr = measurement_id_ffi::measurement_id_rust_ffi_init(
+            FFIUuid::from(id),
+            ffi_string!("id"),
+            FFIArrayUuid::from(&*vec![id]),
+            std::ptr::null_mut(),
+        );
+        assert_eq!((*ptr).value, id);
+        assert_eq!(
+            Uuid::from(measurement_id_ffi::get_measurement_id_value(ptr)),
+            id
+        );
+        measurement_id_ffi::measurement_id_rust_ffi_free(ptr);
+    }
+}
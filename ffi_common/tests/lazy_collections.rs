@@ -0,0 +1,103 @@
+//!
+//! Tests that cover accessing the elements of a collection field one at a time, instead of cloning
+//! the whole collection.
+//!
+
+use ffi_common::core::{error::get_last_err_msg, string::string_from_c};
+
+#[derive(Debug, Clone, PartialEq, ffi_common::derive::FFI)]
+pub struct Track {
+    title: String,
+}
+
+#[derive(Debug, Clone, ffi_common::derive::FFI)]
+#[ffi(ffi_mod_imports(track_ffi::FFIArrayTrack))]
+pub struct Playlist {
+    tracks: Vec<Track>,
+}
+
+#[derive(Debug, Clone, ffi_common::derive::FFI)]
+#[ffi(ffi_mod_imports(track_ffi::FFIArrayTrack))]
+pub struct Album {
+    bonus_tracks: Option<Vec<Track>>,
+}
+
+#[test]
+fn elements_are_fetched_individually() {
+    use playlist_ffi::*;
+
+    let tracks = vec![
+        Track {
+            title: "Intro".to_string(),
+        },
+        Track {
+            title: "Outro".to_string(),
+        },
+    ];
+    let ptr = Box::into_raw(Box::new(Playlist {
+        tracks: tracks.clone(),
+    }));
+    unsafe {
+        let playlist = &*ptr;
+        assert_eq!(get_playlist_tracks_count(ptr), 2);
+        for (index, track) in tracks.iter().enumerate() {
+            let element = get_playlist_tracks_at(ptr, index);
+            assert_eq!(&*element, track);
+            // Elements are clones owned by the caller, not references into the playlist.
+            assert_ne!(element, &playlist.tracks[index] as *const Track);
+            track_ffi::track_rust_ffi_free(element);
+        }
+        playlist_rust_ffi_free(ptr);
+    }
+}
+
+#[test]
+fn out_of_bounds_access_sets_error() {
+    use playlist_ffi::*;
+
+    let ptr = Box::into_raw(Box::new(Playlist { tracks: vec![] }));
+    unsafe {
+        assert_eq!(get_playlist_tracks_count(ptr), 0);
+        assert!(get_playlist_tracks_at(ptr, 0).is_null());
+        let error = get_last_err_msg();
+        assert_eq!(
+            string_from_c(error),
+            "Index 0 is out of bounds for `tracks` with 0 elements."
+        );
+        ffi_common::core::string::free_rust_string(error);
+        playlist_rust_ffi_free(ptr);
+    }
+}
+
+#[test]
+fn optional_collections_are_fetched_individually() {
+    use album_ffi::*;
+
+    let track = Track {
+        title: "Demo".to_string(),
+    };
+    let ptr = Box::into_raw(Box::new(Album {
+        bonus_tracks: Some(vec![track.clone()]),
+    }));
+    unsafe {
+        assert_eq!(get_optional_album_bonus_tracks_count(ptr), 1);
+        let element = get_optional_album_bonus_tracks_at(ptr, 0);
+        assert_eq!(&*element, &track);
+        track_ffi::track_rust_ffi_free(element);
+        album_rust_ffi_free(ptr);
+    }
+
+    // A `None` collection is empty.
+    let ptr = Box::into_raw(Box::new(Album { bonus_tracks: None }));
+    unsafe {
+        assert_eq!(get_optional_album_bonus_tracks_count(ptr), 0);
+        assert!(get_optional_album_bonus_tracks_at(ptr, 0).is_null());
+        let error = get_last_err_msg();
+        assert_eq!(
+            string_from_c(error),
+            "Index 0 is out of bounds for `bonus_tracks` with 0 elements."
+        );
+        ffi_common::core::string::free_rust_string(error);
+        album_rust_ffi_free(ptr);
+    }
+}
@@ -0,0 +1,150 @@
+//!
+//! Tests that cover exposing maps and sets through the derived FFI.
+//!
+
+use ffi_common::core::{collections::FFIMap, string::FFIArrayString, FFIArrayf64, FFIArrayu32};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+#[derive(Debug, Clone, PartialEq, ffi_common::derive::FFI)]
+pub struct FieldRecord {
+    attributes: HashMap<String, String>,
+    acreage_by_crop: BTreeMap<String, f64>,
+    tags: HashSet<String>,
+    zones: Option<BTreeSet<u32>>,
+}
+
+#[ffi_common::derive::expose_impl(description("lookups"))]
+impl FieldRecord {
+    fn crops(&self) -> BTreeSet<String> {
+        self.acreage_by_crop.keys().cloned().collect()
+    }
+
+    fn has_tags(&self, tags: &HashSet<String>) -> bool {
+        tags.is_subset(&self.tags)
+    }
+
+    fn with_attributes(&self, attributes: HashMap<String, String>) -> Self {
+        Self {
+            attributes,
+            ..self.clone()
+        }
+    }
+
+    fn planted_acreage(&self) -> Result<BTreeMap<String, f64>, String> {
+        if self.acreage_by_crop.is_empty() {
+            Err("Nothing has been planted".to_string())
+        } else {
+            Ok(self.acreage_by_crop.clone())
+        }
+    }
+}
+
+fn field_record() -> FieldRecord {
+    FieldRecord {
+        attributes: vec![
+            ("soil".to_string(), "loam".to_string()),
+            ("irrigation".to_string(), "pivot".to_string()),
+        ]
+        .into_iter()
+        .collect(),
+        acreage_by_crop: vec![("corn".to_string(), 80.5), ("soybeans".to_string(), 40.0)]
+            .into_iter()
+            .collect(),
+        tags: vec!["organic".to_string(), "leased".to_string()]
+            .into_iter()
+            .collect(),
+        zones: Some(vec![3, 1, 2].into_iter().collect()),
+    }
+}
+
+fn strings(values: &[&str]) -> HashSet<String> {
+    values.iter().map(|s| (*s).to_string()).collect()
+}
+
+#[test]
+fn map_and_set_fields_round_trip() {
+    use field_record_ffi::*;
+
+    let native = field_record();
+    let tags: Vec<String> = native.tags.iter().cloned().collect();
+    let zones: Vec<u32> = native.zones.iter().flatten().copied().collect();
+    unsafe {
+        let ptr = field_record_rust_ffi_init(
+            (&native.attributes).into(),
+            (&native.acreage_by_crop).into(),
+            (&*tags).into(),
+            (&*zones).into(),
+        );
+        assert_eq!(&*ptr, &native);
+
+        let attributes: FFIMap<FFIArrayString, FFIArrayString> = get_field_record_attributes(ptr);
+        assert_eq!(attributes.keys.len, 2);
+        assert_eq!(
+            HashMap::<String, String>::from(attributes),
+            native.attributes
+        );
+
+        let acreage: FFIMap<FFIArrayString, FFIArrayf64> = get_field_record_acreage_by_crop(ptr);
+        let keys: Vec<String> = acreage.keys.into();
+        let values: Vec<f64> = acreage.values.into();
+        assert_eq!(keys, vec!["corn".to_string(), "soybeans".to_string()]);
+        assert_eq!(values, vec![80.5, 40.0]);
+
+        let tags: Vec<String> = get_field_record_tags(ptr).into();
+        assert_eq!(tags.into_iter().collect::<HashSet<_>>(), native.tags);
+
+        let zones: Option<Vec<u32>> = get_optional_field_record_zones(ptr).into();
+        assert_eq!(zones, Some(vec![1, 2, 3]));
+
+        field_record_rust_ffi_free(ptr);
+
+        let none = field_record_rust_ffi_init(
+            (&HashMap::<String, String>::new()).into(),
+            (&BTreeMap::<String, f64>::new()).into(),
+            (&*Vec::<String>::new()).into(),
+            FFIArrayu32::from(None),
+        );
+        assert_eq!((*none).zones, None);
+        assert!(get_optional_field_record_zones(none).ptr.is_null());
+        field_record_rust_ffi_free(none);
+    }
+}
+
+#[test]
+fn map_and_set_arguments_and_returns() {
+    use lookups_field_record_ffi::*;
+
+    let ptr = Box::into_raw(Box::new(field_record()));
+    unsafe {
+        let crops: Vec<String> = lookups_field_record_ffi_crops(ptr).into();
+        assert_eq!(crops, vec!["corn".to_string(), "soybeans".to_string()]);
+
+        let tags = vec!["organic".to_string()];
+        assert!(lookups_field_record_ffi_has_tags(ptr, (&*tags).into()));
+        let tags = vec!["organic".to_string(), "irrigated".to_string()];
+        assert!(!lookups_field_record_ffi_has_tags(ptr, (&*tags).into()));
+
+        let attributes: HashMap<String, String> =
+            vec![("drainage".to_string(), "tiled".to_string())]
+                .into_iter()
+                .collect();
+        let updated = lookups_field_record_ffi_with_attributes(ptr, (&attributes).into());
+        assert_eq!((*updated).attributes, attributes);
+        assert_eq!((*updated).tags, strings(&["organic", "leased"]));
+
+        let acreage: BTreeMap<String, f64> = lookups_field_record_ffi_planted_acreage(ptr).into();
+        assert_eq!(acreage, (*ptr).acreage_by_crop);
+
+        let unplanted = Box::into_raw(Box::new(FieldRecord {
+            acreage_by_crop: BTreeMap::new(),
+            ..field_record()
+        }));
+        let acreage: BTreeMap<String, f64> =
+            lookups_field_record_ffi_planted_acreage(unplanted).into();
+        assert!(acreage.is_empty());
+        field_record_ffi::field_record_rust_ffi_free(unplanted);
+
+        field_record_ffi::field_record_rust_ffi_free(updated);
+        field_record_ffi::field_record_rust_ffi_free(ptr);
+    }
+}
@@ -8,7 +8,6 @@
 //!
 
 use ffi_common::core;
-use std::os::raw::c_char;
 use uuid::Uuid;
 
 #[derive(Debug, Clone, ffi_common::derive::FFI)]
@@ -26,12 +25,12 @@ pub struct NestedStruct {
 fn check_uuid_vec_init_and_free() {
     unsafe {
         let v = vec![Uuid::new_v4(), Uuid::new_v4()];
-        let string_array = core::string::FFIArrayString::from(&*v);
-        let unsafe_ptr = string_array.ptr as *mut *mut c_char;
+        let uuid_array = core::FFIArrayUuid::from(&*v);
+        let unsafe_ptr = uuid_array.ptr;
         let original_pointee = *unsafe_ptr;
 
         assert_eq!(*unsafe_ptr, original_pointee);
-        let uuid_struct = uuid_struct_ffi::uuid_struct_rust_ffi_init(string_array);
+        let uuid_struct = uuid_struct_ffi::uuid_struct_rust_ffi_init(uuid_array);
         uuid_struct_ffi::uuid_struct_rust_ffi_free(uuid_struct);
     }
 }
@@ -55,3 +54,30 @@ fn check_struct_vec_init_and_free() {
         nested_struct_ffi::nested_struct_rust_ffi_free(outer_struct);
     }
 }
+
+#[test]
+fn check_consuming_struct_vec_init_moves_elements() {
+    unsafe {
+        let ids = vec![Uuid::new_v4()];
+        let elements = vec![
+            uuid_struct_ffi::uuid_struct_rust_ffi_init((&*ids).into()),
+            uuid_struct_ffi::uuid_struct_rust_ffi_init((&*ids).into()),
+        ];
+        let array = uuid_struct_ffi::ffi_array_UuidStruct_init_consuming(elements.as_ptr(), 2);
+        // The array holds the same instances that were passed in, rather than copies of them.
+        assert_ne!(array.ptr, elements.as_ptr());
+        assert_eq!(std::slice::from_raw_parts(array.ptr, array.len), &*elements);
+
+        let outer_struct = nested_struct_ffi::nested_struct_rust_ffi_init(array);
+        nested_struct_ffi::nested_struct_rust_ffi_free(outer_struct);
+    }
+}
+
+#[test]
+fn check_consuming_vec_init_with_null() {
+    unsafe {
+        let array = uuid_struct_ffi::ffi_array_UuidStruct_init_consuming(std::ptr::null(), 0);
+        assert!(array.ptr.is_null());
+        assert_eq!(array.len, 0);
+    }
+}
@@ -0,0 +1,90 @@
+//!
+//! Tests that cover exposing enum variants with several unnamed fields, or with named fields.
+//!
+
+use ffi_common::core::{ffi_string, string::string_from_c, FFIArrayf64};
+
+#[derive(Debug, Clone, PartialEq, ffi_common::derive::FFI)]
+pub struct Marker {
+    name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, ffi_common::derive::FFI)]
+pub enum Shape {
+    Point,
+    Circle(f64),
+    Segment(f64, f64, Option<String>),
+    Rect {
+        width: f64,
+        height: f64,
+        marker: Marker,
+    },
+    Polygon {
+        label: String,
+        vertices: Vec<f64>,
+    },
+}
+
+#[test]
+fn unnamed_fields_round_trip() {
+    use shape_ffi::*;
+
+    unsafe {
+        let segment = shape_segment_rust_ffi_init(1.5, 4.0, ffi_string!("edge").cast_mut());
+        assert_eq!(get_shape_variant(segment), ShapeType::Segment);
+        assert_eq!(
+            &*segment,
+            &Shape::Segment(1.5, 4.0, Some("edge".to_string()))
+        );
+        assert_eq!(get_shape_segment_unnamed_field_0(segment), 1.5);
+        assert_eq!(get_shape_segment_unnamed_field_1(segment), 4.0);
+        assert_eq!(
+            string_from_c(get_optional_shape_segment_unnamed_field_2(segment)),
+            "edge"
+        );
+        rust_ffi_free_shape(segment);
+
+        let circle = shape_circle_rust_ffi_init(2.0);
+        assert_eq!(get_shape_circle_unnamed_field_0(circle), 2.0);
+        rust_ffi_free_shape(circle);
+    }
+}
+
+#[test]
+fn named_fields_round_trip() {
+    use shape_ffi::*;
+
+    unsafe {
+        let marker = marker_ffi::marker_rust_ffi_init(ffi_string!("corner"));
+        let rect = shape_rect_rust_ffi_init(3.0, 2.0, marker.cast_mut());
+        assert_eq!(get_shape_variant(rect), ShapeType::Rect);
+        assert_eq!(get_shape_rect_width(rect), 3.0);
+        assert_eq!(get_shape_rect_height(rect), 2.0);
+        let marker = get_shape_rect_marker(rect);
+        assert_eq!(string_from_c(marker_ffi::get_marker_name(marker)), "corner");
+        marker_ffi::marker_rust_ffi_free(marker);
+        rust_ffi_free_shape(rect);
+
+        let polygon = shape_polygon_rust_ffi_init(
+            ffi_string!("triangle"),
+            FFIArrayf64::from(&*vec![0.0, 1.0, 2.0]),
+        );
+        assert_eq!(
+            &*polygon,
+            &Shape::Polygon {
+                label: "triangle".to_string(),
+                vertices: vec![0.0, 1.0, 2.0],
+            }
+        );
+        assert_eq!(string_from_c(get_shape_polygon_label(polygon)), "triangle");
+        assert_eq!(
+            Vec::<f64>::from(get_shape_polygon_vertices(polygon)),
+            vec![0.0, 1.0, 2.0]
+        );
+        rust_ffi_free_shape(polygon);
+
+        let point = shape_point_rust_ffi_init();
+        assert_eq!(get_shape_variant(point), ShapeType::Point);
+        rust_ffi_free_shape(point);
+    }
+}
@@ -0,0 +1,160 @@
+//!
+//! Tests that cover exposing nested `Vec`s and `Option`s through the derived FFI.
+//!
+
+use ffi_common::core::{
+    nested::{FFINestedArray, FFINestedOption},
+    string::FFIArrayString,
+    FFIArrayf64,
+};
+
+#[derive(Debug, Clone, PartialEq, ffi_common::derive::FFI)]
+pub struct Survey {
+    samples: Vec<Vec<f64>>,
+    readings: Vec<Option<f64>>,
+    note: Option<Option<String>>,
+    labels: Option<Vec<Vec<String>>>,
+}
+
+#[ffi_common::derive::expose_impl(
+    description("grids"),
+    ffi_imports(ffi_common::core::option_f64_init)
+)]
+impl Survey {
+    fn sample_totals(&self) -> Vec<Option<f64>> {
+        self.samples
+            .iter()
+            .map(|row| {
+                if row.is_empty() {
+                    None
+                } else {
+                    Some(row.iter().sum())
+                }
+            })
+            .collect()
+    }
+
+    fn with_samples(&self, samples: Vec<Vec<f64>>) -> Self {
+        Self {
+            samples,
+            ..self.clone()
+        }
+    }
+
+    fn count_labels(&self, labels: &[Vec<String>]) -> u32 {
+        labels.iter().map(Vec::len).sum::<usize>() as u32
+    }
+}
+
+fn survey() -> Survey {
+    Survey {
+        samples: vec![vec![1.0, 2.5], vec![], vec![4.0]],
+        readings: vec![Some(0.5), None, Some(1.5)],
+        note: Some(None),
+        labels: Some(vec![vec!["north".to_string()], vec![]]),
+    }
+}
+
+fn samples_into_ffi(samples: &[Vec<f64>]) -> FFINestedArray<FFIArrayf64> {
+    samples
+        .iter()
+        .map(|row| FFIArrayf64::from(&**row))
+        .collect::<Vec<_>>()
+        .into()
+}
+
+fn samples_from_ffi(samples: FFINestedArray<FFIArrayf64>) -> Vec<Vec<f64>> {
+    Vec::from(samples).into_iter().map(Vec::from).collect()
+}
+
+#[test]
+fn nested_fields_round_trip() {
+    use survey_ffi::*;
+
+    let native = survey();
+    unsafe {
+        let readings: Vec<*const f64> = native
+            .readings
+            .iter()
+            .map(|r| r.map_or(std::ptr::null(), |r| Box::into_raw(Box::new(r))))
+            .collect();
+        let labels: FFINestedArray<FFIArrayString> = native
+            .labels
+            .as_ref()
+            .map(|labels| {
+                labels
+                    .iter()
+                    .map(|l| FFIArrayString::from(&**l))
+                    .collect::<Vec<_>>()
+            })
+            .into();
+        let ptr = survey_rust_ffi_init(
+            samples_into_ffi(&native.samples),
+            readings.into(),
+            FFINestedOption::some(std::ptr::null()),
+            labels,
+        );
+        assert_eq!(&*ptr, &native);
+
+        assert_eq!(samples_from_ffi(get_survey_samples(ptr)), native.samples);
+
+        let readings: Vec<Option<f64>> = Vec::from(get_survey_readings(ptr))
+            .into_iter()
+            .map(|r| (!r.is_null()).then(|| *Box::from_raw(r as *mut f64)))
+            .collect();
+        assert_eq!(readings, native.readings);
+
+        let note = get_optional_survey_note(ptr);
+        assert!(note.is_some);
+        assert!(note.value.is_null());
+
+        let labels: Option<Vec<FFIArrayString>> = get_optional_survey_labels(ptr).into();
+        let labels: Option<Vec<Vec<String>>> =
+            labels.map(|labels| labels.into_iter().map(Vec::from).collect());
+        assert_eq!(labels, native.labels);
+
+        survey_rust_ffi_free(ptr);
+
+        let empty = survey_rust_ffi_init(
+            FFINestedArray::default(),
+            FFINestedArray::default(),
+            FFINestedOption::none(std::ptr::null()),
+            FFINestedArray::default(),
+        );
+        assert!((*empty).samples.is_empty());
+        assert_eq!((*empty).note, None);
+        assert_eq!((*empty).labels, None);
+        assert!(!get_optional_survey_note(empty).is_some);
+        assert!(get_optional_survey_labels(empty).ptr.is_null());
+        survey_rust_ffi_free(empty);
+    }
+}
+
+#[test]
+fn nested_arguments_and_returns() {
+    use grids_survey_ffi::*;
+
+    let ptr = Box::into_raw(Box::new(survey()));
+    unsafe {
+        let totals: Vec<Option<f64>> = Vec::from(grids_survey_ffi_sample_totals(ptr))
+            .into_iter()
+            .map(|t| (!t.is_null()).then(|| *Box::from_raw(t as *mut f64)))
+            .collect();
+        assert_eq!(totals, vec![Some(3.5), None, Some(4.0)]);
+
+        let samples = vec![vec![9.0], vec![8.0, 7.0]];
+        let updated = grids_survey_ffi_with_samples(ptr, samples_into_ffi(&samples));
+        assert_eq!((*updated).samples, samples);
+        assert_eq!((*updated).readings, (*ptr).readings);
+
+        let labels: FFINestedArray<FFIArrayString> = vec![
+            FFIArrayString::from(&*vec!["a".to_string(), "b".to_string()]),
+            FFIArrayString::from(&*vec!["c".to_string()]),
+        ]
+        .into();
+        assert_eq!(grids_survey_ffi_count_labels(ptr, labels), 3);
+
+        survey_ffi::survey_rust_ffi_free(updated);
+        survey_ffi::survey_rust_ffi_free(ptr);
+    }
+}
@@ -0,0 +1,128 @@
+//!
+//! Tests that cover exposing `ffi(shared)` types, whose instances are allocated in an `Arc`.
+//!
+
+use ffi_common::core::{ffi_string, string::string_from_c};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, PartialEq, ffi_common::derive::FFI)]
+#[ffi(shared)]
+pub struct Model {
+    name: String,
+    weights: Vec<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, ffi_common::derive::FFI)]
+#[ffi(ffi_mod_imports(model_ffi::FFIArrayModel))]
+pub struct Scene {
+    model: Arc<Model>,
+    fallback: Option<Arc<Model>>,
+    history: Vec<Arc<Model>>,
+}
+
+#[ffi_common::derive::expose_impl(description("scenes"))]
+impl Scene {
+    fn current_model(&self) -> Arc<Model> {
+        Arc::clone(&self.model)
+    }
+
+    fn replacing_model(&self, model: Arc<Model>) -> Self {
+        Self {
+            model,
+            ..self.clone()
+        }
+    }
+
+    fn is_current(&self, model: &Arc<Model>) -> bool {
+        Arc::ptr_eq(&self.model, model)
+    }
+}
+
+fn model(name: &str) -> Arc<Model> {
+    Arc::new(Model {
+        name: name.to_string(),
+        weights: vec![0.5, 1.5],
+    })
+}
+
+#[test]
+fn shared_instances_are_retained_and_released() {
+    use model_ffi::*;
+
+    unsafe {
+        let ptr = model_rust_ffi_init(ffi_string!("base"), vec![1.0].into());
+        assert_eq!(string_from_c(get_model_name(ptr)), "base");
+
+        let retained = model_rust_ffi_retain(ptr);
+        assert_eq!(retained, ptr);
+        let cloned = rust_ffi_clone_model(ptr);
+        assert_eq!(cloned, ptr);
+
+        let native = <Model as ffi_common::core::handle::OpaqueHandle>::arc_from_handle(cloned);
+        assert_eq!(Arc::strong_count(&native), 3);
+        model_rust_ffi_release(retained);
+        model_rust_ffi_free(ptr);
+        assert_eq!(Arc::strong_count(&native), 1);
+        assert_eq!(native.weights, vec![1.0]);
+    }
+}
+
+#[test]
+fn shared_fields_share_their_instance() {
+    use scene_ffi::*;
+
+    let current = model("current");
+    let scene = Arc::new(Scene {
+        model: Arc::clone(&current),
+        fallback: Some(model("fallback")),
+        history: vec![Arc::clone(&current)],
+    });
+    let ptr = Arc::as_ptr(&scene);
+    unsafe {
+        let field = get_scene_model(ptr);
+        assert_eq!(field, Arc::as_ptr(&current));
+        assert_eq!(Arc::strong_count(&current), 4);
+
+        let fallback = get_optional_scene_fallback(ptr);
+        assert_eq!(fallback, Arc::as_ptr(scene.fallback.as_ref().unwrap()));
+
+        let history: Vec<Model> = get_scene_history(ptr).into();
+        assert_eq!(history, vec![Model::clone(&current)]);
+        // Converting the array into a `Vec<Model>` took ownership of a reference, which had to
+        // clone the still-shared instance.
+        assert_eq!(Arc::strong_count(&current), 4);
+
+        model_ffi::model_rust_ffi_release(fallback);
+        model_ffi::model_rust_ffi_release(field);
+        assert_eq!(Arc::strong_count(&current), 3);
+    }
+}
+
+#[test]
+fn shared_arguments_and_returns() {
+    use scenes_scene_ffi::*;
+
+    let current = model("current");
+    let ptr = Box::into_raw(Box::new(Scene {
+        model: Arc::clone(&current),
+        fallback: None,
+        history: Vec::new(),
+    }));
+    unsafe {
+        let returned = scenes_scene_ffi_current_model(ptr);
+        assert_eq!(returned, Arc::as_ptr(&current));
+        assert!(scenes_scene_ffi_is_current(ptr, returned as *mut Model));
+
+        let next = model("next");
+        let argument = Arc::into_raw(Arc::clone(&next));
+        let replaced = scenes_scene_ffi_replacing_model(ptr, argument as *mut Model);
+        assert!(Arc::ptr_eq(&(*replaced).model, &next));
+        assert!(!scenes_scene_ffi_is_current(ptr, argument as *mut Model));
+
+        scene_ffi::scene_rust_ffi_free(replaced);
+        assert_eq!(Arc::strong_count(&next), 1);
+        model_ffi::model_rust_ffi_release(returned);
+        scene_ffi::scene_rust_ffi_free(ptr);
+        assert_eq!(Arc::strong_count(&current), 1);
+    }
+}
@@ -0,0 +1,191 @@
+//!
+//! Tests that cover exposing smart pointers (including recursive types) through the derived FFI.
+//!
+
+use ffi_common::core::{ffi_string, string::string_from_c};
+use std::{rc::Rc, sync::Arc};
+
+#[derive(Debug, Clone, PartialEq, ffi_common::derive::FFI)]
+pub struct Crop {
+    name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, ffi_common::derive::FFI)]
+pub struct Zone {
+    name: Arc<str>,
+    crop: Arc<Crop>,
+    note: Option<Rc<str>>,
+    sub_zone: Option<Box<Zone>>,
+    children: Vec<Arc<Self>>,
+}
+
+#[derive(Debug, Clone, PartialEq, ffi_common::derive::FFI)]
+pub enum Expression {
+    Constant(f64),
+    Variable(Arc<str>),
+    Negate(Box<Expression>),
+    Square(Box<Self>),
+    Grouped(Option<Box<Self>>),
+}
+
+#[ffi_common::derive::expose_impl(description("zones"))]
+impl Zone {
+    fn leaf(name: Arc<str>, crop: &Arc<Crop>) -> Box<Self> {
+        Box::new(Self {
+            name,
+            crop: Arc::clone(crop),
+            note: None,
+            sub_zone: None,
+            children: Vec::new(),
+        })
+    }
+
+    fn adopting(&self, child: Box<Zone>) -> Self {
+        let mut zone = self.clone();
+        zone.children.push(child.into());
+        zone
+    }
+
+    fn first_child(&self) -> Option<Arc<Zone>> {
+        self.children.first().cloned()
+    }
+
+    fn shared_crop(&self) -> Arc<Crop> {
+        Arc::clone(&self.crop)
+    }
+
+    fn checked_sub_zone(&self) -> Result<Box<Zone>, String> {
+        self.sub_zone
+            .clone()
+            .ok_or_else(|| "No sub-zone".to_string())
+    }
+}
+
+fn crop(name: &str) -> Arc<Crop> {
+    Arc::new(Crop {
+        name: name.to_string(),
+    })
+}
+
+fn zone(name: &str) -> Zone {
+    Zone {
+        name: name.into(),
+        crop: crop("wheat"),
+        note: Some("irrigated".into()),
+        sub_zone: Some(Zone::leaf("corner".into(), &crop("barley"))),
+        children: vec![Zone::leaf("north".into(), &crop("oats")).into()],
+    }
+}
+
+#[test]
+fn smart_pointer_fields_round_trip() {
+    use zone_ffi::*;
+
+    let native = zone("field");
+    unsafe {
+        let ptr = zone_rust_ffi_init(
+            ffi_string!("field"),
+            Box::into_raw(Box::new(Crop::clone(&native.crop))),
+            ffi_string!("irrigated"),
+            Box::into_raw(Box::new(Zone::clone(native.sub_zone.as_ref().unwrap()))),
+            FFIArrayZone::from(&[Zone::clone(&native.children[0])][..]),
+        );
+        assert_eq!(&*ptr, &native);
+
+        assert_eq!(string_from_c(get_zone_name(ptr)), "field");
+        assert_eq!(*get_zone_crop(ptr), *native.crop);
+        assert_eq!(string_from_c(get_optional_zone_note(ptr)), "irrigated");
+
+        let sub_zone = get_optional_zone_sub_zone(ptr);
+        assert_eq!(&*sub_zone, native.sub_zone.as_deref().unwrap());
+        assert!(get_optional_zone_sub_zone(sub_zone).is_null());
+
+        let children: Vec<Zone> = get_zone_children(ptr).into();
+        assert_eq!(children, vec![Zone::clone(&native.children[0])]);
+
+        zone_rust_ffi_free(sub_zone as *mut Zone);
+        zone_rust_ffi_free(ptr);
+    }
+}
+
+#[test]
+fn smart_pointer_arguments_and_returns() {
+    use zones_zone_ffi::*;
+
+    let ptr = Box::into_raw(Box::new(zone("field")));
+    unsafe {
+        let crop = Box::into_raw(Box::new(Crop {
+            name: "rye".to_string(),
+        }));
+        let leaf = zones_zone_ffi_leaf(ffi_string!("south"), crop);
+        assert_eq!(&*(*leaf).name, "south");
+        assert_eq!((&*leaf).crop.name, "rye");
+
+        let adopting = zones_zone_ffi_adopting(ptr, leaf as *mut Zone);
+        assert_eq!((&*adopting).children.len(), 2);
+        assert_eq!(&*(&*adopting).children[1].name, "south");
+
+        let first_child = zones_zone_ffi_first_child(ptr);
+        assert_eq!(&*(*first_child).name, "north");
+
+        let shared_crop = zones_zone_ffi_shared_crop(ptr);
+        assert_eq!((*shared_crop).name, "wheat");
+
+        let sub_zone = zones_zone_ffi_checked_sub_zone(ptr);
+        assert_eq!(&*(*sub_zone).name, "corner");
+
+        (*ptr).sub_zone = None;
+        assert!(zones_zone_ffi_checked_sub_zone(ptr).is_null());
+
+        zone_ffi::zone_rust_ffi_free(sub_zone as *mut Zone);
+        crop_ffi::crop_rust_ffi_free(shared_crop as *mut Crop);
+        crop_ffi::crop_rust_ffi_free(crop);
+        zone_ffi::zone_rust_ffi_free(first_child as *mut Zone);
+        zone_ffi::zone_rust_ffi_free(adopting);
+        zone_ffi::zone_rust_ffi_free(ptr);
+    }
+}
+
+#[test]
+fn recursive_enum_variants() {
+    use expression_ffi::*;
+
+    unsafe {
+        let x = expression_variable_rust_ffi_init(ffi_string!("x"));
+        let square = expression_square_rust_ffi_init(x as *mut Expression);
+        let negated =
+            expression_negate_rust_ffi_init(Box::into_raw(Box::new(Expression::clone(&*square))));
+        assert_eq!(
+            *negated,
+            Expression::Negate(Box::new(Expression::Square(Box::new(
+                Expression::Variable("x".into())
+            ))))
+        );
+
+        assert_eq!(get_expression_variant(negated), ExpressionType::Negate);
+        let inner = get_expression_negate_unnamed_field_0(negated);
+        assert_eq!(&*inner, &*square);
+        let variable = get_expression_square_unnamed_field_0(inner);
+        assert_eq!(
+            string_from_c(get_expression_variable_unnamed_field_0(variable)),
+            "x"
+        );
+
+        let missing = expression_grouped_rust_ffi_init(std::ptr::null_mut());
+        assert_eq!(*missing, Expression::Grouped(None));
+        assert!(get_optional_expression_grouped_unnamed_field_0(missing).is_null());
+        let two = expression_constant_rust_ffi_init(2.0);
+        let present = expression_grouped_rust_ffi_init(two as *mut Expression);
+        assert_eq!(
+            *get_optional_expression_grouped_unnamed_field_0(present),
+            Expression::Constant(2.0)
+        );
+
+        rust_ffi_free_expression(variable as *mut Expression);
+        rust_ffi_free_expression(inner as *mut Expression);
+        rust_ffi_free_expression(negated);
+        rust_ffi_free_expression(square);
+        rust_ffi_free_expression(missing);
+        rust_ffi_free_expression(present);
+    }
+}
@@ -0,0 +1,75 @@
+//!
+//! Tests that cover reading every field of a struct in a single call with the `snapshot`
+//! attribute.
+//!
+
+use ffi_common::core::string::string_from_c;
+
+#[derive(Debug, Clone, PartialEq, ffi_common::derive::FFI)]
+pub struct Bin {
+    label: String,
+}
+
+#[derive(Debug, Clone, ffi_common::derive::FFI)]
+#[ffi(snapshot)]
+pub struct Inventory {
+    name: String,
+    count: Option<u16>,
+    values: Vec<i32>,
+    bin: Bin,
+    nickname: Option<String>,
+}
+
+#[test]
+fn snapshot_contains_every_field() {
+    use inventory_ffi::*;
+
+    let inventory = Inventory {
+        name: "Widgets".to_string(),
+        count: Some(42),
+        values: vec![1, 2, 3],
+        bin: Bin {
+            label: "A1".to_string(),
+        },
+        nickname: None,
+    };
+    let ptr = Box::into_raw(Box::new(inventory.clone()));
+    unsafe {
+        let snapshot: InventorySnapshot = get_inventory_snapshot(ptr);
+        assert_eq!(string_from_c(snapshot.name), inventory.name);
+        assert_eq!(*snapshot.count, 42);
+        assert_eq!(
+            std::slice::from_raw_parts(snapshot.values.ptr, snapshot.values.len),
+            &*inventory.values
+        );
+        assert_eq!(&*snapshot.bin, &inventory.bin);
+        assert!(snapshot.nickname.is_null());
+
+        free_inventory_snapshot(snapshot);
+        inventory_rust_ffi_free(ptr);
+    }
+}
+
+#[test]
+fn snapshot_fields_are_owned_copies() {
+    use inventory_ffi::*;
+
+    let ptr = Box::into_raw(Box::new(Inventory {
+        name: "Gadgets".to_string(),
+        count: None,
+        values: vec![],
+        bin: Bin {
+            label: "B2".to_string(),
+        },
+        nickname: Some("gizmos".to_string()),
+    }));
+    unsafe {
+        let snapshot = get_inventory_snapshot(ptr);
+        // Freeing the original doesn't invalidate the snapshot.
+        inventory_rust_ffi_free(ptr);
+        assert!(snapshot.count.is_null());
+        assert_eq!(string_from_c(snapshot.nickname), "gizmos");
+        assert_eq!((*snapshot.bin).label, "B2");
+        free_inventory_snapshot(snapshot);
+    }
+}
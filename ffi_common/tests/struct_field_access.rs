@@ -63,7 +63,7 @@ fn test_struct_ffi() {
             input_i32_vec.len().try_into().unwrap(),
         );
         let ffi_string_array = FFIArrayString::from(&*input_string_vec);
-        let ffi_uuid_array = FFIArrayString::from(&*input_uuid_vec);
+        let ffi_uuid_array = FFIArrayUuid::from(&*input_uuid_vec);
         let ffi_variant_array = test_enum_ffi::ffi_array_TestEnum_init(
             input_variant_vec.as_ptr(),
             input_variant_vec.len().try_into().unwrap(),
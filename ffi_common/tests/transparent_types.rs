@@ -0,0 +1,100 @@
+//!
+//! Tests that cover exposing `ffi(transparent)` newtypes, which cross the FFI as the type they wrap.
+//!
+
+use ffi_common::core::{
+    allocator::export_box, ffi_string, option_f64_free, string::string_from_c, FFIArrayf64,
+};
+
+/// An area, in acres.
+///
+#[derive(Debug, Clone, Copy, PartialEq, ffi_common::derive::FFI)]
+#[ffi(transparent)]
+pub struct Acres(f64);
+
+#[derive(Debug, Clone, PartialEq, ffi_common::derive::FFI)]
+#[ffi(transparent)]
+pub struct Label {
+    value: String,
+}
+
+#[derive(Debug, Clone, PartialEq, ffi_common::derive::FFI)]
+pub struct Field {
+    label: Label,
+    area: Acres,
+    planted: Option<Acres>,
+    zones: Vec<Acres>,
+}
+
+#[ffi_common::derive::expose_impl(description("areas"))]
+impl Field {
+    fn total_area(&self) -> Acres {
+        Acres(self.zones.iter().map(|zone| zone.0).sum())
+    }
+
+    fn with_planted(&self, planted: Option<Acres>) -> Self {
+        Self {
+            planted,
+            ..self.clone()
+        }
+    }
+
+    fn is_larger_than(&self, area: &Acres) -> bool {
+        self.area.0 > area.0
+    }
+}
+
+fn field() -> Field {
+    Field {
+        label: Label {
+            value: "north".to_string(),
+        },
+        area: Acres(12.5),
+        planted: Some(Acres(10.0)),
+        zones: vec![Acres(4.0), Acres(8.5)],
+    }
+}
+
+#[test]
+fn transparent_fields_round_trip() {
+    use field_ffi::*;
+
+    let native = field();
+    unsafe {
+        let ptr = field_rust_ffi_init(
+            ffi_string!("north"),
+            12.5,
+            export_box(10.0).cast_mut(),
+            FFIArrayf64::from(&*vec![4.0, 8.5]),
+        );
+        assert_eq!(&*ptr, &native);
+
+        assert_eq!(string_from_c(get_field_label(ptr)), "north");
+        assert_eq!(get_field_area(ptr), 12.5);
+        let planted = get_optional_field_planted(ptr);
+        assert_eq!(*planted, 10.0);
+        option_f64_free(planted);
+        assert_eq!(Vec::<f64>::from(get_field_zones(ptr)), vec![4.0, 8.5]);
+
+        field_rust_ffi_free(ptr);
+    }
+}
+
+#[test]
+fn transparent_arguments_and_returns() {
+    use areas_field_ffi::*;
+
+    let ptr = Box::into_raw(Box::new(field()));
+    unsafe {
+        assert_eq!(areas_field_ffi_total_area(ptr), 12.5);
+        assert!(areas_field_ffi_is_larger_than(ptr, 12.0));
+        assert!(!areas_field_ffi_is_larger_than(ptr, 13.0));
+
+        let unplanted = areas_field_ffi_with_planted(ptr, std::ptr::null_mut());
+        assert_eq!((*unplanted).planted, None);
+        assert_eq!((*unplanted).zones, (*ptr).zones);
+
+        field_ffi::field_rust_ffi_free(unplanted);
+        field_ffi::field_rust_ffi_free(ptr);
+    }
+}
@@ -0,0 +1,167 @@
+//!
+//! Tests that cover exposing tuples through the derived FFI.
+//!
+
+use ffi_common::core::{
+    ffi_string,
+    nested::FFINestedArray,
+    string::{free_rust_string, string_from_c},
+    tuple::{FFITuple2, FFITuple3},
+};
+
+#[derive(Debug, Clone, PartialEq, ffi_common::derive::FFI)]
+pub struct Marker {
+    name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, ffi_common::derive::FFI)]
+pub struct Plot {
+    bounds: (f64, f64),
+    label: (String, u32, bool),
+    corners: Vec<(f64, f64)>,
+    marker: Option<(Marker, Option<u32>)>,
+}
+
+#[derive(Debug, Clone, PartialEq, ffi_common::derive::FFI)]
+pub enum Extent {
+    Range((f64, f64)),
+    Unbounded,
+}
+
+#[ffi_common::derive::expose_impl(description("geometry"))]
+impl Plot {
+    fn width_and_height(&self) -> (f64, f64) {
+        let (min, max) = self.bounds;
+        (max - min, max - min)
+    }
+
+    fn with_label(&self, label: (String, u32, bool)) -> Self {
+        Self {
+            label,
+            ..self.clone()
+        }
+    }
+
+    fn contains(&self, point: &(f64, f64)) -> bool {
+        let (min, max) = self.bounds;
+        (min..=max).contains(&point.0) && (min..=max).contains(&point.1)
+    }
+}
+
+fn plot() -> Plot {
+    Plot {
+        bounds: (-1.0, 4.5),
+        label: ("north field".to_string(), 7, true),
+        corners: vec![(0.0, 0.0), (4.5, 2.0)],
+        marker: Some((
+            Marker {
+                name: "well".to_string(),
+            },
+            None,
+        )),
+    }
+}
+
+unsafe fn label_into_ffi(
+    label: &(String, u32, bool),
+) -> FFITuple3<*const std::os::raw::c_char, u32, bool> {
+    FFITuple3 {
+        _0: ffi_string!(label.0.clone()),
+        _1: label.1,
+        _2: label.2,
+    }
+}
+
+unsafe fn label_from_ffi(
+    label: FFITuple3<*const std::os::raw::c_char, u32, bool>,
+) -> (String, u32, bool) {
+    let name = string_from_c(label._0);
+    free_rust_string(label._0);
+    (name, label._1, label._2)
+}
+
+#[test]
+fn tuple_fields_round_trip() {
+    use plot_ffi::*;
+
+    let native = plot();
+    unsafe {
+        let corners: FFINestedArray<FFITuple2<f64, f64>> = native
+            .corners
+            .iter()
+            .map(|(x, y)| FFITuple2 { _0: *x, _1: *y })
+            .collect::<Vec<_>>()
+            .into();
+        let marker = Box::into_raw(Box::new(native.marker.clone().unwrap().0));
+        let ptr = plot_rust_ffi_init(
+            FFITuple2 { _0: -1.0, _1: 4.5 },
+            label_into_ffi(&native.label),
+            corners,
+            ffi_common::core::nested::FFINestedOption::some(FFITuple2 {
+                _0: marker,
+                _1: std::ptr::null(),
+            }),
+        );
+        assert_eq!(&*ptr, &native);
+
+        let bounds = get_plot_bounds(ptr);
+        assert_eq!((bounds._0, bounds._1), native.bounds);
+
+        assert_eq!(label_from_ffi(get_plot_label(ptr)), native.label);
+
+        let corners: Vec<(f64, f64)> = Vec::from(get_plot_corners(ptr))
+            .into_iter()
+            .map(|corner| (corner._0, corner._1))
+            .collect();
+        assert_eq!(corners, native.corners);
+
+        let marker = get_optional_plot_marker(ptr);
+        assert!(marker.is_some);
+        assert!(marker.value._1.is_null());
+        let marker = Box::from_raw(marker.value._0 as *mut Marker);
+        assert_eq!(marker.name, "well");
+
+        plot_rust_ffi_free(ptr);
+    }
+}
+
+#[test]
+fn tuple_arguments_and_returns() {
+    use geometry_plot_ffi::*;
+
+    let ptr = Box::into_raw(Box::new(plot()));
+    unsafe {
+        let size = geometry_plot_ffi_width_and_height(ptr);
+        assert_eq!((size._0, size._1), (5.5, 5.5));
+
+        let label = ("south field".to_string(), 2, false);
+        let updated = geometry_plot_ffi_with_label(ptr, label_into_ffi(&label));
+        assert_eq!((*updated).label, label);
+        assert_eq!((*updated).corners, (*ptr).corners);
+
+        assert!(geometry_plot_ffi_contains(
+            ptr,
+            FFITuple2 { _0: 0.5, _1: 4.0 }
+        ));
+        assert!(!geometry_plot_ffi_contains(
+            ptr,
+            FFITuple2 { _0: 5.0, _1: 0.0 }
+        ));
+
+        plot_ffi::plot_rust_ffi_free(updated);
+        plot_ffi::plot_rust_ffi_free(ptr);
+    }
+}
+
+#[test]
+fn tuple_enum_variants() {
+    use extent_ffi::*;
+
+    unsafe {
+        let range = extent_range_rust_ffi_init(FFITuple2 { _0: 1.0, _1: 2.0 });
+        assert_eq!(get_extent_variant(range), ExtentType::Range);
+        let value = get_extent_range_unnamed_field_0(range);
+        assert_eq!((value._0, value._1), (1.0, 2.0));
+        rust_ffi_free_extent(range);
+    }
+}
@@ -0,0 +1,89 @@
+//!
+//! Tests that cover listing the variants of enums and converting them to and from their names.
+//!
+
+use ffi_common::core::{
+    error::get_last_err_msg,
+    ffi_string,
+    string::{free_rust_string, string_from_c},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, ffi_common::derive::FFI)]
+#[repr(C)]
+pub enum Season {
+    Spring,
+    Summer,
+    Fall,
+    Winter,
+}
+
+#[derive(Debug, Clone, PartialEq, ffi_common::derive::FFI)]
+pub enum Application {
+    Seed(String),
+    Fertilizer { rate: f64 },
+    Harvest,
+}
+
+unsafe fn variant_name(name: *const std::os::raw::c_char) -> String {
+    let string = string_from_c(name);
+    free_rust_string(name);
+    string
+}
+
+#[test]
+fn all_variants_are_listed_in_order() {
+    let seasons = Vec::<Season>::from(season_ffi::season_all_variants());
+    assert_eq!(
+        seasons,
+        vec![Season::Spring, Season::Summer, Season::Fall, Season::Winter]
+    );
+
+    use application_ffi::{application_type_ffi::application_type_all_variants, ApplicationType};
+    let applications = Vec::<ApplicationType>::from(application_type_all_variants());
+    assert_eq!(
+        applications,
+        vec![
+            ApplicationType::Seed,
+            ApplicationType::Fertilizer,
+            ApplicationType::Harvest
+        ]
+    );
+}
+
+#[test]
+fn variants_round_trip_through_names() {
+    use season_ffi::*;
+
+    unsafe {
+        assert_eq!(variant_name(season_variant_name(Season::Fall)), "Fall");
+        let season = season_variant_from_name(ffi_string!("Winter"));
+        assert_eq!(*season, Season::Winter);
+        option_Season_free(season);
+
+        use application_ffi::{application_type_ffi::*, ApplicationType};
+        let ptr = application_ffi::application_harvest_rust_ffi_init();
+        assert_eq!(
+            variant_name(application_type_variant_name(
+                application_ffi::get_application_variant(ptr)
+            )),
+            "Harvest"
+        );
+        application_ffi::rust_ffi_free_application(ptr);
+        let variant = application_type_variant_from_name(ffi_string!("Fertilizer"));
+        assert_eq!(*variant, ApplicationType::Fertilizer);
+        option_ApplicationType_free(variant);
+    }
+}
+
+#[test]
+fn unknown_names_set_error() {
+    unsafe {
+        assert!(season_ffi::season_variant_from_name(ffi_string!("Monsoon")).is_null());
+        let error = get_last_err_msg();
+        assert_eq!(
+            string_from_c(error),
+            "`Monsoon` isn't the name of a `Season` variant."
+        );
+        free_rust_string(error);
+    }
+}
@@ -0,0 +1,87 @@
+//!
+//! Tests that cover reading the fields of a complex enum's variants, including from the wrong
+//! variant.
+//!
+
+use ffi_common::core::{
+    error::get_last_err_msg,
+    ffi_string,
+    string::{free_rust_string, string_from_c},
+};
+
+#[derive(Debug, Clone, PartialEq, ffi_common::derive::FFI)]
+pub struct Crop {
+    name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, ffi_common::derive::FFI)]
+pub enum Activity {
+    Idle,
+    Planting(Crop, u32),
+    Irrigation { depth: f64, note: Option<String> },
+}
+
+unsafe fn last_error() -> String {
+    let error = get_last_err_msg();
+    let message = string_from_c(error);
+    free_rust_string(error);
+    message
+}
+
+#[test]
+fn getters_for_another_variant_set_error() {
+    use activity_ffi::*;
+
+    unsafe {
+        let ptr = activity_irrigation_rust_ffi_init(1.5, std::ptr::null_mut());
+        assert!(get_activity_planting_unnamed_field_0(ptr).is_null());
+        assert_eq!(
+            last_error(),
+            "Expected `Activity::Planting`, found `Activity::Irrigation`."
+        );
+        assert_eq!(get_activity_planting_unnamed_field_1(ptr), 0);
+        assert_eq!(get_activity_irrigation_depth(ptr), 1.5);
+        rust_ffi_free_activity(ptr);
+
+        let idle = activity_idle_rust_ffi_init();
+        assert_eq!(get_activity_irrigation_depth(idle), 0.0);
+        assert_eq!(
+            last_error(),
+            "Expected `Activity::Irrigation`, found `Activity::Idle`."
+        );
+        rust_ffi_free_activity(idle);
+    }
+}
+
+#[test]
+fn try_as_returns_payload_for_matching_variant() {
+    use activity_ffi::*;
+
+    unsafe {
+        let crop = crop_ffi::crop_rust_ffi_init(ffi_string!("wheat"));
+        let ptr = activity_planting_rust_ffi_init(crop.cast_mut(), 40);
+
+        let payload = activity_try_as_planting(ptr);
+        assert!(!payload.is_null());
+        assert_eq!(
+            string_from_c(crop_ffi::get_crop_name((*payload).unnamed_field_0)),
+            "wheat"
+        );
+        assert_eq!((*payload).unnamed_field_1, 40);
+        free_activity_planting_payload(payload);
+
+        assert!(activity_try_as_irrigation(ptr).is_null());
+        assert_eq!(
+            last_error(),
+            "Expected `Activity::Irrigation`, found `Activity::Planting`."
+        );
+        rust_ffi_free_activity(ptr);
+
+        let ptr = activity_irrigation_rust_ffi_init(2.0, ffi_string!("dry").cast_mut());
+        let payload = activity_try_as_irrigation(ptr);
+        assert_eq!((*payload).depth, 2.0);
+        assert_eq!(string_from_c((*payload).note), "dry");
+        free_activity_irrigation_payload(payload);
+        rust_ffi_free_activity(ptr);
+    }
+}
@@ -0,0 +1,252 @@
+//!
+//! Support for routing the memory that we hand to the consumer through an allocator that the
+//! consumer provides.
+//!
+//! By default, every buffer that crosses the FFI boundary (`CString`s from `ffi_string!`,
+//! `FFIArray*` buffers, and `option_*` boxes) is allocated by Rust's global allocator. Hosts that
+//! need to account for that memory themselves can call `ffi_register_allocator` with their own
+//! `malloc` and `free`, in which case those buffers are copied into memory from the host's
+//! allocator before they're handed over, and are released with the host's `free` when they're
+//! passed back to Rust.
+//!
+//! The `export_*` and `import_*` functions in this module are the only places where those buffers
+//! should be created and reclaimed, so that both sides always agree on which allocator owns them.
+//!
+
+use std::{
+    alloc::{handle_alloc_error, Layout},
+    ffi::{c_void, CStr, CString},
+    mem::{size_of, ManuallyDrop},
+    os::raw::c_char,
+    sync::OnceLock,
+};
+
+/// A consumer-provided allocation function with the semantics of C's `malloc`.
+///
+pub type MallocFn = unsafe extern "C" fn(size: usize) -> *mut c_void;
+
+/// A consumer-provided deallocation function with the semantics of C's `free`.
+///
+pub type FreeFn = unsafe extern "C" fn(ptr: *mut c_void);
+
+#[derive(Clone, Copy)]
+struct Hooks {
+    malloc: MallocFn,
+    free: FreeFn,
+}
+
+impl Hooks {
+    fn allocate(self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { (self.malloc)(layout.size()) }.cast::<u8>();
+        if ptr.is_null() {
+            handle_alloc_error(layout);
+        }
+        debug_assert_eq!(
+            ptr as usize % layout.align(),
+            0,
+            "The registered allocator returned a misaligned pointer."
+        );
+        ptr
+    }
+
+    unsafe fn release<T>(self, ptr: *const T) {
+        (self.free)(ptr.cast_mut().cast::<c_void>());
+    }
+}
+
+static HOOKS: OnceLock<Hooks> = OnceLock::new();
+
+fn hooks() -> Option<Hooks> {
+    HOOKS.get().copied()
+}
+
+/// Registers `malloc` and `free` as the allocator for every buffer that Rust hands to the
+/// consumer, and that the consumer passes back to Rust to be freed.
+///
+/// Returns `true` if the allocator was registered, or `false` if either function is null or an
+/// allocator has already been registered (it can only be set once).
+///
+/// # Safety
+///
+/// This must be called before any other FFI function, since buffers that were allocated before
+/// the allocator was registered would otherwise be released with the wrong allocator. `malloc`
+/// must return memory that is aligned for any primitive type (as C's `malloc` does), and `free`
+/// must accept any pointer returned by `malloc`.
+///
+#[no_mangle]
+pub unsafe extern "C" fn ffi_register_allocator(
+    malloc: Option<MallocFn>,
+    free: Option<FreeFn>,
+) -> bool {
+    match (malloc, free) {
+        (Some(malloc), Some(free)) => HOOKS.set(Hooks { malloc, free }).is_ok(),
+        _ => false,
+    }
+}
+
+/// Hands the buffer of `v` to the consumer, returning its pointer, length, and capacity.
+///
+/// If an allocator has been registered, the elements are moved into a buffer from that allocator.
+/// Either way, the result must be reclaimed with `import_vec`.
+///
+#[must_use]
+pub fn export_vec<T>(v: Vec<T>) -> (*const T, usize, usize) {
+    match hooks() {
+        Some(hooks) if !v.is_empty() && size_of::<T>() != 0 => {
+            let mut v = v;
+            let len = v.len();
+            let layout =
+                Layout::array::<T>(len).unwrap_or_else(|_| handle_alloc_error(Layout::new::<T>()));
+            let ptr = hooks.allocate(layout).cast::<T>();
+            unsafe {
+                std::ptr::copy_nonoverlapping(v.as_ptr(), ptr, len);
+                // The elements were moved; only the original buffer is left to free.
+                v.set_len(0);
+            }
+            (ptr, len, len)
+        }
+        _ => {
+            let v = ManuallyDrop::new(v);
+            (v.as_ptr(), v.len(), v.capacity())
+        }
+    }
+}
+
+/// Reclaims a buffer that was handed to the consumer with `export_vec`.
+///
+/// # Safety
+///
+/// `ptr`, `len`, and `cap` must have been returned by `export_vec` (and not reclaimed since).
+///
+#[must_use]
+pub unsafe fn import_vec<T>(ptr: *const T, len: usize, cap: usize) -> Vec<T> {
+    match hooks() {
+        Some(hooks) if len != 0 && size_of::<T>() != 0 => {
+            let mut v = Vec::with_capacity(len);
+            std::ptr::copy_nonoverlapping(ptr, v.as_mut_ptr(), len);
+            v.set_len(len);
+            hooks.release(ptr);
+            v
+        }
+        _ => Vec::from_raw_parts(ptr.cast_mut(), len, cap),
+    }
+}
+
+/// Allocates a buffer for `len` elements with the layout `element`.
+///
+/// The buffer comes from the registered allocator, if there is one, and is for an array that the
+/// consumer builds without knowing its Rust type. It can be reclaimed with `import_vec` (as a `Vec`
+/// of the type that `element` describes), or with `release_array`.
+///
+/// If the array is empty, this returns a dangling (but aligned) pointer instead of allocating.
+///
+#[must_use]
+pub fn allocate_array(len: usize, element: Layout) -> *mut u8 {
+    let layout = Layout::from_size_align(element.size().saturating_mul(len), element.align())
+        .unwrap_or_else(|_| handle_alloc_error(element));
+    if layout.size() == 0 {
+        return std::ptr::without_provenance_mut(layout.align());
+    }
+    hooks().map_or_else(
+        || {
+            let ptr = unsafe { std::alloc::alloc(layout) };
+            if ptr.is_null() {
+                handle_alloc_error(layout);
+            }
+            ptr
+        },
+        |hooks| hooks.allocate(layout),
+    )
+}
+
+/// Releases the buffer of an array that was handed to the consumer with `export_vec` or
+/// `allocate_array`, without dropping its elements (which the consumer must already have taken
+/// ownership of).
+///
+/// # Safety
+///
+/// `ptr`, `len`, and `cap` must have been returned by `export_vec` (or `allocate_array`, with a
+/// `cap` equal to `len`) for an array of elements with the layout `element`, and not reclaimed
+/// since.
+///
+pub unsafe fn release_array(ptr: *const u8, len: usize, cap: usize, element: Layout) {
+    match hooks() {
+        Some(hooks) if len != 0 && element.size() != 0 => hooks.release(ptr),
+        _ => {
+            let size = element.size().saturating_mul(cap);
+            if size != 0 {
+                std::alloc::dealloc(
+                    ptr.cast_mut(),
+                    Layout::from_size_align_unchecked(size, element.align()),
+                );
+            }
+        }
+    }
+}
+
+/// Moves `value` to the heap and hands it to the consumer. The result must be reclaimed with
+/// `import_box`.
+///
+#[must_use]
+pub fn export_box<T>(value: T) -> *const T {
+    match hooks() {
+        Some(hooks) if size_of::<T>() != 0 => {
+            let ptr = hooks.allocate(Layout::new::<T>()).cast::<T>();
+            unsafe { ptr.write(value) };
+            ptr
+        }
+        _ => Box::into_raw(Box::new(value)),
+    }
+}
+
+/// Reclaims a value that was handed to the consumer with `export_box`.
+///
+/// # Safety
+///
+/// `ptr` must have been returned by `export_box` (and not reclaimed since).
+///
+#[must_use]
+pub unsafe fn import_box<T>(ptr: *const T) -> T {
+    match hooks() {
+        Some(hooks) if size_of::<T>() != 0 => {
+            let value = ptr.read();
+            hooks.release(ptr);
+            value
+        }
+        _ => *Box::from_raw(ptr.cast_mut()),
+    }
+}
+
+/// Hands `string` to the consumer as a nul-terminated C string. The result must be reclaimed with
+/// `import_c_string`.
+///
+#[must_use]
+pub fn export_c_string(string: CString) -> *mut c_char {
+    match hooks() {
+        Some(hooks) => {
+            let bytes = string.as_bytes_with_nul();
+            let ptr = hooks.allocate(Layout::for_value(bytes));
+            unsafe { std::ptr::copy_nonoverlapping(bytes.as_ptr(), ptr, bytes.len()) };
+            ptr.cast::<c_char>()
+        }
+        None => string.into_raw(),
+    }
+}
+
+/// Reclaims a string that was handed to the consumer with `export_c_string`.
+///
+/// # Safety
+///
+/// `ptr` must have been returned by `export_c_string` (and not reclaimed since).
+///
+#[must_use]
+pub unsafe fn import_c_string(ptr: *const c_char) -> CString {
+    hooks().map_or_else(
+        || CString::from_raw(ptr.cast_mut()),
+        |hooks| {
+            let string = CStr::from_ptr(ptr).to_owned();
+            hooks.release(ptr);
+            string
+        },
+    )
+}
@@ -0,0 +1,143 @@
+//!
+//! An arena that takes ownership of values returned across the FFI.
+//!
+//! This lets a consumer release everything allocated for a call (strings, arrays, boxed structs,
+//! and their elements) with a single call to `ffi_arena_free`, instead of freeing each piece
+//! individually.
+//!
+
+/// Owns the values that were returned by `_in_arena` FFI functions until the arena is freed.
+///
+/// The consumer opens an arena with `ffi_arena_init`, passes it to any number of `_in_arena`
+/// functions, reads the results, and then releases all of them at once with `ffi_arena_free`.
+/// Values returned into an arena must not be freed individually.
+///
+#[derive(Default)]
+pub struct FFIArena {
+    deallocators: Vec<Box<dyn FnOnce()>>,
+}
+
+impl FFIArena {
+    /// Registers `free`, which will release a value owned by this arena when the arena is freed.
+    /// Deallocators run in the reverse order of registration.
+    ///
+    pub fn defer_free<F: FnOnce() + 'static>(&mut self, free: F) {
+        self.deallocators.push(Box::new(free));
+    }
+
+    /// The number of values currently owned by this arena.
+    ///
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.deallocators.len()
+    }
+
+    /// Returns true if this arena doesn't own any values.
+    ///
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.deallocators.is_empty()
+    }
+}
+
+impl Drop for FFIArena {
+    fn drop(&mut self) {
+        while let Some(free) = self.deallocators.pop() {
+            free();
+        }
+    }
+}
+
+impl std::fmt::Debug for FFIArena {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FFIArena")
+            .field("len", &self.deallocators.len())
+            .finish()
+    }
+}
+
+/// Creates a new, empty arena. The result must be released with `ffi_arena_free`.
+///
+#[no_mangle]
+#[must_use]
+pub extern "C" fn ffi_arena_init() -> *mut FFIArena {
+    Box::into_raw(Box::new(FFIArena::default()))
+}
+
+/// Frees `arena`, along with every value that was returned into it.
+///
+/// # Safety
+///
+/// `arena` must have been created by `ffi_arena_init` and must not be used after this call. None of
+/// the values returned into the arena may be used after this call either.
+///
+#[no_mangle]
+pub unsafe extern "C" fn ffi_arena_free(arena: *mut FFIArena) {
+    if arena.is_null() {
+        return;
+    }
+    drop(Box::from_raw(arena));
+}
+
+/// Transfers ownership of `value` to `arena`, which will release it with `free` when the arena is
+/// freed. If `arena` is null, ownership stays with the caller and `free` is never called.
+///
+/// The arena keeps a bitwise copy of `value`; the returned value is the same allocation, so the
+/// caller can read from it, but must not free it.
+///
+/// # Safety
+///
+/// `arena` must be null or a live arena created by `ffi_arena_init`. `free` must be the correct way
+/// to release `value`. `T` must not have any drop glue (like the FFI types that are returned to the
+/// consumer, which only hold pointers and plain values), since dropping either copy would release
+/// the allocation that the other still refers to. This is checked in debug builds.
+///
+pub unsafe fn adopt<T: 'static, F: FnOnce(T) + 'static>(
+    arena: *mut FFIArena,
+    value: T,
+    free: F,
+) -> T {
+    debug_assert!(
+        !std::mem::needs_drop::<T>(),
+        "Values adopted by an arena must not have drop glue."
+    );
+    if let Some(arena) = arena.as_mut() {
+        let owned = std::ptr::read(std::ptr::addr_of!(value));
+        arena.defer_free(move || free(owned));
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{cell::RefCell, rc::Rc};
+
+    #[test]
+    fn frees_in_reverse_order() {
+        let freed = Rc::new(RefCell::new(vec![]));
+        let arena = ffi_arena_init();
+        for value in 0..3 {
+            let freed = Rc::clone(&freed);
+            let returned = unsafe { adopt(arena, value, move |v| freed.borrow_mut().push(v)) };
+            assert_eq!(returned, value);
+        }
+        assert_eq!(unsafe { &*arena }.len(), 3);
+        assert!(freed.borrow().is_empty());
+        unsafe { ffi_arena_free(arena) };
+        assert_eq!(*freed.borrow(), vec![2, 1, 0]);
+    }
+
+    #[test]
+    fn null_arena_leaves_ownership_with_caller() {
+        let freed = Rc::new(RefCell::new(false));
+        let flag = Rc::clone(&freed);
+        let value = unsafe {
+            adopt(std::ptr::null_mut(), 1_u8, move |_| {
+                *flag.borrow_mut() = true;
+            })
+        };
+        assert_eq!(value, 1);
+        assert!(!*freed.borrow());
+    }
+}
@@ -0,0 +1,41 @@
+//!
+//! Helpers for batched FFI functions, which operate on an array of receivers in a single call
+//! instead of requiring one call per receiver.
+//!
+
+/// Iterates over the receivers in the array of pointers passed to a batched FFI function.
+///
+/// # Safety
+///
+/// Unless `len` is `0` (in which case `ptr` may be null), `ptr` must point to the first of `len`
+/// pointers, each of which must point to a valid instance of `T`. The receivers are only borrowed;
+/// the caller retains ownership of them.
+///
+pub unsafe fn receivers<'a, T: 'a>(
+    ptr: *const *const T,
+    len: usize,
+) -> impl ExactSizeIterator<Item = &'a T> {
+    let pointers: &[*const T] = if ptr.is_null() || len == 0 {
+        &[]
+    } else {
+        std::slice::from_raw_parts(ptr, len)
+    };
+    pointers.iter().map(|receiver| &**receiver)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_receivers() {
+        let values = [1_u8, 2, 3];
+        let pointers: Vec<*const u8> = values.iter().map(|v| -> *const u8 { v }).collect();
+        let receivers: Vec<&u8> = unsafe { receivers(pointers.as_ptr(), pointers.len()) }.collect();
+        assert_eq!(receivers, vec![&1, &2, &3]);
+        assert_eq!(
+            unsafe { super::receivers::<u8>(std::ptr::null(), 0) }.len(),
+            0
+        );
+    }
+}
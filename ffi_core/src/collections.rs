@@ -0,0 +1,124 @@
+//!
+//! An FFI-safe representation of maps (like `HashMap` and `BTreeMap`).
+//!
+//! Maps are exposed as parallel `FFIArray*`s of their keys and values, so any key and value types
+//! that can be exposed in an `FFIArray*` can be exposed in a map. Sets (like `HashSet` and
+//! `BTreeSet`) don't need a representation of their own; they're exposed as an `FFIArray*` of
+//! their elements, just like a `Vec`.
+//!
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    hash::{BuildHasher, Hash},
+    iter::FromIterator,
+};
+
+/// A map exposed across the FFI as parallel arrays of its keys and values.
+///
+/// `K` and `V` are the `FFIArray*` types of the map's keys and values. The value at each index of
+/// `values` belongs to the key at the same index of `keys`.
+///
+/// The map owns both arrays, so it should be released by converting it back into a native map (or
+/// by releasing each array the way it would be released on its own).
+///
+#[repr(C)]
+#[derive(Debug)]
+pub struct FFIMap<K, V> {
+    /// The keys of the map.
+    pub keys: K,
+    /// The values of the map, in the same order as `keys`.
+    pub values: V,
+}
+
+impl<'a, K, V, KA, VA> FromIterator<(&'a K, &'a V)> for FFIMap<KA, VA>
+where
+    K: Clone + 'a,
+    V: Clone + 'a,
+    KA: for<'b> From<&'b [K]>,
+    VA: for<'b> From<&'b [V]>,
+{
+    fn from_iter<I: IntoIterator<Item = (&'a K, &'a V)>>(iter: I) -> Self {
+        let (keys, values): (Vec<K>, Vec<V>) = iter
+            .into_iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .unzip();
+        Self {
+            keys: KA::from(&*keys),
+            values: VA::from(&*values),
+        }
+    }
+}
+
+impl<K, V, KA, VA, S> From<&HashMap<K, V, S>> for FFIMap<KA, VA>
+where
+    K: Clone,
+    V: Clone,
+    KA: for<'b> From<&'b [K]>,
+    VA: for<'b> From<&'b [V]>,
+{
+    fn from(map: &HashMap<K, V, S>) -> Self {
+        map.iter().collect()
+    }
+}
+
+impl<K, V, KA, VA> From<&BTreeMap<K, V>> for FFIMap<KA, VA>
+where
+    K: Clone,
+    V: Clone,
+    KA: for<'b> From<&'b [K]>,
+    VA: for<'b> From<&'b [V]>,
+{
+    fn from(map: &BTreeMap<K, V>) -> Self {
+        map.iter().collect()
+    }
+}
+
+impl<K, V, KA, VA, S> From<FFIMap<KA, VA>> for HashMap<K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher + Default,
+    Vec<K>: From<KA>,
+    Vec<V>: From<VA>,
+{
+    fn from(map: FFIMap<KA, VA>) -> Self {
+        Vec::from(map.keys)
+            .into_iter()
+            .zip(Vec::from(map.values))
+            .collect()
+    }
+}
+
+impl<K, V, KA, VA> From<FFIMap<KA, VA>> for BTreeMap<K, V>
+where
+    K: Ord,
+    Vec<K>: From<KA>,
+    Vec<V>: From<VA>,
+{
+    fn from(map: FFIMap<KA, VA>) -> Self {
+        Vec::from(map.keys)
+            .into_iter()
+            .zip(Vec::from(map.values))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{string::FFIArrayString, FFIArrayi32};
+
+    #[test]
+    fn maps_round_trip() {
+        let mut map = HashMap::new();
+        let _ = map.insert("one".to_string(), 1);
+        let _ = map.insert("two".to_string(), 2);
+        let ffi_map: FFIMap<FFIArrayString, FFIArrayi32> = (&map).into();
+        assert_eq!(ffi_map.keys.len, 2);
+        assert_eq!(ffi_map.values.len, 2);
+        assert_eq!(HashMap::from(ffi_map), map);
+
+        let map: BTreeMap<i32, String> = map.into_iter().map(|(k, v)| (v, k)).collect();
+        let ffi_map: FFIMap<FFIArrayi32, FFIArrayString> = (&map).into();
+        assert_eq!(BTreeMap::from(ffi_map), map);
+    }
+}
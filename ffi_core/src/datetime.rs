@@ -1,9 +1,23 @@
 //!
-//! FFI support for exposing time stamps.
+//! FFI support for exposing dates, times, and durations.
+//!
+//! `NaiveDateTime` and `DateTime<Utc>` are exposed as a `TimeStamp`. Types that describe a calendar
+//! date or a time of day, rather than an instant (`NaiveDate`, `NaiveTime`, and
+//! `DateTime<FixedOffset>`), are exposed as `CalendarComponents`. Durations (`chrono::Duration` and
+//! `std::time::Duration`) are exposed as an `f64` number of seconds (see `TimeInterval`).
+//!
+//! A `TimeStamp` or `CalendarComponents` passed in by the consumer may not describe a valid
+//! `DateTime<Utc>`, `NaiveDate`, `NaiveTime`, or `DateTime<FixedOffset>` (like a month of 13).
+//! Generated functions convert these with `CheckedFromFFI`, and set the last error message (see
+//! `crate::error`) instead of making up a value.
 //!
 
-use crate::declare_opaque_type_ffi;
-use chrono::NaiveDateTime;
+use crate::{declare_opaque_type_ffi, declare_value_type_ffi, FFIArrayf64};
+use chrono::{
+    DateTime, Datelike, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike, Utc,
+};
+
+const NANOS_PER_SEC: f64 = 1_000_000_000.0;
 
 /// Represents a UTC timestamp in a way that's safe to transfer across the FFI boundary.
 #[derive(Debug, Clone, Copy, Default)]
@@ -87,32 +101,301 @@ impl From<&TimeStamp> for NaiveDateTime {
     }
 }
 
-// Collection conversion impls
-impl From<&[NaiveDateTime]> for FFIArrayTimeStamp {
-    fn from(slice: &[NaiveDateTime]) -> Self {
-        let timestamps: Vec<TimeStamp> = slice.iter().map(|e| e.into()).collect();
-        timestamps.as_slice().into()
+impl From<&DateTime<Utc>> for TimeStamp {
+    fn from(datetime: &DateTime<Utc>) -> Self {
+        Self {
+            secs: datetime.timestamp(),
+            nsecs: datetime.timestamp_subsec_nanos(),
+        }
     }
 }
 
-#[allow(clippy::use_self)]
-impl From<FFIArrayTimeStamp> for Vec<NaiveDateTime> {
-    fn from(array: FFIArrayTimeStamp) -> Self {
-        let timestamps = Vec::<TimeStamp>::from(array);
-        timestamps.iter().map(NaiveDateTime::from).collect()
+impl CheckedFromFFI for DateTime<Utc> {
+    type FFIValue = TimeStamp;
+
+    fn checked_from_ffi(timestamp: &TimeStamp) -> Result<Self, String> {
+        Self::from_timestamp(timestamp.secs, timestamp.nsecs).ok_or_else(|| {
+            format!(
+                "Invalid timestamp: {} seconds and {} nanoseconds.",
+                timestamp.secs, timestamp.nsecs
+            )
+        })
+    }
+}
+
+/// The components of a calendar date, a time of day, and a UTC offset, in a way that's safe to
+/// transfer across the FFI boundary (and that maps directly to something like Swift's
+/// `DateComponents`).
+///
+/// Each group of components is only meaningful if its `has_*` flag is set: a `NaiveDate` only sets
+/// the date, a `NaiveTime` only sets the time, and a `DateTime<FixedOffset>` sets all three.
+/// Components that don't describe a valid date, time, or offset can't be converted back to a native
+/// type (see `CheckedFromFFI`).
+///
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CalendarComponents {
+    /// True if `year`, `month`, and `day` are set.
+    pub has_date: bool,
+    /// The year (in the proleptic Gregorian calendar).
+    pub year: i32,
+    /// The month, starting from 1.
+    pub month: u32,
+    /// The day of the month, starting from 1.
+    pub day: u32,
+    /// True if `hour`, `minute`, `second`, and `nanosecond` are set.
+    pub has_time: bool,
+    /// The hour, from 0 to 23.
+    pub hour: u32,
+    /// The minute, from 0 to 59.
+    pub minute: u32,
+    /// The second, from 0 to 59.
+    pub second: u32,
+    /// Nanoseconds since the last whole second (which may exceed 999,999,999 to represent a leap
+    /// second).
+    pub nanosecond: u32,
+    /// True if `offset_secs` is set.
+    pub has_offset: bool,
+    /// The offset from UTC, in seconds east of UTC.
+    pub offset_secs: i32,
+}
+
+declare_value_type_ffi!(CalendarComponents);
+
+impl CalendarComponents {
+    fn date(&self) -> Result<NaiveDate, String> {
+        NaiveDate::from_ymd_opt(self.year, self.month, self.day).ok_or_else(|| {
+            format!(
+                "Invalid date: year {}, month {}, day {}.",
+                self.year, self.month, self.day
+            )
+        })
+    }
+
+    fn time(&self) -> Result<NaiveTime, String> {
+        NaiveTime::from_hms_nano_opt(self.hour, self.minute, self.second, self.nanosecond)
+            .ok_or_else(|| {
+                format!(
+                    "Invalid time: {}:{}:{} and {} nanoseconds.",
+                    self.hour, self.minute, self.second, self.nanosecond
+                )
+            })
+    }
+
+    fn offset(&self) -> Result<FixedOffset, String> {
+        FixedOffset::east_opt(self.offset_secs)
+            .ok_or_else(|| format!("Invalid UTC offset: {} seconds.", self.offset_secs))
+    }
+
+    fn with_date(self, date: NaiveDate) -> Self {
+        Self {
+            has_date: true,
+            year: date.year(),
+            month: date.month(),
+            day: date.day(),
+            ..self
+        }
+    }
+
+    fn with_time(self, time: NaiveTime) -> Self {
+        Self {
+            has_time: true,
+            hour: time.hour(),
+            minute: time.minute(),
+            second: time.second(),
+            nanosecond: time.nanosecond(),
+            ..self
+        }
+    }
+}
+
+impl From<&NaiveDate> for CalendarComponents {
+    fn from(date: &NaiveDate) -> Self {
+        Self::default().with_date(*date)
+    }
+}
+
+impl CheckedFromFFI for NaiveDate {
+    type FFIValue = CalendarComponents;
+
+    fn checked_from_ffi(components: &CalendarComponents) -> Result<Self, String> {
+        components.date()
     }
 }
 
-impl From<Option<&[NaiveDateTime]>> for FFIArrayTimeStamp {
-    fn from(slice: Option<&[NaiveDateTime]>) -> Self {
-        slice.map_or(
-            Self {
-                ptr: std::ptr::null(),
-                len: 0,
-                cap: 0,
-            },
-            |s| s.into(),
-        )
+impl From<&NaiveTime> for CalendarComponents {
+    fn from(time: &NaiveTime) -> Self {
+        Self::default().with_time(*time)
+    }
+}
+
+impl CheckedFromFFI for NaiveTime {
+    type FFIValue = CalendarComponents;
+
+    fn checked_from_ffi(components: &CalendarComponents) -> Result<Self, String> {
+        components.time()
+    }
+}
+
+impl From<&DateTime<FixedOffset>> for CalendarComponents {
+    fn from(datetime: &DateTime<FixedOffset>) -> Self {
+        let local = datetime.naive_local();
+        Self {
+            has_offset: true,
+            offset_secs: datetime.offset().local_minus_utc(),
+            ..Self::default()
+        }
+        .with_date(local.date())
+        .with_time(local.time())
+    }
+}
+
+impl CheckedFromFFI for DateTime<FixedOffset> {
+    type FFIValue = CalendarComponents;
+
+    fn checked_from_ffi(components: &CalendarComponents) -> Result<Self, String> {
+        let local = components.date()?.and_time(components.time()?);
+        let offset = components.offset()?;
+        // A fixed offset always maps a local time to exactly one instant.
+        Ok(offset
+            .from_local_datetime(&local)
+            .single()
+            .unwrap_or_else(|| offset.from_utc_datetime(&local)))
+    }
+}
+
+/// A type that's converted from an FFI value that may not describe a valid value of the type, like
+/// a date or time (from a `TimeStamp` or `CalendarComponents`), or a `char` (from an `FFIchar`).
+///
+pub trait CheckedFromFFI: Sized {
+    /// The FFI type that this is converted from.
+    ///
+    type FFIValue;
+
+    /// Converts `value` into this type.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the problem if `value` doesn't describe a valid value of this type.
+    ///
+    fn checked_from_ffi(value: &Self::FFIValue) -> Result<Self, String>;
+}
+
+/// Converts `array` (an `FFIArray*` of `T::FFIValue`s) into a `Vec` of `T`.
+///
+/// # Errors
+///
+/// Returns a description of the problem with the first element that isn't a valid `T`.
+///
+pub fn checked_vec_from_ffi<T, A>(array: A) -> Result<Vec<T>, String>
+where
+    T: CheckedFromFFI,
+    Vec<T::FFIValue>: From<A>,
+{
+    Vec::<T::FFIValue>::from(array)
+        .iter()
+        .map(T::checked_from_ffi)
+        .collect()
+}
+
+/// Like `checked_vec_from_ffi`, for an optional array (which is `None` if its pointer is null).
+///
+/// # Errors
+///
+/// Returns a description of the problem with the first element that isn't a valid `T`.
+///
+pub fn checked_option_vec_from_ffi<T, A>(array: A) -> Result<Option<Vec<T>>, String>
+where
+    T: CheckedFromFFI,
+    Option<Vec<T::FFIValue>>: From<A>,
+{
+    Option::<Vec<T::FFIValue>>::from(array)
+        .map(|values| values.iter().map(T::checked_from_ffi).collect())
+        .transpose()
+}
+
+/// A duration that can be exposed across the FFI boundary as an `f64` number of seconds (like
+/// Swift's `TimeInterval`).
+///
+pub trait TimeInterval: Sized {
+    /// Creates a duration from a number of seconds. Values that can't be represented are clamped
+    /// to the nearest value that can.
+    ///
+    fn from_time_interval(interval: f64) -> Self;
+
+    /// The number of seconds in this duration.
+    ///
+    fn time_interval(&self) -> f64;
+}
+
+impl TimeInterval for std::time::Duration {
+    fn from_time_interval(interval: f64) -> Self {
+        // `std::time::Duration` can't be negative, so negative intervals (and NaN) become zero.
+        Self::try_from_secs_f64(interval.max(0.0)).unwrap_or(Self::MAX)
+    }
+
+    fn time_interval(&self) -> f64 {
+        self.as_secs_f64()
+    }
+}
+
+impl TimeInterval for chrono::Duration {
+    fn from_time_interval(interval: f64) -> Self {
+        let magnitude = std::time::Duration::from_time_interval(interval.abs());
+        let duration = Self::from_std(magnitude).unwrap_or(Self::MAX);
+        if interval < 0.0 {
+            -duration
+        } else {
+            duration
+        }
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn time_interval(&self) -> f64 {
+        self.num_seconds() as f64 + f64::from(self.subsec_nanos()) / NANOS_PER_SEC
+    }
+}
+
+// Collection conversion impls (the elements of these arrays are converted individually, so we
+// can't use the impls generated by `declare_*_type_ffi!` directly).
+macro_rules! declare_collection_conversions {
+    ($($native:ty => $array:ident of $element:ident),*) => ($(
+        impl From<&[$native]> for $array {
+            fn from(slice: &[$native]) -> Self {
+                let elements: Vec<$element> = slice.iter().map(<$element>::from).collect();
+                elements.as_slice().into()
+            }
+        }
+
+        impl From<Option<&[$native]>> for $array {
+            fn from(slice: Option<&[$native]>) -> Self {
+                slice.map_or(
+                    Self {
+                        ptr: std::ptr::null(),
+                        len: 0,
+                        cap: 0,
+                    },
+                    |s| s.into(),
+                )
+            }
+        }
+    )*);
+}
+
+declare_collection_conversions!(
+    NaiveDateTime => FFIArrayTimeStamp of TimeStamp,
+    DateTime<Utc> => FFIArrayTimeStamp of TimeStamp,
+    NaiveDate => FFIArrayCalendarComponents of CalendarComponents,
+    NaiveTime => FFIArrayCalendarComponents of CalendarComponents,
+    DateTime<FixedOffset> => FFIArrayCalendarComponents of CalendarComponents
+);
+
+// Any `TimeStamp` is a valid `NaiveDateTime`, so arrays of them can be converted back directly.
+// Arrays of the other date and time types are converted with `checked_vec_from_ffi`.
+impl From<FFIArrayTimeStamp> for Vec<NaiveDateTime> {
+    fn from(array: FFIArrayTimeStamp) -> Self {
+        let elements = Vec::<TimeStamp>::from(array);
+        elements.iter().map(NaiveDateTime::from).collect()
     }
 }
 
@@ -126,6 +409,52 @@ impl From<FFIArrayTimeStamp> for Option<Vec<NaiveDateTime>> {
     }
 }
 
+// Durations are exposed as arrays of seconds.
+macro_rules! declare_time_interval_collection_conversions {
+    ($($native:ty),*) => ($(
+        impl From<&[$native]> for FFIArrayf64 {
+            fn from(slice: &[$native]) -> Self {
+                let intervals: Vec<f64> = slice.iter().map(TimeInterval::time_interval).collect();
+                intervals.into()
+            }
+        }
+
+        impl From<FFIArrayf64> for Vec<$native> {
+            fn from(array: FFIArrayf64) -> Self {
+                Vec::<f64>::from(array)
+                    .into_iter()
+                    .map(TimeInterval::from_time_interval)
+                    .collect()
+            }
+        }
+
+        impl From<Option<&[$native]>> for FFIArrayf64 {
+            fn from(slice: Option<&[$native]>) -> Self {
+                slice.map_or(
+                    Self {
+                        ptr: std::ptr::null(),
+                        len: 0,
+                        cap: 0,
+                    },
+                    |s| s.into(),
+                )
+            }
+        }
+
+        impl From<FFIArrayf64> for Option<Vec<$native>> {
+            fn from(array: FFIArrayf64) -> Self {
+                if array.ptr.is_null() {
+                    None
+                } else {
+                    Some(Vec::from(array))
+                }
+            }
+        }
+    )*);
+}
+
+declare_time_interval_collection_conversions!(std::time::Duration, chrono::Duration);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -162,4 +491,66 @@ mod tests {
         let date_vec_again = Vec::<NaiveDateTime>::from(time_stamp_array);
         assert_eq!(input_date_vec, date_vec_again);
     }
+
+    #[test]
+    fn naive_date_round_trips_through_calendar_components() {
+        let date = NaiveDate::from_ymd_opt(2021, 4, 15).unwrap();
+        let components = CalendarComponents::from(&date);
+        assert!(components.has_date && !components.has_time && !components.has_offset);
+        assert_eq!(
+            (components.year, components.month, components.day),
+            (2021, 4, 15)
+        );
+        assert_eq!(NaiveDate::checked_from_ffi(&components), Ok(date));
+    }
+
+    #[test]
+    fn fixed_offset_date_time_keeps_its_offset() {
+        let offset = FixedOffset::west_opt(7 * 3600).unwrap();
+        let datetime = offset.with_ymd_and_hms(2021, 4, 15, 6, 30, 0).unwrap();
+        let components = CalendarComponents::from(&datetime);
+        assert_eq!(components.hour, 6);
+        assert_eq!(components.offset_secs, -7 * 3600);
+        let datetime_again = DateTime::<FixedOffset>::checked_from_ffi(&components).unwrap();
+        assert_eq!(datetime_again, datetime);
+        assert_eq!(datetime_again.offset(), &offset);
+    }
+
+    #[test]
+    fn invalid_calendar_components_are_errors() {
+        let components = CalendarComponents {
+            has_date: true,
+            year: 2021,
+            month: 2,
+            day: 30,
+            ..CalendarComponents::default()
+        };
+        assert_eq!(
+            NaiveDate::checked_from_ffi(&components),
+            Err("Invalid date: year 2021, month 2, day 30.".to_string())
+        );
+        let components = CalendarComponents {
+            day: 28,
+            offset_secs: 86_400,
+            ..components
+        };
+        assert_eq!(
+            DateTime::<FixedOffset>::checked_from_ffi(&components),
+            Err("Invalid UTC offset: 86400 seconds.".to_string())
+        );
+    }
+
+    #[test]
+    fn durations_to_time_intervals_and_back() {
+        let duration = chrono::Duration::milliseconds(-1_500);
+        assert!((duration.time_interval() + 1.5).abs() < f64::EPSILON);
+        assert_eq!(chrono::Duration::from_time_interval(-1.5), duration);
+        assert_eq!(
+            std::time::Duration::from_time_interval(-1.5),
+            std::time::Duration::ZERO
+        );
+        let durations = vec![std::time::Duration::from_millis(250); 2];
+        let array = FFIArrayf64::from(&*durations);
+        assert_eq!(Vec::<std::time::Duration>::from(array), durations);
+    }
 }
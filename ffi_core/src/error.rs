@@ -7,6 +7,7 @@
 //! that error as a "string" (`*const c_char`).
 //!
 
+use crate::allocator::export_c_string;
 use std::{cell::RefCell, ffi::CString, os::raw::c_char};
 
 thread_local! {
@@ -70,7 +71,7 @@ pub extern "C" fn get_last_err_msg() -> *const c_char {
         msg = last_error.borrow().clone();
     });
     match msg {
-        Some(string) => try_or_set_error!(CString::new(string).map(CString::into_raw)),
+        Some(string) => try_or_set_error!(CString::new(string).map(export_c_string)),
         None => std::ptr::null(),
     }
 }
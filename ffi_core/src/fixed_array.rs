@@ -0,0 +1,66 @@
+//!
+//! FFI-safe representations of fixed-size arrays, like `[f64; 3]`.
+//!
+//! C can't pass arrays by value, so arrays of raw types are exposed inline in an `FFIFixedArray`, a
+//! `repr(C)` struct with a single array field (which Swift imports as a tuple of the elements).
+//! This is generic, so `cbindgen` generates a C struct for each combination of element type and
+//! length that's used (like `FFIFixedArray_f64__3` for a `[f64; 3]`).
+//!
+//! Arrays of other types are exposed as an `FFIArray*`, just like a `Vec`, and are converted back
+//! into an array with `into_fixed_array`, which checks that the consumer passed in the right number
+//! of elements. If it didn't, the generated function sets the last error message (see
+//! `crate::error`) and returns null (or a placeholder value) instead.
+//!
+
+use std::convert::TryFrom;
+
+/// An array of `N` values of the raw FFI type `T`, which is passed by value.
+///
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct FFIFixedArray<T, const N: usize> {
+    /// The elements of the array.
+    pub elements: [T; N],
+}
+
+impl<T, const N: usize> From<[T; N]> for FFIFixedArray<T, N> {
+    fn from(elements: [T; N]) -> Self {
+        Self { elements }
+    }
+}
+
+/// Converts `elements` (generally, the contents of an `FFIArray*` that was passed in by the
+/// consumer) into an array of `N` elements.
+///
+/// # Errors
+///
+/// Returns an error message if `elements` doesn't contain exactly `N` elements.
+///
+pub fn into_fixed_array<T, const N: usize>(elements: Vec<T>) -> Result<[T; N], String> {
+    let len = elements.len();
+    <[T; N]>::try_from(elements)
+        .map_err(|_| format!("Expected an array of {N} elements, but received {len} elements."))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed_arrays_round_trip() {
+        let array = FFIFixedArray::from([1, 2, -3]);
+        assert_eq!(array.elements, [1, 2, -3]);
+        assert_eq!(
+            into_fixed_array::<String, 2>(vec!["a".to_string(), "b".to_string()]),
+            Ok(["a".to_string(), "b".to_string()])
+        );
+    }
+
+    #[test]
+    fn fixed_arrays_check_their_length() {
+        assert_eq!(
+            into_fixed_array::<u8, 3>(vec![1, 2]),
+            Err("Expected an array of 3 elements, but received 2 elements.".to_string())
+        );
+    }
+}
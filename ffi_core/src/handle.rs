@@ -0,0 +1,176 @@
+//!
+//! Describes how instances of opaque types (the types that are exposed to the consumer as a
+//! `*const T`) are allocated when they're passed across the FFI.
+//!
+//! By default, each pointer that's passed to the consumer owns a unique `Box`, and copying it
+//! clones the whole instance. Types with the `ffi(shared)` attribute are allocated in an `Arc`
+//! instead, so copying a pointer only increments the reference count, and the consumer can share an
+//! instance with Rust (through an `Arc<T>` field, argument, or return value).
+//!
+//! Every opaque type implements `OpaqueHandle` (see `declare_opaque_type_ffi`), and generated code
+//! always goes through it, so that other types don't need to know how an opaque type is allocated.
+//!
+
+use std::sync::Arc;
+
+/// Allocates and releases the pointers to `Self` that are passed across the FFI. The provided
+/// implementations allocate each instance in its own `Box`.
+///
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` isn't an opaque FFI type",
+    note = "if `{Self}` is an `ffi(transparent)` newtype, derive it before the types that use it, \
+or reference it by a path that starts with its crate's name"
+)]
+pub trait OpaqueHandle: Sized {
+    /// Moves `value` into a new allocation, and returns a pointer that owns it.
+    ///
+    fn into_handle(value: Self) -> *const Self {
+        Box::into_raw(Box::new(value))
+    }
+
+    /// Takes ownership of the instance that `handle` points to (or of a clone of it, if it's still
+    /// shared with something else).
+    ///
+    /// # Safety
+    ///
+    /// `handle` must have been returned by `into_handle` (or `retain_handle` or `share_handle`),
+    /// and must not be used after this call.
+    ///
+    unsafe fn from_handle(handle: *const Self) -> Self {
+        *Box::from_raw(handle.cast_mut())
+    }
+
+    /// Releases `handle`, freeing the instance it points to unless it's still shared with something
+    /// else.
+    ///
+    /// # Safety
+    ///
+    /// `handle` must be null, or must have been returned by `into_handle` (or `retain_handle` or
+    /// `share_handle`), and must not be used after this call.
+    ///
+    unsafe fn release_handle(handle: *const Self) {
+        if !handle.is_null() {
+            drop(Self::from_handle(handle));
+        }
+    }
+
+    /// Returns a new pointer that owns a clone of the instance that `handle` points to (or, for
+    /// shared types, another reference to the same instance).
+    ///
+    /// # Safety
+    ///
+    /// `handle` must point to a valid instance of `Self`.
+    ///
+    unsafe fn retain_handle(handle: *const Self) -> *const Self
+    where
+        Self: Clone,
+    {
+        Self::into_handle(Self::clone(&*handle))
+    }
+
+    /// Returns a pointer that owns a clone of `value` (or, for shared types, another reference to
+    /// the same instance).
+    ///
+    fn share_handle(value: &Arc<Self>) -> *const Self
+    where
+        Self: Clone,
+    {
+        Self::into_handle(Self::clone(value))
+    }
+
+    /// Like `from_handle`, but returns the instance in an `Arc`, which doesn't require a clone for
+    /// shared types.
+    ///
+    /// # Safety
+    ///
+    /// See `from_handle`.
+    ///
+    unsafe fn arc_from_handle(handle: *const Self) -> Arc<Self> {
+        Arc::new(Self::from_handle(handle))
+    }
+}
+
+/// Implements `OpaqueHandle` for a type with the `ffi(shared)` attribute, so that its instances
+/// are allocated in an `Arc`. The type must implement `Clone`.
+///
+#[macro_export]
+macro_rules! declare_shared_handle {
+    ($t:ident) => {
+        impl $crate::handle::OpaqueHandle for $t {
+            fn into_handle(value: Self) -> *const Self {
+                std::sync::Arc::into_raw(std::sync::Arc::new(value))
+            }
+
+            unsafe fn from_handle(handle: *const Self) -> Self {
+                std::sync::Arc::try_unwrap(std::sync::Arc::from_raw(handle))
+                    .unwrap_or_else(|shared| Self::clone(&shared))
+            }
+
+            unsafe fn release_handle(handle: *const Self) {
+                if !handle.is_null() {
+                    std::sync::Arc::decrement_strong_count(handle);
+                }
+            }
+
+            unsafe fn retain_handle(handle: *const Self) -> *const Self {
+                std::sync::Arc::increment_strong_count(handle);
+                handle
+            }
+
+            fn share_handle(value: &std::sync::Arc<Self>) -> *const Self {
+                std::sync::Arc::into_raw(std::sync::Arc::clone(value))
+            }
+
+            unsafe fn arc_from_handle(handle: *const Self) -> std::sync::Arc<Self> {
+                std::sync::Arc::from_raw(handle)
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Unique(u8);
+
+    impl OpaqueHandle for Unique {}
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Shared(u8);
+
+    declare_shared_handle!(Shared);
+
+    #[test]
+    fn unique_handles_own_a_copy() {
+        let handle = Unique::into_handle(Unique(1));
+        let copy = unsafe { Unique::retain_handle(handle) };
+        assert_ne!(handle, copy);
+        unsafe {
+            Unique::release_handle(copy);
+            assert_eq!(Unique::from_handle(handle), Unique(1));
+        }
+    }
+
+    #[test]
+    fn shared_handles_share_an_instance() {
+        let native = Arc::new(Shared(2));
+        let handle = Shared::share_handle(&native);
+        assert_eq!(handle, Arc::as_ptr(&native));
+        let retained = unsafe { Shared::retain_handle(handle) };
+        assert_eq!(retained, handle);
+        assert_eq!(Arc::strong_count(&native), 3);
+        unsafe {
+            Shared::release_handle(retained);
+            assert_eq!(Arc::strong_count(&native), 2);
+            // The instance is still shared, so taking ownership of it clones it.
+            assert_eq!(Shared::from_handle(handle), Shared(2));
+        }
+        assert_eq!(Arc::strong_count(&native), 1);
+
+        let handle = Shared::into_handle(Shared(3));
+        let arc = unsafe { Shared::arc_from_handle(handle) };
+        assert_eq!(Arc::as_ptr(&arc), handle);
+    }
+}
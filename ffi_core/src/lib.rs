@@ -22,14 +22,31 @@
 
 pub use paste::paste;
 
+pub mod allocator;
+pub mod arena;
+pub mod batch;
+pub mod collections;
 #[macro_use]
 pub mod error;
 pub mod datetime;
+pub mod fixed_array;
+#[macro_use]
+pub mod handle;
 #[macro_use]
 pub mod macros;
+pub mod nested;
+pub mod scalar;
 pub mod string;
+pub mod transparent;
+pub mod tuple;
+
+pub use scalar::{FFIUuid, FFIchar, FFIi128, FFIu128};
+use uuid::Uuid;
 
-declare_value_type_ffi!(bool, u8, u16, u32, u64, i8, i16, i32, i64, f32, f64);
+declare_value_type_ffi!(bool, u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, f32, f64);
+declare_mapped_value_type_ffi!(u128 => FFIu128, i128 => FFIi128);
+declare_mapped_value_type_ffi!(checked char => FFIchar);
+declare_mapped_value_type_ffi!(Uuid => FFIUuid);
 
 #[cfg(test)]
 mod tests {
@@ -47,4 +64,16 @@ mod tests {
         assert!(!u8_pointer.is_null());
         assert_eq!(unsafe { *Box::from_raw(u8_pointer as *mut u8) }, 3);
     }
+
+    #[test]
+    fn test_ffi_slice_borrows() {
+        let v: Vec<f64> = vec![1.0, 2.0, 3.0];
+        let slice = FFISlicef64::from(&*v);
+        assert_eq!(slice.ptr, v.as_ptr());
+        assert_eq!(slice.len, 3);
+
+        let none = FFISlicef64::from(None);
+        assert!(none.ptr.is_null());
+        assert_eq!(none.len, 0);
+    }
 }
@@ -8,6 +8,8 @@
 /// length, and its capacity.
 /// 1. `From` impls for converting between `&[T]` of those element types and this new struct.
 /// 1. A function for freeing an array of this type.
+/// 1. A repr(C) borrowed slice type (a pointer and a length) for exposing a view into Rust-owned
+///    memory without copying it.
 ///
 /// Usage looks like:
 /// ```
@@ -31,6 +33,8 @@
 ///
 /// let v: Vec<Foo> = vec![Foo::Bar, Foo::Baz];
 /// let ffi = FFIArrayFoo::from(&*v);
+/// let slice = FFISliceFoo::from(&*v);
+/// assert_eq!(slice.ptr, v.as_ptr());
 /// # }
 /// ```
 ///
@@ -48,8 +52,8 @@ places where null is really possible.)
 
 # Safety
 
-The collection represented by this type needs to be reclaimed by Rust with `Vec::from_raw_parts` so
-it can be deallocated safely. Pass this struct to `ffi_array_*_free` when you're done with it (i.e.,
+The collection represented by this type needs to be reclaimed by Rust (with
+`allocator::import_vec`) so it can be deallocated safely. Pass this struct to `ffi_array_*_free` when you're done with it (i.e.,
 when you've copied it into native memory, displayed it, whatever you're doing on the other side of
 the FFI boundary) so we can take care of those steps.
             """]
@@ -125,17 +129,12 @@ unallocated memory if, for example, you pass an array that represents the `None`
                 if array.ptr.is_null() {
                     return;
                 }
-                drop(Vec::from_raw_parts(array.ptr as *mut $t, array.len, array.cap));
+                drop($crate::allocator::import_vec(array.ptr, array.len, array.cap));
             }
 
             impl From<&[$t]> for [<FFIArray $t>] {
                 fn from(slice: &[$t]) -> Self {
-                    let v: std::mem::ManuallyDrop<Vec<$t>> = std::mem::ManuallyDrop::new(slice.to_vec());
-                    let len = v.len();
-                    let ptr = v.as_ptr();
-                    let cap = v.capacity();
-
-                    Self { ptr, len, cap }
+                    slice.to_vec().into()
                 }
             }
 
@@ -152,12 +151,19 @@ unallocated memory if, for example, you pass an array that represents the `None`
                 }
             }
 
+            impl From<Vec<$t>> for [<FFIArray $t>] {
+                /// Moves the elements of `v` into an array without copying them.
+                ///
+                fn from(v: Vec<$t>) -> Self {
+                    let (ptr, len, cap) = $crate::allocator::export_vec(v);
+                    Self { ptr, len, cap }
+                }
+            }
+
             #[allow(clippy::use_self)]
             impl From<[<FFIArray $t>]> for Vec<$t> {
                 fn from(array: [<FFIArray $t>]) -> Self {
-                    unsafe {
-                        Vec::from_raw_parts(array.ptr as *mut $t, array.len, array.cap)
-                    }
+                    unsafe { $crate::allocator::import_vec(array.ptr, array.len, array.cap) }
                 }
             }
 
@@ -171,10 +177,53 @@ unallocated memory if, for example, you pass an array that represents the `None`
                 }
             }
 
+            #[doc = """
+An FFI-safe, non-owning view into a contiguous collection of FFI-safe data structures that are owned
+by Rust.
+
+This can also express the `None` variant of an `Option<Vec<_>>` with a null pointer and a len of 0.
+
+# Safety
+
+Unlike `FFIArray*`, this type does not own the memory it points to, so there is nothing to free.
+The memory is owned by the Rust instance this slice was borrowed from, and it is only valid for as
+long as that instance is alive and unmodified. Consumers must keep the owning instance alive while
+they read from this slice, and must not read from it after freeing the owner.
+            """]
+            #[repr(C)]
+            #[derive(Clone, Copy, Debug)]
+            pub struct [<FFISlice $t>] {
+                #[doc = "Pointer to the first element in the slice."]
+                pub ptr: *const $t,
+                #[doc = "The length of (i.e. the number of elements in) this slice."]
+                pub len: usize,
+            }
+
+            impl From<&[$t]> for [<FFISlice $t>] {
+                fn from(slice: &[$t]) -> Self {
+                    Self {
+                        ptr: slice.as_ptr(),
+                        len: slice.len(),
+                    }
+                }
+            }
+
+            impl From<Option<&[$t]>> for [<FFISlice $t>] {
+                fn from(opt: Option<&[$t]>) -> Self {
+                    opt.map_or(
+                        Self {
+                            ptr: std::ptr::null(),
+                            len: 0,
+                        },
+                        |v| v.into(),
+                    )
+                }
+            }
+
             #[doc = """
 Initialize an optional value type from across the FFI boundary. When `has_value` is `false`, `value`
-will be ignored and the return value will be a null pointer. When has_value is `true`, a pointer to
-a Rust-managed instance of `value` will be returned.
+will be ignored and the return value will be a null pointer. When `has_value` is `true`, a pointer
+to a Rust-managed instance of `value` will be returned.
 
 # Safety
 
@@ -184,7 +233,7 @@ you're finished with it on the consumer side. Otherwise you will leak memory.
             #[no_mangle]
             pub extern "C" fn [<option_ $t _init>](has_value: bool, value: $t) -> *const $t {
                  if has_value {
-                    Box::into_raw(Box::new(value))
+                    $crate::allocator::export_box(value)
                 } else {
                     std::ptr::null()
                 }
@@ -207,18 +256,188 @@ It's safe to call this with a null pointer.
             #[no_mangle]
             pub unsafe extern "C" fn [<option_ $t _free>](option: *const $t) {
                 if !option.is_null() {
-                    drop(Box::from_raw(option as *mut $t));
+                    let _value = $crate::allocator::import_box(option);
                 }
             }
         }
     )*);
 }
 
+/// Like `declare_value_type_ffi`, but for value types that aren't FFI-safe themselves.
+///
+/// These are exposed as another (FFI-safe) type, like `u128` (as `FFIu128`). The FFI type must be
+/// `Copy`, and convertible to and from the native type with `From`. Generates the following:
+/// 1. A repr(C) struct with a pointer to an array (whose elements are the FFI type), its length,
+///    and its capacity, named after the native type.
+/// 1. `From` impls for converting between `&[T]` of the native type and this new struct, and from
+///    this new struct to a `Vec` of the FFI type.
+/// 1. Functions for initializing and freeing an array of this type.
+/// 1. Functions for initializing and freeing an optional value of this type.
+///
+/// Since the elements are converted, there's no borrowed slice type for these.
+///
+/// Types that are prefixed with `checked` (like `char`, since not every `u32` is a Unicode scalar)
+/// are converted from their FFI type with `CheckedFromFFI` instead of `From`, so this struct can
+/// only be converted to a `Vec` of them with `checked_vec_from_ffi`.
+///
+#[macro_export]
+macro_rules! declare_mapped_value_type_ffi {
+    ($(checked $t:ident => $ffi:ident),*) => ($(
+        $crate::declare_mapped_value_type_ffi!(@array $t => $ffi);
+    )*);
+    ($($t:ident => $ffi:ident),*) => ($(
+        $crate::declare_mapped_value_type_ffi!(@array $t => $ffi);
+
+        $crate::paste! {
+            #[allow(clippy::use_self)]
+            impl From<[<FFIArray $t>]> for Vec<$t> {
+                fn from(array: [<FFIArray $t>]) -> Self {
+                    Vec::<$ffi>::from(array).into_iter().map(<$t>::from).collect()
+                }
+            }
+
+            impl From<[<FFIArray $t>]> for Option<Vec<$t>> {
+                fn from(array: [<FFIArray $t>]) -> Self {
+                    Option::<Vec<$ffi>>::from(array)
+                        .map(|v| v.into_iter().map(<$t>::from).collect())
+                }
+            }
+        }
+    )*);
+    (@array $t:ident => $ffi:ident) => (
+        $crate::paste! {
+            #[doc = "An FFI-safe representation of a collection of `" $t "`, as `" $ffi "` elements."]
+            #[doc = ""]
+            #[doc = "This can also express an `Option<Vec<_>>` with a null pointer and a len and capacity of 0."]
+            #[doc = "Pass this to `ffi_array_" $t "_free` when you're done with it."]
+            #[repr(C)]
+            #[allow(missing_copy_implementations)]
+            #[derive(Clone, Debug)]
+            pub struct [<FFIArray $t>] {
+                #[doc = "Pointer to the first element in the array."]
+                pub ptr: *const $ffi,
+                #[doc = "The length of (i.e. the number of elements in) this array."]
+                pub len: usize,
+                #[doc = "The capacity with which this array was allocated."]
+                pub cap: usize,
+            }
+
+            #[no_mangle]
+            #[doc = "Initialize an `FFIArray" $t "` from across the FFI boundary. This will copy the provided data into Rust memory."]
+            #[doc = ""]
+            #[doc = "# Safety"]
+            #[doc = ""]
+            #[doc = "`ptr` must be null (for the `None` variant of an `Option<Vec<_>>`), or point to the first of `len` elements."]
+            pub unsafe extern "C" fn [<ffi_array_ $t _init>](
+                ptr: *const $ffi,
+                len: isize,
+            ) -> [<FFIArray $t>] {
+                if ptr.is_null() {
+                    [<FFIArray $t>] {
+                        ptr: std::ptr::null(),
+                        len: 0,
+                        cap: 0
+                    }
+                } else {
+                    #[allow(clippy::cast_sign_loss)]
+                    let v = std::slice::from_raw_parts(ptr, len as usize).to_vec();
+                    let (ptr, len, cap) = $crate::allocator::export_vec(v);
+                    [<FFIArray $t>] { ptr, len, cap }
+                }
+            }
+
+            #[no_mangle]
+            #[doc = "Pass an FFI array to this method to allow Rust to reclaim ownership of the object so that it can be safely deallocated."]
+            #[doc = ""]
+            #[doc = "# Safety"]
+            #[doc = ""]
+            #[doc = "`array` must have been allocated by Rust, and must not be accessed after this call. It's safe to call this with a null `ptr`."]
+            pub unsafe extern "C" fn [<ffi_array_ $t _free>](array: [<FFIArray $t>]) {
+                if array.ptr.is_null() {
+                    return;
+                }
+                drop($crate::allocator::import_vec(array.ptr, array.len, array.cap));
+            }
+
+            impl From<&[$t]> for [<FFIArray $t>] {
+                fn from(slice: &[$t]) -> Self {
+                    slice.to_vec().into()
+                }
+            }
+
+            impl From<Option<&[$t]>> for [<FFIArray $t>] {
+                fn from(opt: Option<&[$t]>) -> Self {
+                    opt.map_or(
+                        Self {
+                            ptr: std::ptr::null(),
+                            len: 0,
+                            cap: 0,
+                        },
+                        |v| v.into(),
+                    )
+                }
+            }
+
+            impl From<Vec<$t>> for [<FFIArray $t>] {
+                fn from(v: Vec<$t>) -> Self {
+                    let v: Vec<$ffi> = v.into_iter().map($ffi::from).collect();
+                    let (ptr, len, cap) = $crate::allocator::export_vec(v);
+                    Self { ptr, len, cap }
+                }
+            }
+
+            impl From<[<FFIArray $t>]> for Vec<$ffi> {
+                fn from(array: [<FFIArray $t>]) -> Self {
+                    unsafe { $crate::allocator::import_vec(array.ptr, array.len, array.cap) }
+                }
+            }
+
+            impl From<[<FFIArray $t>]> for Option<Vec<$ffi>> {
+                fn from(array: [<FFIArray $t>]) -> Self {
+                    if array.ptr.is_null() {
+                        None
+                    } else {
+                        Some(Vec::from(array))
+                    }
+                }
+            }
+
+            #[no_mangle]
+            #[doc = "Initialize an optional `" $t "` from across the FFI boundary. When `has_value` is `false`, `value` is ignored and the return value is a null pointer."]
+            #[doc = ""]
+            #[doc = "# Safety"]
+            #[doc = ""]
+            #[doc = "If the returned pointer is not null, you must pass it to `option_" $t "_free` once you're finished with it."]
+            pub extern "C" fn [<option_ $t _init>](has_value: bool, value: $ffi) -> *const $ffi {
+                if has_value {
+                    $crate::allocator::export_box(value)
+                } else {
+                    std::ptr::null()
+                }
+            }
+
+            #[no_mangle]
+            #[doc = "Pass a pointer to an optional `" $t "` to allow Rust to reclaim the memory allocated for it."]
+            #[doc = ""]
+            #[doc = "# Safety"]
+            #[doc = ""]
+            #[doc = "`option` must have been allocated by Rust, and must not be accessed after this call. It's safe to call this with a null pointer."]
+            pub unsafe extern "C" fn [<option_ $t _free>](option: *const $ffi) {
+                if !option.is_null() {
+                    let _value = $crate::allocator::import_box(option);
+                }
+            }
+        }
+    );
+}
+
 /// Generates the following:
-/// 1. A repr(C) struct with a pointer to an array (whose elements are raw `Box<T>`), its
-/// length, and its capacity. These elements will be visible across the FFI boundary as opaque
-/// pointers, and they will not be deallocated until the struct is passed back to the matching free
-/// function (3).
+/// 1. An `OpaqueHandle` impl, which allocates each instance that's passed across the FFI in its own
+///    `Box` (or, if the type is prefixed with `shared`, in an `Arc`; see `ffi_core::handle`).
+/// 1. A repr(C) struct with a pointer to an array (whose elements are those raw handles), its
+///    length, and its capacity. These elements will be visible across the FFI boundary as opaque
+///    pointers, and they will not be deallocated until the struct is passed back to the matching
+///    free function (4).
 /// 1. `From` impls for converting between `&[T]` of those element types and this new struct.
 /// 1. A function for freeing an array of this type.
 ///
@@ -241,7 +460,7 @@ It's safe to call this with a null pointer.
 ///
 #[macro_export]
 macro_rules! declare_opaque_type_ffi {
-    ($($t:ident),*) => ($(
+    (@array $t:ident) => (
         $crate::paste! {
             #[doc = """
 An FFI-safe representation of a collection of opaque data structures for passing ownership of those
@@ -315,28 +534,54 @@ simplify memory management.
                 } else {
                     let mut v = vec![];
                     for i in 0..len {
-                        let e = *ptr.offset(i);
-                        v.push((&*e).clone());
+                        v.push(<$t as $crate::handle::OpaqueHandle>::retain_handle(*ptr.offset(i)));
+                    }
+                    v.into()
+                }
+            }
+
+            #[doc = """
+Initialize an `FFIArray*` from across the FFI boundary, taking ownership of the elements that `ptr`
+points to instead of copying them.
+
+Use this instead of `ffi_array_*_init` when the elements were created for the sole purpose of
+building this array (for example, by cloning native values), so that the elements aren't copied a
+second time and the originals don't need to be freed separately.
+
+# Safety
+
+The pointer you send must point to the first element of an array whose elements are themselves
+pointers to Rust-owned instances of opaque types. Rust takes ownership of each of those instances,
+so you **must not** use or free them after calling this. The array that holds the pointers is still
+owned by the caller (it's copied).
+
+If `ptr` is a null pointer, this will create an array wrapper with a length and capacity of `0`,
+and a null pointer; this expresses the `None` variant of an `Option<Vec<T>>`.
+            """]
+            #[no_mangle]
+            pub unsafe extern "C" fn [<ffi_array_ $t _init_consuming>](
+                ptr: *const *const $t,
+                len: isize,
+            ) -> [<FFIArray $t>] {
+                if ptr.is_null() {
+                    [<FFIArray $t>] {
+                        ptr: std::ptr::null(),
+                        len: 0,
+                        cap: 0
                     }
-                    v.as_slice().into()
+                } else {
+                    #[allow(clippy::cast_sign_loss)]
+                    let v = std::slice::from_raw_parts(ptr, len as usize).to_vec();
+                    v.into()
                 }
             }
 
             impl From<&[$t]> for [<FFIArray $t>] {
                 fn from(slice: &[$t]) -> Self {
-                    let v: std::mem::ManuallyDrop<Vec<*const $t>> = std::mem::ManuallyDrop::new(
-                        slice.iter()
-                            .map(|e| {
-                                let boxed: *const $t = Box::into_raw(Box::new(e.clone()));
-                                boxed
-                            })
-                            .collect()
-                    );
-                    let len = v.len();
-                    let ptr = v.as_ptr();
-                    let cap = v.capacity();
-
-                    Self { ptr, len, cap }
+                    let v: Vec<*const $t> = slice.iter()
+                        .map(|e| <$t as $crate::handle::OpaqueHandle>::into_handle(e.clone()))
+                        .collect();
+                    v.into()
                 }
             }
 
@@ -353,12 +598,22 @@ simplify memory management.
                 }
             }
 
+            impl From<Vec<*const $t>> for [<FFIArray $t>] {
+                /// Moves the element pointers in `v` into an array, which takes ownership of the
+                /// instances they point to.
+                ///
+                fn from(v: Vec<*const $t>) -> Self {
+                    let (ptr, len, cap) = $crate::allocator::export_vec(v);
+                    Self { ptr, len, cap }
+                }
+            }
+
             impl From<[<FFIArray $t>]> for Vec<$t> {
                 fn from(array: [<FFIArray $t>]) -> Self {
                     unsafe {
-                        Vec::from_raw_parts(array.ptr as *mut *const $t, array.len, array.cap)
+                        $crate::allocator::import_vec(array.ptr, array.len, array.cap)
                             .into_iter()
-                            .map(|e| *Box::from_raw(e as *mut $t))
+                            .map(|e| <$t as $crate::handle::OpaqueHandle>::from_handle(e))
                             .collect()
                     }
                 }
@@ -402,8 +657,16 @@ unallocated memory if, for example, you pass an array that represents the `None`
                 if array.ptr.is_null() {
                     return;
                 }
-                drop(Vec::from_raw_parts(array.ptr as *mut *const $t, array.len, array.cap));
+                drop($crate::allocator::import_vec(array.ptr, array.len, array.cap));
             }
         }
+    );
+    (shared $($t:ident),*) => ($(
+        $crate::declare_shared_handle!($t);
+        $crate::declare_opaque_type_ffi!(@array $t);
+    )*);
+    ($($t:ident),*) => ($(
+        impl $crate::handle::OpaqueHandle for $t {}
+        $crate::declare_opaque_type_ffi!(@array $t);
     )*);
 }
@@ -0,0 +1,231 @@
+//!
+//! FFI-safe representations of `Vec`s and `Option`s of values that are already wrapped in a `Vec`
+//! or an `Option` themselves, like `Vec<Vec<T>>`, `Vec<Option<T>>`, and `Option<Option<T>>`.
+//!
+//! The innermost wrapper is exposed the way it would be on its own (as an `FFIArray*`, or a
+//! nullable pointer for an `Option`), and each additional wrapper around it is exposed as an
+//! `FFINestedArray` or an `FFINestedOption` of the FFI type inside of it. These are generic, so
+//! `cbindgen` generates a C struct for each combination that's used (like
+//! `FFINestedArray_FFIArrayf64` for a `Vec<Vec<f64>>`).
+//!
+
+use crate::allocator::{allocate_array, export_vec, import_vec, release_array};
+use std::{
+    alloc::{handle_alloc_error, Layout},
+    ffi::c_void,
+};
+
+/// An array of values of the FFI type `T`, where `T` is itself an array or an optional.
+///
+/// Like an `FFIArray*`, this expresses the `None` variant of an optional array with a null
+/// pointer and a length and capacity of `0`.
+///
+/// # Safety
+///
+/// The buffer must be reclaimed by Rust. Rust takes care of that when an argument is passed in, but
+/// the consumer must release the buffer of a returned array with `ffi_nested_array_free` once it has
+/// taken ownership of (or released) each of its elements.
+///
+#[repr(C)]
+#[derive(Debug)]
+pub struct FFINestedArray<T> {
+    /// Pointer to the first element in the array.
+    pub ptr: *const T,
+    /// The length of (i.e. the number of elements in) this array.
+    pub len: usize,
+    /// The capacity with which this array was allocated.
+    pub cap: usize,
+}
+
+impl<T> Default for FFINestedArray<T> {
+    /// An array with a null pointer, which expresses `None` (or an empty array, where it isn't
+    /// optional).
+    ///
+    fn default() -> Self {
+        Self {
+            ptr: std::ptr::null(),
+            len: 0,
+            cap: 0,
+        }
+    }
+}
+
+impl<T> From<Vec<T>> for FFINestedArray<T> {
+    fn from(v: Vec<T>) -> Self {
+        let (ptr, len, cap) = export_vec(v);
+        Self { ptr, len, cap }
+    }
+}
+
+impl<T> From<Option<Vec<T>>> for FFINestedArray<T> {
+    fn from(v: Option<Vec<T>>) -> Self {
+        v.map_or_else(Self::default, Self::from)
+    }
+}
+
+impl<T> From<FFINestedArray<T>> for Option<Vec<T>> {
+    fn from(array: FFINestedArray<T>) -> Self {
+        if array.ptr.is_null() {
+            None
+        } else {
+            Some(unsafe { import_vec(array.ptr, array.len, array.cap) })
+        }
+    }
+}
+
+impl<T> From<FFINestedArray<T>> for Vec<T> {
+    fn from(array: FFINestedArray<T>) -> Self {
+        Option::from(array).unwrap_or_default()
+    }
+}
+
+/// An optional value of the FFI type `T`, where `T` is itself an array or an optional (so it can't
+/// express another level of optionality with a null pointer).
+///
+/// When `is_some` is false, `value` is a placeholder (like a null pointer) that doesn't need to be
+/// released.
+///
+#[repr(C)]
+#[derive(Debug)]
+pub struct FFINestedOption<T> {
+    /// Whether this is the `Some` variant of the option.
+    pub is_some: bool,
+    /// The value of the option, if `is_some` is true.
+    pub value: T,
+}
+
+impl<T> FFINestedOption<T> {
+    /// The `Some` variant, containing `value`.
+    ///
+    pub const fn some(value: T) -> Self {
+        Self {
+            is_some: true,
+            value,
+        }
+    }
+
+    /// The `None` variant, with `placeholder` in place of a value.
+    ///
+    pub const fn none(placeholder: T) -> Self {
+        Self {
+            is_some: false,
+            value: placeholder,
+        }
+    }
+
+    /// Converts this into an `Option`, discarding the placeholder if this is `None`.
+    ///
+    #[allow(clippy::missing_const_for_fn)]
+    pub fn into_option(self) -> Option<T> {
+        if self.is_some {
+            Some(self.value)
+        } else {
+            None
+        }
+    }
+}
+
+fn element_layout(size: usize, align: usize) -> Layout {
+    Layout::from_size_align(size, align).unwrap_or_else(|_| handle_alloc_error(Layout::new::<u8>()))
+}
+
+/// Initialize the buffer of an `FFINestedArray*` from across the FFI boundary.
+///
+/// This moves `len` elements of `size` bytes (aligned to `align`) from `ptr` into Rust memory. Use
+/// the result as the `ptr` of the array, with `len` as both its length and its capacity.
+///
+/// # Safety
+///
+/// `ptr` must point to `len` initialized elements of the array's element type, and `size` and
+/// `align` must be the size (or stride) and alignment of that type. Ownership of the elements is
+/// transferred to the array, so they must have been allocated by Rust (i.e., they must have been
+/// initialized with the matching `*_init` functions), and they must not be used or freed afterward.
+///
+#[no_mangle]
+pub unsafe extern "C" fn ffi_nested_array_init(
+    ptr: *const c_void,
+    len: usize,
+    size: usize,
+    align: usize,
+) -> *const c_void {
+    let buffer = allocate_array(len, element_layout(size, align));
+    if len != 0 && size != 0 {
+        std::ptr::copy_nonoverlapping(ptr.cast::<u8>(), buffer, size * len);
+    }
+    buffer.cast::<c_void>()
+}
+
+/// Release the buffer of an `FFINestedArray*` that was returned by Rust, without releasing its
+/// elements.
+///
+/// This takes the array's `ptr`, `len`, and `cap`, along with the `size` (or stride) and
+/// `align`ment of its element type.
+///
+/// # Safety
+///
+/// The consumer must already have taken ownership of (or released) each element, and must not
+/// access the array after this call. It's safe to call this with a null `ptr`.
+///
+#[no_mangle]
+pub unsafe extern "C" fn ffi_nested_array_free(
+    ptr: *const c_void,
+    len: usize,
+    cap: usize,
+    size: usize,
+    align: usize,
+) {
+    if ptr.is_null() {
+        return;
+    }
+    release_array(ptr.cast::<u8>(), len, cap, element_layout(size, align));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FFIArrayf64;
+    use std::mem::{align_of, size_of, ManuallyDrop};
+
+    #[test]
+    fn nested_arrays_round_trip() {
+        let rows = vec![vec![1.0, 2.0], vec![], vec![3.0]];
+        let arrays: Vec<FFIArrayf64> = rows.iter().map(|row| (&**row).into()).collect();
+        let nested = FFINestedArray::from(arrays);
+        assert_eq!(nested.len, 3);
+        let round_trip: Vec<Vec<f64>> = Vec::from(nested).into_iter().map(Vec::from).collect();
+        assert_eq!(round_trip, rows);
+
+        let none = FFINestedArray::<FFIArrayf64>::from(None);
+        assert!(none.ptr.is_null());
+        assert_eq!(
+            Option::<Vec<FFIArrayf64>>::from(none).map(|v| v.len()),
+            None
+        );
+    }
+
+    #[test]
+    fn nested_arrays_from_the_consumer() {
+        let elements = ManuallyDrop::new(vec![Some(1_u32), None, Some(3)]);
+        let ptr = unsafe {
+            ffi_nested_array_init(
+                elements.as_ptr().cast(),
+                elements.len(),
+                size_of::<Option<u32>>(),
+                align_of::<Option<u32>>(),
+            )
+        };
+        let nested = FFINestedArray::<Option<u32>> {
+            ptr: ptr.cast(),
+            len: 3,
+            cap: 3,
+        };
+        assert_eq!(Vec::from(nested), vec![Some(1), None, Some(3)]);
+        drop(ManuallyDrop::into_inner(elements));
+    }
+
+    #[test]
+    fn nested_options() {
+        assert_eq!(FFINestedOption::some(3).into_option(), Some(3));
+        assert_eq!(FFINestedOption::none(0).into_option(), None);
+    }
+}
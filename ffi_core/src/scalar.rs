@@ -0,0 +1,144 @@
+//!
+//! FFI-safe representations of the scalar types that don't have a C equivalent.
+//!
+//! 128-bit integers are split into a pair of 64-bit words, a `char` is exposed as the `u32` value
+//! of its Unicode scalar (which is checked when it's converted back, see `CheckedFromFFI`), and a
+//! `Uuid` is exposed as its 16 bytes.
+//!
+
+use crate::datetime::CheckedFromFFI;
+use uuid::Uuid;
+
+/// An FFI-safe `u128`, split into its high and low 64 bits.
+///
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FFIu128 {
+    /// The most significant 64 bits.
+    pub high: u64,
+    /// The least significant 64 bits.
+    pub low: u64,
+}
+
+impl From<u128> for FFIu128 {
+    #[allow(clippy::cast_possible_truncation)]
+    fn from(value: u128) -> Self {
+        Self {
+            high: (value >> 64) as u64,
+            low: value as u64,
+        }
+    }
+}
+
+impl From<FFIu128> for u128 {
+    fn from(value: FFIu128) -> Self {
+        (Self::from(value.high) << 64) | Self::from(value.low)
+    }
+}
+
+/// An FFI-safe `i128`, split into its (signed) high and (unsigned) low 64 bits.
+///
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FFIi128 {
+    /// The most significant 64 bits, including the sign.
+    pub high: i64,
+    /// The least significant 64 bits.
+    pub low: u64,
+}
+
+impl From<i128> for FFIi128 {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn from(value: i128) -> Self {
+        Self {
+            high: (value >> 64) as i64,
+            low: value as u64,
+        }
+    }
+}
+
+impl From<FFIi128> for i128 {
+    fn from(value: FFIi128) -> Self {
+        (Self::from(value.high) << 64) | Self::from(value.low)
+    }
+}
+
+/// An FFI-safe `char`, as the `u32` value of its Unicode scalar.
+///
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FFIchar(pub u32);
+
+impl From<char> for FFIchar {
+    fn from(value: char) -> Self {
+        Self(value.into())
+    }
+}
+
+impl CheckedFromFFI for char {
+    type FFIValue = FFIchar;
+
+    fn checked_from_ffi(value: &FFIchar) -> Result<Self, String> {
+        Self::from_u32(value.0)
+            .ok_or_else(|| format!("Invalid Unicode scalar value: {:#X}.", value.0))
+    }
+}
+
+/// An FFI-safe `Uuid`, as its 16 bytes in big-endian order (the same layout as C's `uuid_t`).
+///
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FFIUuid(pub [u8; 16]);
+
+impl From<Uuid> for FFIUuid {
+    fn from(value: Uuid) -> Self {
+        Self(*value.as_bytes())
+    }
+}
+
+impl From<FFIUuid> for Uuid {
+    fn from(value: FFIUuid) -> Self {
+        Self::from_bytes(value.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wide_integers_round_trip() {
+        for value in [0, 1, u128::from(u64::MAX) + 1, u128::MAX] {
+            assert_eq!(u128::from(FFIu128::from(value)), value);
+        }
+        for value in [0, -1, i128::from(i64::MIN) - 1, i128::MIN, i128::MAX] {
+            assert_eq!(i128::from(FFIi128::from(value)), value);
+        }
+        assert_eq!(
+            FFIu128::from(u128::MAX << 64),
+            FFIu128 {
+                high: u64::MAX,
+                low: 0
+            }
+        );
+    }
+
+    #[test]
+    fn chars_round_trip() {
+        assert_eq!(FFIchar::from('🌽'), FFIchar(0x1F33D));
+        assert_eq!(char::checked_from_ffi(&FFIchar::from('é')), Ok('é'));
+        assert_eq!(
+            char::checked_from_ffi(&FFIchar(0xD800)),
+            Err("Invalid Unicode scalar value: 0xD800.".to_string())
+        );
+    }
+
+    #[test]
+    fn uuids_round_trip() {
+        let uuid = Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+        let ffi = FFIUuid::from(uuid);
+        assert_eq!(ffi.0[0], 0x67);
+        assert_eq!(ffi.0[15], 0xc8);
+        assert_eq!(Uuid::from(ffi), uuid);
+    }
+}
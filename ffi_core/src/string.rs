@@ -4,9 +4,10 @@
 
 #![allow(clippy::module_name_repetitions)]
 
+use crate::allocator::{export_c_string, export_vec, import_c_string, import_vec};
 use std::{
+    convert::TryFrom,
     ffi::{CStr, CString},
-    mem::ManuallyDrop,
     os::raw::c_char,
 };
 use uuid::Uuid;
@@ -31,8 +32,8 @@ use uuid::Uuid;
 /// # Safety
 ///
 /// This will need to be brought back into rust ownership in two ways; first, the vec needs to
-/// be reclaimed with `Vec::from_raw_parts`; second, each `CString` element of the vec will need
-/// to be reclaimed with `CString::from_raw`. Pass this `FFIArrayString` to
+/// be reclaimed with `allocator::import_vec`; second, each `CString` element of the vec will need
+/// to be reclaimed with `allocator::import_c_string`. Pass this `FFIArrayString` to
 /// `free_ffi_array_string` when you're done with it (i.e., when you've copied it into native
 /// memory, displayed it, whatever you're doing on the other side of the FFI boundary) so we can
 /// take care of those steps.
@@ -42,12 +43,12 @@ use uuid::Uuid;
 /// Note that creating this struct requires creating new `CString`s from the original vec, which
 /// means:
 /// 1. There's a cost to getting an array of strings (just like getting a single string, we have to
-/// allocate a `CString` for each element in the original collection).
+///    allocate a `CString` for each element in the original collection).
 /// 2. The lifetime of this struct is unrelated to the lifetime of whatever may hold the array in
-/// Rust. That's why this struct has to be returned to Rust to clean up, and why it can outlive the
-/// object it came from. This lends some flexibility for optimizing large arrays; clients are free
-/// to hold on to this struct indefinitely, reading from it as needed instead of copying the array
-/// contents into native memory up front.
+///    Rust. That's why this struct has to be returned to Rust to clean up, and why it can outlive
+///    the object it came from. This lends some flexibility for optimizing large arrays; clients are
+///    free to hold on to this struct indefinitely, reading from it as needed instead of copying the
+///    array contents into native memory up front.
 ///
 #[repr(C)]
 #[allow(missing_copy_implementations)]
@@ -110,22 +111,15 @@ impl<T: ToString> From<&[T]> for FFIArrayString {
     /// Convenience for converting any string-like vec into an `FFIArrayString`.
     ///
     fn from(slice: &[T]) -> Self {
-        let v: ManuallyDrop<Vec<*const c_char>> = ManuallyDrop::new(
-            slice
-                .iter()
-                .map(|s| {
-                    let c_string = try_or_set_error!(
-                        CString::new(s.to_string()).map(std::ffi::CString::into_raw)
-                    );
-                    c_string
-                })
-                .collect(),
-        );
-        let len = v.len();
-        let ptr = v.as_ptr();
-        let cap = v.capacity();
-
-        Self { ptr, len, cap }
+        let v: Vec<*const c_char> = slice
+            .iter()
+            .map(|s| {
+                let c_string: *const c_char =
+                    try_or_set_error!(CString::new(s.to_string()).map(export_c_string));
+                c_string
+            })
+            .collect();
+        v.into()
     }
 }
 
@@ -144,6 +138,16 @@ impl<T: ToString> From<Option<&[T]>> for FFIArrayString {
     }
 }
 
+impl From<Vec<*const c_char>> for FFIArrayString {
+    /// Moves the string pointers in `v` into an `FFIArrayString`, which takes ownership of the
+    /// strings they point to. Elements may be null.
+    ///
+    fn from(v: Vec<*const c_char>) -> Self {
+        let (ptr, len, cap) = export_vec(v);
+        Self { ptr, len, cap }
+    }
+}
+
 #[allow(clippy::use_self)]
 impl From<FFIArrayString> for Vec<String> {
     fn from(array: FFIArrayString) -> Self {
@@ -151,14 +155,9 @@ impl From<FFIArrayString> for Vec<String> {
             // Create a vec from the data in the array, but don't let Rust drop it. That will happen
             // when the consumer tells us they're done with the array by calling
             // `free_ffi_array_string`. Clone it into one that we can use safely.
-            Vec::from_raw_parts(array.ptr as *mut *const c_char, array.len, array.cap)
+            import_vec(array.ptr, array.len, array.cap)
                 .into_iter()
-                .map(|s| {
-                    CString::from_raw(s as *mut c_char)
-                        .to_str()
-                        .unwrap()
-                        .to_string()
-                })
+                .map(|s| import_c_string(s).to_str().unwrap().to_string())
                 .collect()
         }
     }
@@ -174,17 +173,21 @@ impl From<FFIArrayString> for Option<Vec<String>> {
     }
 }
 
-#[allow(clippy::use_self)]
-impl From<FFIArrayString> for Vec<Uuid> {
-    fn from(array: FFIArrayString) -> Self {
-        unsafe {
-            Vec::from_raw_parts(array.ptr as *mut *const c_char, array.len, array.cap)
+/// Parses each string in the array as a `Uuid`, failing with the first string that can't be parsed.
+///
+/// Every element is reclaimed whether or not it parses, so the array is always consumed.
+///
+impl TryFrom<FFIArrayString> for Vec<Uuid> {
+    type Error = uuid::Error;
+
+    fn try_from(array: FFIArrayString) -> Result<Self, Self::Error> {
+        let parsed: Vec<Result<Uuid, uuid::Error>> = unsafe {
+            import_vec(array.ptr, array.len, array.cap)
                 .into_iter()
-                .map(|s| {
-                    Uuid::parse_str(CString::from_raw(s as *mut c_char).to_str().unwrap()).unwrap()
-                })
+                .map(|s| Uuid::parse_str(&import_c_string(s).to_string_lossy()))
                 .collect()
-        }
+        };
+        parsed.into_iter().collect()
     }
 }
 
@@ -193,12 +196,14 @@ impl From<FFIArrayString> for Vec<Uuid> {
 /// between `None` and `[]` to mean nothing vs everything. Avoid Option<Vec<T>> if you can, but
 /// it's sometimes required to describe service resources in `agrian_types`.
 ///
-impl From<FFIArrayString> for Option<Vec<Uuid>> {
-    fn from(array: FFIArrayString) -> Self {
+impl TryFrom<FFIArrayString> for Option<Vec<Uuid>> {
+    type Error = uuid::Error;
+
+    fn try_from(array: FFIArrayString) -> Result<Self, Self::Error> {
         if array.ptr.is_null() {
-            None
+            Ok(None)
         } else {
-            Some(Vec::from(array))
+            Vec::try_from(array).map(Some)
         }
     }
 }
@@ -225,7 +230,7 @@ pub unsafe extern "C" fn ffi_array_string_free(array: FFIArrayString) {
     if array.ptr.is_null() {
         return;
     }
-    let v = Vec::from_raw_parts(array.ptr as *mut *const c_char, array.len, array.cap);
+    let v = import_vec(array.ptr, array.len, array.cap);
     for s in v {
         free_rust_string(s);
     }
@@ -241,7 +246,7 @@ macro_rules! ffi_string {
     ($string:expr) => {{
         $crate::error::clear_last_err_msg();
         let c_string = $crate::try_or_set_error!(
-            std::ffi::CString::new($string).map(std::ffi::CString::into_raw)
+            std::ffi::CString::new($string).map($crate::allocator::export_c_string)
         );
         c_string
     }};
@@ -253,13 +258,12 @@ macro_rules! ffi_string {
 ///
 /// `ptr` is unchecked and will be dereferenced, so it must not be null.
 ///
-/// # Panics
+/// # Errors
 ///
-/// This will panic if we cannot parse the string at `ptr` as a `Uuid`.
+/// Returns an error if the string at `ptr` can't be parsed as a `Uuid`.
 ///
-#[must_use]
-pub unsafe fn uuid_from_c(ptr: *const c_char) -> Uuid {
-    Uuid::parse_str(&CStr::from_ptr(ptr).to_string_lossy()).unwrap()
+pub unsafe fn uuid_from_c(ptr: *const c_char) -> Result<Uuid, uuid::Error> {
+    Uuid::parse_str(&CStr::from_ptr(ptr).to_string_lossy())
 }
 
 /// Converts an FFI string (a `*const c_char`) to a `String`.
@@ -293,7 +297,7 @@ pub unsafe extern "C" fn free_rust_string(string: *const c_char) {
     if string.is_null() {
         return;
     }
-    drop(CString::from_raw(string as *mut c_char));
+    drop(import_c_string(string));
 }
 
 #[cfg(test)]
@@ -326,4 +330,20 @@ mod tests {
         // consumer, and simultaneously reclaiming the memory occupied by the FFI type).
         assert_eq!(v, v2);
     }
+
+    #[test]
+    fn invalid_uuid_strings_are_errors() {
+        let id = Uuid::new_v4().to_string();
+        let valid = FFIArrayString::from(&*vec![id.as_str()]);
+        assert_eq!(
+            Vec::<Uuid>::try_from(valid).unwrap(),
+            vec![Uuid::parse_str(&id).unwrap()]
+        );
+
+        let invalid = FFIArrayString::from(&*vec![id.as_str(), "not a uuid"]);
+        assert!(Vec::<Uuid>::try_from(invalid).is_err());
+
+        let c_string = CString::new("not a uuid").unwrap();
+        assert!(unsafe { uuid_from_c(c_string.as_ptr()) }.is_err());
+    }
 }
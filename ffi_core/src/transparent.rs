@@ -0,0 +1,23 @@
+//!
+//! Support for newtypes with the `ffi(transparent)` attribute (like `pub struct Acres(f64)`), which
+//! are exposed just like the type they wrap instead of as an opaque type.
+//!
+//! The derived FFI for other types converts between a transparent type and its inner value with
+//! `Transparent`, so that it doesn't need access to the newtype's (possibly private) field.
+//!
+
+/// Converts between a transparent newtype and the value it wraps.
+///
+pub trait Transparent {
+    /// The type that's wrapped in this newtype, which is what's exposed over the FFI.
+    ///
+    type Inner;
+
+    /// Wraps `inner` in this newtype.
+    ///
+    fn from_inner(inner: Self::Inner) -> Self;
+
+    /// Returns a reference to the wrapped value.
+    ///
+    fn inner(&self) -> &Self::Inner;
+}
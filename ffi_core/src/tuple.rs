@@ -0,0 +1,95 @@
+//!
+//! FFI-safe representations of tuples, like `(f64, f64)` or `(String, u32)`.
+//!
+//! C doesn't have tuples, so each tuple is exposed as a `repr(C)` struct with a field for each of
+//! its elements (`_0`, `_1`, and so on), each of which is exposed the way it would be on its own
+//! (so a `String` element is a `*const c_char`, and an element of another FFI type is a pointer to
+//! it). These are generic, so `cbindgen` generates a C struct for each combination of element types
+//! that's used (like `FFITuple2_f64__f64` for an `(f64, f64)`).
+//!
+//! The field names follow Rust's tuple fields (`.0`, `.1`, and so on), and they're part of the C
+//! and Swift interfaces, so these structs allow `clippy::pub_underscore_fields`.
+//!
+//! The tuple owns its elements, so it should be released by converting it back into a native tuple
+//! (or by releasing each element the way it would be released on its own).
+//!
+
+/// A tuple of two elements.
+///
+#[repr(C)]
+#[derive(Debug)]
+#[allow(clippy::pub_underscore_fields)]
+pub struct FFITuple2<T0, T1> {
+    /// The first element.
+    pub _0: T0,
+    /// The second element.
+    pub _1: T1,
+}
+
+/// A tuple of three elements.
+///
+#[repr(C)]
+#[derive(Debug)]
+#[allow(clippy::pub_underscore_fields)]
+pub struct FFITuple3<T0, T1, T2> {
+    /// The first element.
+    pub _0: T0,
+    /// The second element.
+    pub _1: T1,
+    /// The third element.
+    pub _2: T2,
+}
+
+/// A tuple of four elements.
+///
+#[repr(C)]
+#[derive(Debug)]
+#[allow(clippy::pub_underscore_fields)]
+pub struct FFITuple4<T0, T1, T2, T3> {
+    /// The first element.
+    pub _0: T0,
+    /// The second element.
+    pub _1: T1,
+    /// The third element.
+    pub _2: T2,
+    /// The fourth element.
+    pub _3: T3,
+}
+
+/// A tuple of five elements.
+///
+#[repr(C)]
+#[derive(Debug)]
+#[allow(clippy::pub_underscore_fields)]
+pub struct FFITuple5<T0, T1, T2, T3, T4> {
+    /// The first element.
+    pub _0: T0,
+    /// The second element.
+    pub _1: T1,
+    /// The third element.
+    pub _2: T2,
+    /// The fourth element.
+    pub _3: T3,
+    /// The fifth element.
+    pub _4: T4,
+}
+
+/// A tuple of six elements.
+///
+#[repr(C)]
+#[derive(Debug)]
+#[allow(clippy::pub_underscore_fields)]
+pub struct FFITuple6<T0, T1, T2, T3, T4, T5> {
+    /// The first element.
+    pub _0: T0,
+    /// The second element.
+    pub _1: T1,
+    /// The third element.
+    pub _2: T2,
+    /// The fourth element.
+    pub _3: T3,
+    /// The fifth element.
+    pub _4: T4,
+    /// The sixth element.
+    pub _5: T5,
+}
@@ -56,19 +56,62 @@
 //!
 //! ## Supported types:
 //! 1. `String`.
-//! 1. `Uuid`.
-//! 1. Numeric primitives (`u8` through `f64`).
-//! 1. Custom `repr(C)` types.
+//! 1. `Uuid` (exposed as the 16-byte `FFIUuid`, and to Swift as `UUID`).
+//! 1. Numeric primitives (`u8` through `f64`, plus `usize`, `isize`, `u128`, and `i128`).
+//! 1. `char`.
+//! 1. `NaiveDateTime` and `DateTime<Utc>` (exposed to Swift as `Date`).
+//! 1. `NaiveDate`, `NaiveTime`, and `DateTime<FixedOffset>` (exposed to Swift as `DateComponents`,
+//!    which keeps the offset of a `DateTime<FixedOffset>` as its `timeZone`).
+//! 1. `chrono::Duration` and `std::time::Duration` (exposed to Swift as a `TimeInterval`). Since
+//!    other types share their names, these are opt-in: mark fields `ffi(duration)`, and list the
+//!    types in `duration_types(...)` on `expose_impl` and `expose_fn`.
+//! 1. Custom `repr(C)` types. `repr(C)` enums (including the `{Type}Type` tag enum of an enum with
+//!    associated values) get `{type}_all_variants`, `{type}_variant_name`, and
+//!    `{type}_variant_from_name`, which Swift exposes as a `CaseIterable` conformance,
+//!    `variantName`, and `init?(variantName:)`.
+//! 1. Enums without associated values that have an integer `repr` (like `repr(u8)`), with implicit
+//!    or integer literal discriminants (like `Archived = 7`). These are exposed like `repr(C)`
+//!    enums, but Swift gets an enum with the discriminants as its raw values, and `{type}_from_raw`
+//!    checks a raw value before converting it.
 //! 1. Custom non-`repr(C)` types.
 //! 1. Typealiases over any of the above.
 //! 1. Typealiases defined in remote crates (see `Remote types` section).
 //! 1. Remote types with custom FFI implementations (see `Remote types` section).
-//! 1. Enums whose variants have one or zero fields (additional fields need more work in
-//! [this GitHub issue](https://github.com/agrian-inc/ffi_common/issues/22)).
+//! 1. Enums with unit, tuple, and struct-like variants (like `Rect { width: f64, height: f64 }`).
+//!    Each field of a variant gets its own getter, and each variant gets an initializer that takes
+//!    all of its fields. Named fields are labeled associated values in the Swift enum. Calling a
+//!    getter on the wrong variant sets the last error message instead of panicking, and
+//!    `{type}_try_as_{variant}` returns all of a variant's fields in a single payload (or null, for
+//!    the wrong variant).
 //! 1. A few specific generics:
-//!   1. `Option<T>` where `T` is any supported type (but not nested `Option<Option<T>>`).
-//!   1. `Vec<T>` where `T` is any supported type (but not nested `Vec<Vec<T>>`).
-//!   1. `Option<Vec<T>>` where `T` is any supported type (but no additional nesting).
+//!   1. `Option<T>` where `T` is any supported type, including another `Option` or a `Vec` (like
+//!      `Option<Option<T>>`).
+//!   1. `Vec<T>` where `T` is any supported type, including another `Vec` or an `Option` (like
+//!      `Vec<Vec<T>>` and `Vec<Option<T>>`).
+//!   1. `Option<Vec<T>>` where `T` is any supported type, including another `Vec` or an `Option`.
+//!   1. `HashSet<T>` and `BTreeSet<T>` where `T` is any supported type that isn't an `Option` or
+//!      another collection (exposed as an `FFIArray*` of the elements, and to Swift as a `Set`),
+//!      and `Option`s of them.
+//!   1. `HashMap<K, V>` and `BTreeMap<K, V>` where `K` and `V` are any supported types that aren't
+//!      an `Option` or another collection (exposed as an `FFIMap` of parallel key and value arrays,
+//!      and to Swift as a `Dictionary`). Optional maps aren't supported; use an empty map instead.
+//!      These are recognized by name, so import them rather than writing out their paths. Keys and
+//!      set elements must be `Hashable` on the Swift side.
+//! 1. Tuples of two to six supported types (exposed as an `FFITuple*` of the elements, and to Swift
+//!    as a tuple), and `Option`s and `Vec`s of them. Tuples can't be returned in a `Result`.
+//! 1. Fixed-size arrays (like `[f64; 3]`) of any supported type that isn't an `Option`, another
+//!    collection, or a tuple, and `Option`s of them. Arrays of raw types are passed by value in an
+//!    `FFIFixedArray`, and other arrays are exposed as an `FFIArray*` (and to Swift as an array).
+//!    The length must be an integer literal.
+//! 1. `Box`, `Rc`, and `Arc` around other FFI types and `str` (like `Box<Self>` or `Arc<str>`), and
+//!    `Option`s and `Vec`s of them. These are exposed just like the type they point to, so they can
+//!    be used for recursive types.
+//! 1. Newtypes with the `ffi(transparent)` attribute (see `Transparent newtypes` section), and
+//!    `Option`s and `Vec`s of them.
+//! 1. Bit-flag newtypes with the `ffi(flags(...))` attribute (see `Bit-flag types` section), and
+//!    `Option`s and `Vec`s of them.
+//! 1. Instantiations of generic structs that are listed with `ffi(instantiate(...))` (see `Generic
+//!    structs` section).
 //!
 //! ## Using `ffi_derive`
 //!
@@ -118,6 +161,150 @@
 //! }
 //! ```
 //!
+//! ## Borrowing collection fields
+//!
+//! Every getter copies the field's data into memory owned by the consumer, which gets expensive for
+//! large primitive collections (like geometry or sensor buffers). Marking a `Vec` field of
+//! primitive or `raw` elements with `ffi(borrow)` generates an additional
+//! `get_borrowed_{type}_{field}` function that returns an `FFISlice*` pointing into the struct's
+//! own buffer instead. The slice is only valid while the struct is alive; the generated consumer
+//! wraps it in a `BorrowedBuffer` (or `Data`, for `Vec<u8>`) that retains its owner.
+//! ```ignore
+//! #[derive(Clone, Debug, ffi_derive::FFI)]
+//! pub struct SensorReading {
+//!     #[ffi(borrow)]
+//!     pub samples: Vec<f64>,
+//!     #[ffi(borrow)]
+//!     pub image: Vec<u8>,
+//! }
+//! ```
+//!
+//! `ffi(borrow)` also works on fields (and optional fields) of other FFI types, in which case the
+//! borrowed getter returns a non-owning pointer to the field (or null, for `None`). The generated
+//! consumer exposes these as `{field}_borrowed`, which wraps the pointer without freeing it and
+//! retains the parent, so walking a chain of nested structs only copies the leaf values.
+//!
+//! ## Skipped, read-only, and renamed fields
+//!
+//! Fields that consumers shouldn't see (like caches) can be marked with `ffi(skip)`, which leaves
+//! them out of the FFI entirely. Fields that consumers can read but shouldn't set can be marked
+//! with `ffi(readonly)`, which keeps their getters but leaves them out of the memberwise
+//! initializer. In both cases, the initializer fills in the field with `Default::default()`, or by
+//! calling the function named by `default`. `ffi(rename = "...")` exposes a field under a different
+//! name, in both its getters and the consumer. These attributes are only supported on struct
+//! fields, except for `rename`, which also works on enum variant fields.
+//! ```ignore
+//! #[derive(Clone, Debug, ffi_derive::FFI)]
+//! pub struct Document {
+//!     #[ffi(rename = "title")]
+//!     internal_title: String,
+//!     #[ffi(readonly, default = "initial_revision")]
+//!     revision: u32,
+//!     #[ffi(skip)]
+//!     cached_length: Cell<Option<usize>>,
+//! }
+//! ```
+//!
+//! ## Lazy collection access
+//!
+//! The getter for a `Vec` of other FFI types clones every element, even if the consumer only looks
+//! at a few of them. For these fields, we also generate `get_{type}_{field}_count` and
+//! `get_{type}_{field}_at` functions, which return the number of elements and a clone of a single
+//! element (or null, with the last error message set, if the index is out of bounds). The
+//! generated consumer exposes these as a `{field}_lazy` `LazyFFICollection`, which only fetches the
+//! elements that are accessed. `Option<Vec>` fields get the same functions (prefixed with
+//! `get_optional_`), and a `None` collection has no elements.
+//!
+//! ## Arenas
+//!
+//! Results that contain strings, arrays, or other FFI types normally have to be freed piece by
+//! piece. Structs marked with `ffi(arena)` (and methods listed in an impl's `arena(...)` attribute)
+//! also get `_in_arena` variants, which take a `*mut FFIArena` from `ffi_arena_init` and transfer
+//! ownership of the result to it. Everything returned into an arena is released by a single call
+//! to `ffi_arena_free`, so the consumer must not free those values individually (or use them after
+//! the arena is freed). Passing a null arena leaves ownership with the caller.
+//!
+//! In the generated Swift consumer, `withRustArena { arena in ... }` opens a `RustArena` for the
+//! current thread. While it's open, getters and methods with `_in_arena` variants call those
+//! instead, as long as they return strings or other FFI types (or `Option`s or `Vec`s of them);
+//! strings are copied out of the arena, and instances of FFI types borrow from it (retaining the
+//! arena until they're released). Other getters and methods take ownership of their results as
+//! usual.
+//! ```ignore
+//! #[derive(Clone, Debug, ffi_derive::FFI)]
+//! #[ffi(arena)]
+//! pub struct Report {
+//!     pub title: String,
+//!     pub rows: Vec<Row>,
+//! }
+//! ```
+//!
+//! ## Shared instances
+//!
+//! Each pointer to a derived struct normally owns its own copy, so copying it on the consumer side
+//! clones the whole struct. Structs marked with `ffi(shared)` are allocated in an `Arc` instead:
+//! `{type}_rust_ffi_retain` and `{type}_rust_ffi_release` adjust the reference count, consumer
+//! copies share a single instance, and an `Arc<Type>` field, argument, or return value shares its
+//! instance with the consumer instead of copying it. This is useful for large, immutable models.
+//! ```ignore
+//! #[derive(Clone, Debug, ffi_derive::FFI)]
+//! #[ffi(shared)]
+//! pub struct Catalog {
+//!     pub entries: Vec<Entry>,
+//! }
+//! ```
+//!
+//! ## Transparent newtypes
+//!
+//! Newtypes like `pub struct Acres(f64)` are useful in Rust, but they don't need to be opaque to the
+//! consumer. A struct with exactly one field that's marked with `ffi(transparent)` doesn't get an
+//! FFI of its own; instead, fields, arguments, and return values of that type (or an `Option` or a
+//! `Vec` of it) are exposed just like the type it wraps, and the consumer gets a `typealias` that
+//! keeps its name in generated signatures. Types in the same crate pick this up if the newtype is
+//! derived before them (earlier in the same file, or in a module that's declared first), and types
+//! in other crates pick it up when they reference it by a path that starts with its crate's name
+//! (like `model::FieldId`).
+//! ```ignore
+//! #[derive(Clone, Copy, Debug, ffi_derive::FFI)]
+//! #[ffi(transparent)]
+//! pub struct Acres(f64);
+//! ```
+//!
+//! ## Bit-flag types
+//!
+//! A newtype over an unsigned integer with an associated constant for each flag can be marked with
+//! `ffi(flags(...))`, listing the names of those constants. It's exposed like a transparent newtype,
+//! with a `get_{type}_{flag}` function for each constant and `{type}_union`,
+//! `{type}_intersection`, and `{type}_contains` for working with sets of flags, and the consumer
+//! gets an `OptionSet` with a static member for each flag.
+//! ```ignore
+//! #[derive(Clone, Copy, Debug, ffi_derive::FFI)]
+//! #[ffi(flags(READ, WRITE))]
+//! #[repr(transparent)]
+//! pub struct Permissions(u32);
+//!
+//! impl Permissions {
+//!     pub const READ: Self = Self(1);
+//!     pub const WRITE: Self = Self(1 << 1);
+//! }
+//! ```
+//!
+//! ## Generic structs
+//!
+//! A generic struct can't be exposed as-is, since each of the functions in its FFI has to work with
+//! a concrete type. Instead, list the instantiations to expose with `ffi(instantiate(...))`, naming
+//! each one. Each name becomes a type alias for its instantiation (so it shouldn't be declared
+//! again), and gets its own FFI module (like `measurement_f64_ffi`) and consumer type, just like a
+//! non-generic struct with that name. Other types can use these instantiations by their aliases.
+//! ```ignore
+//! #[derive(Clone, Debug, ffi_derive::FFI)]
+//! #[ffi(instantiate(Measurement<f64> as MeasurementF64, Measurement<Uuid> as MeasurementId))]
+//! pub struct Measurement<T> {
+//!     value: T,
+//!     unit: String,
+//! }
+//! ```
+//!
 //! ## Custom implementations
 //!
 //! Some types (like `wise_units::Unit`) don't fit the pattern of deriving an FFI for their visible
@@ -299,13 +486,13 @@ use ffi_internals::{
         enum_ffi,
         fn_ffi::FnFFI,
         impl_ffi::{ImplFFI, ImplInputs},
-        struct_ffi::{custom, standard},
+        struct_ffi::{custom, flags, standard, transparent},
     },
     parsing,
-    quote::{format_ident, ToTokens},
+    quote::{format_ident, quote, ToTokens},
     syn::{
-        parse_macro_input, spanned::Spanned, AttributeArgs, Data, DeriveInput, ItemFn, ItemImpl,
-        ItemMod, Type,
+        parse_macro_input, spanned::Spanned, Attribute, AttributeArgs, Data, DataEnum, DataStruct,
+        DeriveInput, Fields, Ident, ItemFn, ItemImpl, ItemMod, Type,
     },
 };
 use proc_macro::TokenStream;
@@ -327,6 +514,33 @@ use proc_macro_error::{abort, proc_macro_error};
 /// - *forbid_memberwise_init*: This attribute takes no arguments; instead, its presence indicates
 /// that we should not generate a memberwise initializer for this type. Usage looks like
 /// `ffi(forbid_memberwise_init)`.
+/// - *snapshot*: This attribute takes no arguments; instead, its presence indicates that we should
+/// generate a `repr(C)` `{Type}Snapshot` mirror type and a `get_{type}_snapshot` function for
+/// reading every field in a single call (along with a matching `free_{type}_snapshot`). Usage looks
+/// like `ffi(snapshot)`.
+/// - *arena*: This attribute takes no arguments; instead, its presence indicates that we should
+/// generate a `get_{type}_{field}_in_arena` variant of each getter that allocates (and of the
+/// snapshot getter, if there is one), which takes an additional `*mut FFIArena` and transfers
+/// ownership of the result to that arena. Usage looks like `ffi(arena)`.
+/// - *shared*: This attribute takes no arguments; instead, its presence indicates that instances of
+/// this struct should be allocated in an `Arc` when they're passed across the FFI. Copying an
+/// instance on the consumer side (or returning one from an `Arc<Type>`) then shares it instead of
+/// cloning it, and `{type}_rust_ffi_retain` and `{type}_rust_ffi_release` are generated for
+/// managing its reference count. The struct must implement `Clone`. Usage looks like
+/// `ffi(shared)`.
+/// - *transparent*: This attribute takes no arguments; instead, its presence indicates that this
+/// struct is a newtype with exactly one field, which should be exposed just like the type it wraps.
+/// This can't be combined with `snapshot`, `arena`, `shared`, or a custom FFI. Usage looks like
+/// `ffi(transparent)`.
+/// - *flags*: A list of the names of the associated constants of a bit-flag newtype over an
+/// unsigned integer, which is exposed like a `transparent` newtype, along with a getter for each
+/// constant. This has the same restrictions as `transparent`. Usage looks like
+/// `ffi(flags(READ, WRITE))`.
+/// - *instantiate*: A list of the concrete instantiations of a generic struct to expose, each with
+/// the name to expose it as. A type alias is generated for each instantiation, along with an FFI
+/// module and consumer type named after it. This can't be combined with `transparent`, `flags`, or
+/// a custom FFI, and it has to be in its own `ffi` attribute. Usage looks like
+/// `ffi(instantiate(Measurement<f64> as MeasurementF64))`.
 /// - *consumer_imports*: A list of paths to be imported into the consumer type definition. These
 /// should be absolute paths to remote crates; the goal here is to let the consumer set up
 /// frameworks that mirror the crate structure, which means they'll sometimes need to specify that a
@@ -339,6 +553,26 @@ use proc_macro_error::{abort, proc_macro_error};
 ///
 /// # Fields
 ///
+/// - *borrow*: This attribute takes no arguments; on a `Vec` field of primitive or `raw` elements,
+/// it generates an additional getter that returns a zero-copy `FFISlice*` view into the field's
+/// data. On a field (or an optional field) of another FFI type, it generates an additional getter
+/// that returns a non-owning pointer to the field. Usage looks like `ffi(borrow)`.
+/// - *batch*: This attribute takes no arguments; it generates an additional
+/// `get_{type}_{field}_batch` function that reads the field from an array of borrowed instances in
+/// a single call, returning an `FFIArray*` of the values (or, for a `Vec`, an `Option`, or a tuple
+/// field, an `FFINestedArray` of them). Sets, maps, fixed-size arrays, smart pointers, transparent
+/// types, and nested `Vec`s and `Option`s can't be batched. Usage looks like `ffi(batch)`.
+/// - *skip*: This attribute takes no arguments; the field is left out of the FFI entirely (so its
+/// type doesn't need to be FFI-safe), and the memberwise initializer fills it in with its
+/// `default`, or with `Default::default()`. Usage looks like `ffi(skip)`.
+/// - *readonly*: This attribute takes no arguments; the field still has a getter, but it's left out
+/// of the memberwise initializer, which fills it in like a `skip`ped field. Usage looks like
+/// `ffi(readonly)`.
+/// - *default*: The path to a function that returns the initial value of a `skip` or `readonly`
+/// field, as in `ffi(skip, default = "module::function")`.
+/// - *rename*: The name to expose the field as, in its getters and on the consumer, as in
+/// `ffi(rename = "title")`.
+///
 /// ## Custom Struct
 ///
 /// The following additional attributes are valid for structs with a manually implemented FFI:
@@ -376,7 +610,28 @@ fn impl_ffi_macro(ast: &DeriveInput) -> TokenStream {
     let module_name = format_ident!("{}_ffi", &type_name.to_string().to_snake_case());
     let struct_attributes = parsing::StructAttributes::from(&*ast.attrs);
     let doc_comments = ffi_internals::parsing::clone_doc_comments(&*ast.attrs);
+    if struct_attributes.instantiations.is_empty() && ast.generics.type_params().next().is_some() {
+        abort!(
+            ast.generics.span(),
+            "Generic types must list the instantiations to expose with `ffi(instantiate(...))`."
+        );
+    }
     match &ast.data {
+        Data::Struct(data) if !struct_attributes.instantiations.is_empty() => {
+            generic_struct_ffi(ast, data, &struct_attributes, &doc_comments, &out_dir)
+        }
+        Data::Struct(data)
+            if struct_attributes.transparent || struct_attributes.flags.is_some() =>
+        {
+            transparent_struct_ffi(
+                &type_name,
+                &module_name,
+                data,
+                &struct_attributes,
+                &doc_comments,
+                &out_dir,
+            )
+        }
         Data::Struct(data) => struct_attributes.custom_attributes.as_ref().map_or_else(
             || {
                 let ffi = standard::StructFFI::from(&standard::StructInputs {
@@ -387,12 +642,21 @@ fn impl_ffi_macro(ast: &DeriveInput) -> TokenStream {
                     consumer_imports: &struct_attributes.consumer_imports,
                     ffi_mod_imports: &struct_attributes.ffi_mod_imports,
                     forbid_memberwise_init: struct_attributes.forbid_memberwise_init,
+                    snapshot: struct_attributes.snapshot,
+                    arena: struct_attributes.arena,
+                    shared: struct_attributes.shared,
                     doc_comments: &doc_comments,
                 });
                 (&ConsumerStruct::from(&ffi)).write_output(&out_dir);
                 proc_macro2::TokenStream::from(ffi)
             },
             |custom_attributes| {
+                if struct_attributes.shared {
+                    abort!(
+                        type_name.span(),
+                        "`ffi(shared)` is not supported for structs with a custom FFI."
+                    );
+                }
                 let ffi = custom::StructFFI::new(
                     &type_name,
                     &module_name,
@@ -407,30 +671,157 @@ fn impl_ffi_macro(ast: &DeriveInput) -> TokenStream {
                 proc_macro2::TokenStream::from(ffi)
             },
         ),
-        Data::Enum(data) => {
-            if parsing::is_repr_c(&ast.attrs) {
-                let ffi = enum_ffi::reprc::EnumFFI::new(&module_name, &type_name);
-                (&consumer_enum::ReprCConsumerEnum::from(&ffi)).write_output(&out_dir);
-                ffi.into()
-            } else {
-                let ffi: enum_ffi::complex::EnumFFI<'_> = enum_ffi::complex::EnumFFI::new(
-                    &module_name,
-                    &type_name,
-                    data,
-                    &*struct_attributes.alias_modules,
-                    &*struct_attributes.consumer_imports,
-                    &*struct_attributes.ffi_mod_imports,
-                    &doc_comments,
-                );
-                (&consumer_enum::ComplexConsumerEnum::from(&ffi)).write_output(&out_dir);
-                proc_macro2::TokenStream::from(ffi)
-            }
-        }
+        Data::Enum(data) => enum_type_ffi(
+            ast,
+            data,
+            &module_name,
+            &struct_attributes,
+            &doc_comments,
+            &out_dir,
+        ),
         Data::Union(_) => abort!(type_name.span(), "Unions are not supported"),
     }
     .into()
 }
 
+fn enum_type_ffi(
+    ast: &DeriveInput,
+    data: &DataEnum,
+    module_name: &Ident,
+    struct_attributes: &parsing::StructAttributes,
+    doc_comments: &[Attribute],
+    out_dir: &str,
+) -> proc_macro2::TokenStream {
+    let type_name = &ast.ident;
+    if struct_attributes.shared {
+        abort!(
+            type_name.span(),
+            "`ffi(shared)` is only supported for structs."
+        );
+    }
+    if struct_attributes.transparent
+        || struct_attributes.flags.is_some()
+        || !struct_attributes.instantiations.is_empty()
+    {
+        abort!(
+            type_name.span(),
+            "`ffi(transparent)`, `ffi(flags)`, and `ffi(instantiate)` are only supported \
+for structs."
+        );
+    }
+    // Enums with an integer `repr` are exposed as value types too, unless they have any
+    // associated values.
+    let integer_repr = parsing::integer_repr(&ast.attrs).filter(|_| {
+        data.variants
+            .iter()
+            .all(|variant| matches!(variant.fields, Fields::Unit))
+    });
+    if parsing::is_repr_c(&ast.attrs) || integer_repr.is_some() {
+        let ffi = enum_ffi::reprc::EnumFFI::new(module_name, type_name, data, integer_repr);
+        (&consumer_enum::ReprCConsumerEnum::from(&ffi)).write_output(out_dir);
+        ffi.into()
+    } else {
+        let ffi: enum_ffi::complex::EnumFFI<'_> = enum_ffi::complex::EnumFFI::new(
+            module_name,
+            type_name,
+            data,
+            &struct_attributes.alias_modules,
+            &struct_attributes.consumer_imports,
+            &struct_attributes.ffi_mod_imports,
+            doc_comments,
+        );
+        (&consumer_enum::ComplexConsumerEnum::from(&ffi)).write_output(out_dir);
+        proc_macro2::TokenStream::from(ffi)
+    }
+}
+
+fn transparent_struct_ffi(
+    type_name: &Ident,
+    module_name: &Ident,
+    data: &DataStruct,
+    struct_attributes: &parsing::StructAttributes,
+    doc_comments: &[Attribute],
+    out_dir: &str,
+) -> proc_macro2::TokenStream {
+    if struct_attributes.custom_attributes.is_some()
+        || struct_attributes.snapshot
+        || struct_attributes.arena
+        || struct_attributes.shared
+    {
+        abort!(
+            type_name.span(),
+            "`ffi(transparent)` and `ffi(flags)` can't be combined with `custom`, `snapshot`, \
+`arena`, or `shared`."
+        );
+    }
+    let ffi = transparent::StructFFI::new(
+        type_name,
+        module_name,
+        data,
+        &struct_attributes.consumer_imports,
+        doc_comments,
+    );
+    if let Some(flags) = &struct_attributes.flags {
+        let ffi = flags::StructFFI::new(ffi, flags);
+        ffi.write_output(out_dir);
+        return proc_macro2::TokenStream::from(ffi);
+    }
+    ffi.write_output(out_dir);
+    proc_macro2::TokenStream::from(ffi)
+}
+
+fn generic_struct_ffi(
+    ast: &DeriveInput,
+    data: &DataStruct,
+    struct_attributes: &parsing::StructAttributes,
+    doc_comments: &[Attribute],
+    out_dir: &str,
+) -> proc_macro2::TokenStream {
+    if struct_attributes.custom_attributes.is_some()
+        || struct_attributes.transparent
+        || struct_attributes.flags.is_some()
+    {
+        abort!(
+            ast.ident.span(),
+            "`ffi(instantiate(...))` can't be combined with `custom`, `transparent`, or `flags`."
+        );
+    }
+    let visibility = &ast.vis;
+    struct_attributes
+        .instantiations
+        .iter()
+        .map(|instantiation| {
+            // Each instantiation gets an alias, which the rest of the generated FFI uses just like
+            // the name of a non-generic struct.
+            let type_name = &instantiation.name;
+            let ty = &instantiation.ty;
+            let module_name = format_ident!("{}_ffi", &type_name.to_string().to_snake_case());
+            let data = instantiation.monomorphize(&ast.ident, &ast.generics, data);
+            let ffi = standard::StructFFI::from(&standard::StructInputs {
+                module_name: &module_name,
+                type_name,
+                data: &data,
+                alias_modules: &struct_attributes.alias_modules,
+                consumer_imports: &struct_attributes.consumer_imports,
+                ffi_mod_imports: &struct_attributes.ffi_mod_imports,
+                forbid_memberwise_init: struct_attributes.forbid_memberwise_init,
+                snapshot: struct_attributes.snapshot,
+                arena: struct_attributes.arena,
+                shared: struct_attributes.shared,
+                doc_comments,
+            });
+            (&ConsumerStruct::from(&ffi)).write_output(out_dir);
+            let ffi = proc_macro2::TokenStream::from(ffi);
+            quote! {
+                #(#doc_comments)*
+                #visibility type #type_name = #ty;
+
+                #ffi
+            }
+        })
+        .collect()
+}
+
 fn out_dir() -> String {
     let root_output_dir = option_env!("FFI_CONSUMER_ROOT_DIR").unwrap_or_else(|| env!("OUT_DIR"));
     let package_name = std::env::var("CARGO_PKG_NAME").unwrap();
@@ -479,6 +870,20 @@ pub fn alias_resolution(attr: TokenStream, item: TokenStream) -> TokenStream {
 /// - *raw_types*: A list of types that should be exposed directly through the FFI when referenced in
 /// this impl. Generally this should just be types that are `repr(C)`. This looks like
 /// `raw_types(Type)`.
+/// - *duration_types*: A list of types that should be exposed as a `TimeInterval` (an `f64` number
+/// of seconds) when referenced in this impl. These must be `chrono::Duration` (or `TimeDelta`) or
+/// `std::time::Duration`. This looks like `duration_types(Duration)`.
+/// - *batch*: A list of methods in this impl that should also get a batched FFI function, which
+/// calls the method on each instance in an array of borrowed receivers in a single call and returns
+/// an `FFIArray*` of the results (or, if the method returns a `Vec`, an `Option`, or a tuple, an
+/// `FFINestedArray` of them). Only methods that take `&self` (and no other parameters) and return a
+/// type that a field could be batched with can be batched. If the method returns a `Result`, the
+/// batched function also takes an `errors: *mut FFIArrayString` out-parameter, which receives an
+/// error message (or null, for successes) for each receiver. This looks like `batch(method_name)`.
+/// - *arena*: A list of methods in this impl that should also get an `_in_arena` FFI function,
+/// which takes an additional `*mut FFIArena` parameter and transfers ownership of the result to
+/// that arena. Only methods that return a value are supported. This looks like
+/// `arena(method_name)`.
 ///
 /// # Proc Macro Errors
 ///
@@ -528,7 +933,10 @@ pub fn expose_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
         ffi_imports: impl_attributes.ffi_imports,
         consumer_imports: impl_attributes.consumer_imports,
         raw_types: impl_attributes.raw_types,
+        duration_types: impl_attributes.duration_types,
         generics: impl_attributes.generics,
+        batch_fns: impl_attributes.batch,
+        arena_fns: impl_attributes.arena,
         impl_description,
         type_name,
         doc_comments: parsing::clone_doc_comments(&*item_impl.attrs),
@@ -536,6 +944,12 @@ pub fn expose_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
     let out_dir = out_dir();
     let file_name = impl_ffi.consumer_file_name();
     ffi_internals::write_consumer_file(&file_name, String::from(&impl_ffi), &out_dir)
+        .and_then(|()| {
+            ffi_internals::consumer::write_generic_conformances(
+                &impl_ffi.consumer_generic_conformances(),
+                &out_dir,
+            )
+        })
         .unwrap_or_else(|err| abort!(item_impl.span(), "Error writing consumer file: {}", err));
     let ffi = impl_ffi.generate_ffi();
 
@@ -564,6 +978,9 @@ pub fn expose_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
 /// - *raw_types*: A list of types that should be exposed directly through the FFI when referenced in
 /// this impl. Generally this should just be types that are `repr(C)`. This looks like
 /// `raw_types(Type)`.
+/// - *duration_types*: A list of types that should be exposed as a `TimeInterval` (an `f64` number
+/// of seconds) when referenced in this impl. These must be `chrono::Duration` (or `TimeDelta`) or
+/// `std::time::Duration`. This looks like `duration_types(Duration)`.
 ///
 #[proc_macro_attribute]
 #[proc_macro_error]
@@ -582,6 +999,12 @@ pub fn expose_fn(attr: TokenStream, item: TokenStream) -> TokenStream {
         fn_ffi.generate_consumer_extension(&fn_attributes.extend_type.to_string(), &module_name),
         &out_dir,
     )
+    .and_then(|()| {
+        ffi_internals::consumer::write_generic_conformances(
+            &fn_ffi.consumer_generic_conformances(),
+            &out_dir,
+        )
+    })
     .unwrap_or_else(|err| abort!(item_fn.span(), "Error writing consumer file: {}", err));
 
     let ffi = fn_ffi.generate_ffi(&module_name, None, None);
@@ -64,6 +64,9 @@ pub enum Error {
     /// A mutex error occurred.
     #[error("Mutex error: `{0}`")]
     Mutex(String),
+    /// A type that was stored on disk couldn't be parsed.
+    #[error("Parse error: `{0}`")]
+    Parse(String),
 }
 
 impl From<serde_json::Error> for Error {
@@ -118,6 +118,36 @@ fn format_doc(doc: &str, indentation_level: usize) -> String {
     }
 }
 
+/// Returns the body of a consumer getter or function (indented to `indentation_level`) that
+/// evaluates to `from_rust`. If there's a `from_arena` expression, the body evaluates to that
+/// instead while a `RustArena` is open (see `withRustArena`), with the arena bound to `arena`.
+///
+fn arena_aware_body(from_rust: &str, from_arena: Option<&str>, indentation_level: usize) -> String {
+    from_arena.map_or_else(
+        || {
+            format!(
+                "{spacer:level$}{from_rust}",
+                spacer = " ",
+                level = TAB_SIZE * indentation_level,
+                from_rust = from_rust,
+            )
+        },
+        |from_arena| {
+            format!(
+                "{spacer:l1$}if let arena = RustArena.current {{
+{spacer:l2$}return {from_arena}
+{spacer:l1$}}}
+{spacer:l1$}return {from_rust}",
+                spacer = " ",
+                l1 = TAB_SIZE * indentation_level,
+                l2 = TAB_SIZE * (indentation_level + 1),
+                from_arena = from_arena,
+                from_rust = from_rust,
+            )
+        },
+    )
+}
+
 /// Reads the protocol file for `language` and writes it to `consumer_dir/FFIProtocols.language`.
 ///
 /// This is a file in the consumer's language that contains any generic or non-type-specific
@@ -142,15 +172,21 @@ fn write_support_files(consumer_dir: &str, language: &str) -> Result<(), Error>
 ///
 fn write_primitive_conformances(consumer_dir: &str) -> Result<(), std::io::Error> {
     [
-        "bool", "u8", "u16", "u32", "u64", "i8", "i16", "i32", "i64", "f32", "f64",
+        "bool", "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128",
+        "isize", "f32", "f64", "char",
     ]
     .iter()
     .try_for_each(|native_type| {
         let consumer_type = crate::consumer_type_for(native_type, false);
-        // Note: This is only accurate for Swift primitives, whose FFI and consumer types happen to
-        // match. Don't assume consumer_type == ffi_type for non-primitive types, or for primitives
-        // in other languages.
-        let ffi_type = &consumer_type;
+        // Note: Swift primitives (and the `FFIu128`/`FFIi128` structs) are used directly, so their
+        // FFI and consumer types match. A `char` is passed as an `FFIchar` (a `UInt32`), and
+        // converted to a `Character`. Don't assume consumer_type == ffi_type for non-primitive
+        // types, or for primitives in other languages.
+        let ffi_type = if *native_type == "char" {
+            "FFIchar"
+        } else {
+            &consumer_type
+        };
         let conformance_file: String = [
             HEADER,
             &primitives_conformance::generate(native_type, ffi_type, &consumer_type),
@@ -203,6 +239,22 @@ pub trait ConsumerType {
     /// Additional imports for the consumer side that this type requires.
     ///
     fn consumer_imports(&self) -> &[syn::Path];
+
+    /// Any other implementations for this type, like conformances to protocols that only some
+    /// types support.
+    ///
+    /// These should not include leading or trailing newlines.
+    ///
+    fn additional_impls(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// The consumer names and conformances of the generic FFI types (like `FFIMap`) that this
+    /// type's fields are exposed with (see `write_generic_conformances`).
+    ///
+    fn generic_conformances(&self) -> Vec<(String, String)> {
+        Vec::new()
+    }
 }
 
 /// Helper for turning any type that implements `ConsumerType` into an output `String`.
@@ -230,12 +282,36 @@ where
         contents.push(self.ffi_array_impl());
         contents.push(self.native_array_data_impl());
         contents.push(self.option_impl());
+        contents.extend(self.additional_impls());
         let file_name = format!("{}.swift", self.type_name());
         crate::write_consumer_file(&file_name, contents.join("\n\n"), out_dir)
+            .and_then(|()| write_generic_conformances(&self.generic_conformances(), out_dir))
             .unwrap_or_else(|err| proc_macro_error::abort!("Error writing consumer file: {}", err));
     }
 }
 
+/// Writes each of `conformances` to a file in `out_dir`.
+///
+/// Each of `conformances` is a pair of the consumer name of a generic FFI type (like an `FFIMap`)
+/// and its conformance, as returned by `TypeFFI::consumer_generic_conformances`.
+///
+/// The same variant of a generic type may be used by any number of types, but it can only conform
+/// once, so each conformance is written to its own file, named after the type.
+///
+/// # Errors
+///
+/// Returns an `std::io::Error` if we cannot write any of the conformance files.
+///
+pub fn write_generic_conformances(
+    conformances: &[(String, String)],
+    out_dir: &str,
+) -> Result<(), std::io::Error> {
+    conformances.iter().try_for_each(|(name, conformance)| {
+        let contents = [header_and_imports(&[]), conformance.clone()].join("\n\n");
+        crate::write_consumer_file(&format!("{name}.swift"), contents, out_dir)
+    })
+}
+
 /// Turns a path segment into a camel cased string.
 ///
 /// # Errors
@@ -17,6 +17,7 @@ trait ConsumerEnumType {
 trait CommonConsumerNames {
     fn array_name(&self) -> String;
     fn array_init_fn_name(&self) -> String;
+    fn array_init_consuming_fn_name(&self) -> String;
     fn array_free_fn_name(&self) -> String;
     fn option_init_fn_name(&self) -> String;
     fn option_free_fn_name(&self) -> String;
@@ -31,6 +32,10 @@ impl<T: ConsumerEnumType> CommonConsumerNames for T {
         format!("ffi_array_{}_init", self.type_name_ident())
     }
 
+    fn array_init_consuming_fn_name(&self) -> String {
+        format!("ffi_array_{}_init_consuming", self.type_name_ident())
+    }
+
     fn array_free_fn_name(&self) -> String {
         format!("ffi_array_{}_free", self.type_name_ident())
     }
@@ -38,29 +38,27 @@ impl ComplexConsumerEnum<'_> {
             .map(|variant| {
                 let mut result = crate::consumer::consumer_docs_from(&*variant.doc_comments, 1);
                 let ident = variant.ident.to_string().to_mixed_case();
-                let field_types: Vec<String> = variant
+                // Named fields become labeled associated values. Every case also holds on to the
+                // `FFI` instance it was read from (including variants without any fields).
+                let mut associated_values: Vec<String> = variant
                     .fields
                     .iter()
-                    .map(|field| field.native_type_data.consumer_type(None))
+                    .map(|field| {
+                        let consumer_type = field.native_type_data.consumer_type(None);
+                        if variant.has_named_fields() {
+                            format!("{}: {}", field.field_name.consumer_ident(), consumer_type)
+                        } else {
+                            consumer_type
+                        }
+                    })
                     .collect();
-                // Some variants of an enum may not have an associated value (i.e., have zero
-                // fields); we need to support those because an enum cannot be repr(C) if it has one
-                // or more variants with associated values.
-                let associated_values = if field_types.is_empty() {
-                    String::default()
-                } else {
-                    format!(
-                        "({}, {}.FFI)",
-                        field_types.join(", "),
-                        self.type_name_ident(),
-                    )
-                };
+                associated_values.push(format!("{}.FFI", self.type_name_ident()));
                 result.push_str(&format!(
-                    "{spacer:l1$}case {ident}{associated_values}",
+                    "{spacer:l1$}case {ident}({associated_values})",
                     spacer = " ",
                     l1 = TAB_SIZE,
                     ident = ident,
-                    associated_values = associated_values,
+                    associated_values = associated_values.join(", "),
                 ));
                 result
             })
@@ -72,19 +70,30 @@ impl ComplexConsumerEnum<'_> {
         format!(
             r#"{spacer:l1$}public final class FFI {{
 {spacer:l2$}internal let pointer: OpaquePointer
+{spacer:l2$}/// If this instance was borrowed from another instance's field, this is the instance that
+{spacer:l2$}/// owns `pointer`; it's retained to keep `pointer` valid, and `pointer` isn't freed here.
+{spacer:l2$}private var owner: AnyObject?
 
 {spacer:l2$}internal init(_ pointer: OpaquePointer) {{
 {spacer:l3$}self.pointer = pointer
 {spacer:l2$}}}
 
+{spacer:l2$}internal init(borrowing pointer: OpaquePointer, owner: AnyObject) {{
+{spacer:l3$}self.pointer = pointer
+{spacer:l3$}self.owner = owner
+{spacer:l2$}}}
+
 {spacer:l2$}deinit {{
-{spacer:l3$}{free_fn_name}(pointer)
+{spacer:l3$}if owner == nil {{
+{spacer:l4$}{free_fn_name}(pointer)
+{spacer:l3$}}}
 {spacer:l2$}}}
 {spacer:l1$}}}"#,
             spacer = " ",
             l1 = TAB_SIZE,
             l2 = TAB_SIZE * 2,
             l3 = TAB_SIZE * 3,
+            l4 = TAB_SIZE * 4,
             free_fn_name = self.enum_ffi.free_fn_name(),
         )
     }
@@ -120,6 +129,13 @@ extension {type_name}: NativeEnum {{
 {spacer:l1$}public static func fromRust(pointer: FFIType.ForeignType) -> Self {{
 {spacer:l2$}return FFI.fromRust(pointer).makeNative()
 {spacer:l1$}}}
+}}
+
+// MARK: - BorrowableNativeData
+extension {type_name}: BorrowableNativeData {{
+{spacer:l1$}public static func fromRust(borrowing foreignObject: FFIType.ForeignType, owner: AnyObject) -> Self {{
+{spacer:l2$}return FFI(borrowing: foreignObject!, owner: owner).makeNative()
+{spacer:l1$}}}
 }}"#,
             spacer = " ",
             l1 = TAB_SIZE,
@@ -137,39 +153,36 @@ extension {type_name}: NativeEnum {{
             .variants
             .iter()
             .map(|variant| {
-                let (arguments, conversions) = match variant.fields.len() {
-                    0 => (String::default(), String::default()),
-                    1 => (
-                        format!(
-                            "_ data: {}",
-                            variant
-                                .fields
-                                .first()
-                                .unwrap()
-                                .native_type_data
-                                .consumer_type(None)
-                        ),
-                        "data.clone()".to_string(),
-                    ),
-                    _ => (
-                        format!(
-                            "_ data: ({})",
-                            variant
-                                .fields
-                                .iter()
-                                .map(|field| field.native_type_data.consumer_type(None))
-                                .collect::<Vec<String>>()
-                                .join(", ")
-                        ),
-                        variant
-                            .fields
-                            .iter()
-                            .enumerate()
-                            .map(|(index, _)| format!("data.{}.clone()", index))
-                            .collect::<Vec<String>>()
-                            .join(","),
-                    ),
+                // A single unnamed field is passed as `_ data`; otherwise, each argument is
+                // named after its field, and named fields keep their labels.
+                let argument_names: Vec<String> = match &*variant.fields {
+                    [_] if !variant.has_named_fields() => vec!["data".to_string()],
+                    fields => fields
+                        .iter()
+                        .map(|field| field.field_name.consumer_ident())
+                        .collect(),
                 };
+                let arguments = variant
+                    .fields
+                    .iter()
+                    .zip(&argument_names)
+                    .map(|(field, name)| {
+                        format!(
+                            "{label}{name}: {consumer_type}",
+                            label = if variant.has_named_fields() { "" } else { "_ " },
+                            name = name,
+                            consumer_type = field.native_type_data.consumer_type(None)
+                        )
+                    })
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                let conversions = variant
+                    .fields
+                    .iter()
+                    .zip(&argument_names)
+                    .map(|(field, name)| field.native_type_data.consumer_clone(name, None))
+                    .collect::<Vec<String>>()
+                    .join(", ");
                 format!(
                     r#"{spacer:l1$}static func {consumer_variant_name}({arguments}) -> Self {{
 {spacer:l2$}FFI({variant_init_fn_name}({conversions})).makeNative()
@@ -193,23 +206,40 @@ extension {type_name}: NativeEnum {{
             .iter()
             .map(|variant| {
                 let ffi_variant_ident = format!("{}_{}", self.enum_ffi.reprc_enum(), variant.ident);
-                let field_getters: Vec<String> = variant
+                let field_getters: String = variant
                     .fields
                     .iter()
                     .map(|field| {
-                        format!(
-                            "{spacer:l4$}.fromRust({field_getter_name}(pointer))",
-                            spacer = " ",
-                            l4 = TAB_SIZE * 4,
-                            field_getter_name = field.getter_name()
-                        )
+                        let getter = format!("{}(pointer)", field.getter_name());
+                        let label = if variant.has_named_fields() {
+                            format!("{}: ", field.field_name.consumer_ident())
+                        } else {
+                            String::default()
+                        };
+                        if field.native_type_data.converts_with_ffi_type() {
+                            format!(
+                                "{spacer:l4$}{label}{from_rust},\n",
+                                spacer = " ",
+                                l4 = TAB_SIZE * 4,
+                                label = label,
+                                from_rust =
+                                    field.native_type_data.consumer_from_rust(&getter, None)
+                            )
+                        } else {
+                            format!(
+                                "{spacer:l4$}{label}.fromRust({getter}),\n",
+                                spacer = " ",
+                                l4 = TAB_SIZE * 4,
+                                label = label,
+                                getter = getter
+                            )
+                        }
                     })
                     .collect();
                 format!(
                     r#"{spacer:l2$}case {ffi_variant_ident}:
 {spacer:l3$}return .{consumer_variant_ident}(
-{field_getters},
-{spacer:l4$}self
+{field_getters}{spacer:l4$}self
 {spacer:l3$})"#,
                     spacer = " ",
                     l2 = TAB_SIZE * 2,
@@ -217,7 +247,7 @@ extension {type_name}: NativeEnum {{
                     l4 = TAB_SIZE * 4,
                     ffi_variant_ident = ffi_variant_ident,
                     consumer_variant_ident = variant.ident.to_string().to_mixed_case(),
-                    field_getters = field_getters.join(",\n"),
+                    field_getters = field_getters,
                 )
             })
             .collect::<Vec<String>>()
@@ -230,11 +260,11 @@ extension {type_name}: NativeEnum {{
             .iter()
             .map(|variant| {
                 format!(
-                    "{spacer:l3$}let .{variant_name}({placeholders}, ffi)",
+                    "{spacer:l3$}let .{variant_name}({placeholders}ffi)",
                     spacer = " ",
                     l3 = TAB_SIZE * 3,
                     variant_name = variant.ident.to_string().to_mixed_case(),
-                    placeholders = vec!["_"; variant.fields.len()].join(", "),
+                    placeholders = vec!["_, "; variant.fields.len()].concat(),
                 )
             })
             .collect::<Vec<String>>()
@@ -249,8 +279,15 @@ impl ConsumerType for ComplexConsumerEnum<'_> {
 
     fn type_definition(&self) -> Option<String> {
         let mut result = crate::consumer::consumer_docs_from(self.enum_ffi.doc_comments, 0);
+        // Recursive enums need a smart pointer in Rust, and need to be `indirect` in Swift.
+        let is_indirect = self
+            .enum_ffi
+            .variants
+            .iter()
+            .flat_map(|variant| &variant.fields)
+            .any(|field| field.native_type_data.has_pointer());
         result.push_str(&format!(
-            r#"public enum {type_name} {{
+            r"public {indirect}enum {type_name} {{
 {case_definitions}
 
 {case_inits}
@@ -261,7 +298,8 @@ extension {type_name} {{
 {ffi_declaration}
 }}
 
-{enum_protocol_conformance}"#,
+{enum_protocol_conformance}",
+            indirect = if is_indirect { "indirect " } else { "" },
             type_name = self.type_name(),
             case_definitions = self.case_definitions(),
             case_inits = self.case_inits(),
@@ -333,7 +371,7 @@ extension {type_name}: NativeData {{
 {spacer:l1$}public typealias Value = OpaquePointer?
 
 {spacer:l1$}public static func from(ptr: UnsafePointer<Value>?, len: Int) -> Self {{
-{spacer:l2$}{array_init_fn_name}(ptr, len)
+{spacer:l2$}{array_init_consuming_fn_name}(ptr, len)
 {spacer:l1$}}}
 
 {spacer:l1$}public static func free(_ array: Self) {{
@@ -344,7 +382,7 @@ extension {type_name}: NativeData {{
             l1 = TAB_SIZE,
             l2 = TAB_SIZE * 2,
             array_name = self.array_name(),
-            array_init_fn_name = self.array_init_fn_name(),
+            array_init_consuming_fn_name = self.array_init_consuming_fn_name(),
             array_free_fn_name = self.array_free_fn_name()
         )
     }
@@ -433,6 +471,18 @@ public extension Optional where Wrapped == {type_name} {{
     fn consumer_imports(&self) -> &[syn::Path] {
         self.enum_ffi.consumer_imports
     }
+
+    fn generic_conformances(&self) -> Vec<(String, String)> {
+        self.enum_ffi
+            .variants
+            .iter()
+            .flat_map(|variant| &variant.fields)
+            .flat_map(|f| {
+                f.native_type_data
+                    .consumer_generic_conformances(f.attributes.expose_as_ident())
+            })
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -491,14 +541,16 @@ mod tests {
                                 native_type: TypeIdentifier::Raw(format_ident!("u16")),
                                 is_option: false,
                                 is_vec: false,
+                                array_len: None,
                                 is_result: false,
                                 is_cow: false,
+                                pointer: None,
+                                transparent: None,
                                 is_borrow: false,
+                                collection_type: None,
+                                wrapped: None,
                             },
-                            attributes: FieldAttributes {
-                                expose_as: None,
-                                raw: false,
-                            },
+                            attributes: FieldAttributes::default(),
                         }],
                         doc_comments: vec![],
                     },
@@ -515,14 +567,16 @@ mod tests {
                                 native_type: TypeIdentifier::Raw(format_ident!("u8")),
                                 is_option: false,
                                 is_vec: false,
+                                array_len: None,
                                 is_result: false,
                                 is_cow: false,
+                                pointer: None,
+                                transparent: None,
                                 is_borrow: false,
+                                collection_type: None,
+                                wrapped: None,
                             },
-                            attributes: FieldAttributes {
-                                expose_as: None,
-                                raw: false,
-                            },
+                            attributes: FieldAttributes::default(),
                         }],
                         doc_comments: vec![],
                     },
@@ -564,13 +618,23 @@ mod tests {
 extension TestType {
     public final class FFI {
         internal let pointer: OpaquePointer
+        /// If this instance was borrowed from another instance's field, this is the instance that
+        /// owns `pointer`; it's retained to keep `pointer` valid, and `pointer` isn't freed here.
+        private var owner: AnyObject?
 
         internal init(_ pointer: OpaquePointer) {
             self.pointer = pointer
         }
 
+        internal init(borrowing pointer: OpaquePointer, owner: AnyObject) {
+            self.pointer = pointer
+            self.owner = owner
+        }
+
         deinit {
-            rust_ffi_free_test_type(pointer)
+            if owner == nil {
+                rust_ffi_free_test_type(pointer)
+            }
         }
     }
 }
@@ -614,6 +678,13 @@ extension TestType: NativeEnum {
     public static func fromRust(pointer: FFIType.ForeignType) -> Self {
         return FFI.fromRust(pointer).makeNative()
     }
+}
+
+// MARK: - BorrowableNativeData
+extension TestType: BorrowableNativeData {
+    public static func fromRust(borrowing foreignObject: FFIType.ForeignType, owner: AnyObject) -> Self {
+        return FFI(borrowing: foreignObject!, owner: owner).makeNative()
+    }
 }"#
         );
     }
@@ -11,12 +11,18 @@ use crate::{
     items::enum_ffi::reprc,
     syn::Ident,
 };
+use heck::{MixedCase, SnakeCase};
 
 /// Contains the data required to generate a consumer type for `repr(C)` enums, which ought to be
 /// any enums that don't have associated values, and associated functions for doing so.
 ///
+/// Enums with an integer `repr` (like `repr(u8)`) cross the FFI as that integer, so they're exposed
+/// as a consumer enum whose raw values are the Rust enum's discriminants.
+///
 pub struct ReprCConsumerEnum<'a> {
     ident: &'a Ident,
+    repr: Option<&'a Ident>,
+    discriminants: &'a [(Ident, i128)],
 }
 
 impl<'a> ReprCConsumerEnum<'a> {
@@ -24,7 +30,21 @@ impl<'a> ReprCConsumerEnum<'a> {
     ///
     #[must_use]
     pub const fn new(ident: &'a Ident) -> Self {
-        ReprCConsumerEnum { ident }
+        ReprCConsumerEnum {
+            ident,
+            repr: None,
+            discriminants: &[],
+        }
+    }
+
+    /// The consumer type that this enum crosses the FFI as; the raw value type for enums with an
+    /// integer `repr`, and the enum itself otherwise.
+    ///
+    fn foreign_type(&self) -> String {
+        self.repr.map_or_else(
+            || self.type_name_ident().to_string(),
+            |repr| crate::consumer_type_for(&repr.to_string(), false),
+        )
     }
 }
 
@@ -41,11 +61,54 @@ impl ConsumerType for ReprCConsumerEnum<'_> {
 
     fn type_definition(&self) -> Option<String> {
         // There's no type definition for repr(C) enums; instead, we extend the FFI enum since it's
-        // usable as-is.
-        None
+        // usable as-is. Enums with an integer `repr` are only an integer in C, though, so they need
+        // a consumer enum with matching raw values.
+        let repr = self.repr?;
+        let cases: Vec<String> = self
+            .discriminants
+            .iter()
+            .map(|(variant, discriminant)| {
+                format!(
+                    "    case {} = {}",
+                    variant.to_string().to_mixed_case(),
+                    discriminant
+                )
+            })
+            .collect();
+        Some(format!(
+            "public enum {type_name}: {foreign_type} {{\n{cases}\n}}",
+            type_name = self.type_name_ident(),
+            foreign_type = crate::consumer_type_for(&repr.to_string(), false),
+            cases = cases.join("\n"),
+        ))
     }
 
     fn native_data_impl(&self) -> String {
+        if self.repr.is_some() {
+            return format!(
+                "// MARK: - NativeData
+extension {type_name}: NativeData {{
+    public typealias ForeignType = {foreign_type}
+
+    public func clone() -> ForeignType {{
+        return rawValue
+    }}
+
+    public func borrowReference() -> ForeignType {{
+        return rawValue
+    }}
+
+    public static func fromRust(_ foreignObject: ForeignType) -> Self {{
+        guard let value = Self(rawValue: foreignObject) else {{
+            fatalError(\"`\\(foreignObject)` isn't a valid raw value for `{type_name}`.\")
+        }}
+        return value
+    }}
+}}",
+                type_name = self.type_name_ident(),
+                foreign_type = self.foreign_type(),
+            );
+        }
         format!(
             "// MARK: - NativeData
 extension {type_name}: NativeData {{
@@ -71,7 +134,7 @@ extension {type_name}: NativeData {{
         format!(
             "// MARK: - FFIArray
 extension {array_name}: FFIArray {{
-    public typealias Value = {type_name}
+    public typealias Value = {foreign_type}
 
     public static func from(ptr: UnsafePointer<Value>?, len: Int) -> Self {{
         {array_init_fn_name}(ptr, len)
@@ -80,9 +143,15 @@ extension {array_name}: FFIArray {{
     public static func free(_ array: Self) {{
         {array_free_fn_name}(array)
     }}
+}}
+
+// MARK: - FFISlice
+extension FFISlice{type_name}: FFISlice {{
+    public typealias Value = {foreign_type}
 }}",
             array_name = self.array_name(),
             type_name = self.type_name_ident(),
+            foreign_type = self.foreign_type(),
             array_init_fn_name = self.array_init_fn_name(),
             array_free_fn_name = self.array_free_fn_name(),
         )
@@ -103,7 +172,7 @@ extension {type_name}: NativeArrayData {{
         format!(
             "// MARK: - Optional
 public extension Optional where Wrapped == {type_name} {{
-    func clone() -> UnsafeMutablePointer<{type_name}>? {{
+    func clone() -> UnsafeMutablePointer<{foreign_type}>? {{
         switch self {{
         case let .some(value):
             let v = value.clone()
@@ -113,7 +182,7 @@ public extension Optional where Wrapped == {type_name} {{
         }}
     }}
 
-    func borrowReference() -> UnsafeMutablePointer<{type_name}>? {{
+    func borrowReference() -> UnsafeMutablePointer<{foreign_type}>? {{
         switch self {{
         case let .some(value):
             let v = value.borrowReference()
@@ -123,7 +192,7 @@ public extension Optional where Wrapped == {type_name} {{
         }}
     }}
 
-    static func fromRust(_ ptr: UnsafePointer<{type_name}>?) -> Self {{
+    static func fromRust(_ ptr: UnsafePointer<{foreign_type}>?) -> Self {{
         guard let ptr = ptr else {{
             return .none
         }}
@@ -132,11 +201,12 @@ public extension Optional where Wrapped == {type_name} {{
         return value
     }}
 
-    static func free(_ option: UnsafePointer<{type_name}>?) {{
+    static func free(_ option: UnsafePointer<{foreign_type}>?) {{
         {option_free_fn_name}(option)
     }}
 }}",
             type_name = self.type_name_ident(),
+            foreign_type = self.foreign_type(),
             option_init_fn_name = self.option_init_fn_name(),
             option_free_fn_name = self.option_free_fn_name(),
         )
@@ -145,12 +215,50 @@ public extension Optional where Wrapped == {type_name} {{
     fn consumer_imports(&self) -> &[syn::Path] {
         &[]
     }
+
+    fn additional_impls(&self) -> Vec<String> {
+        let type_name = self.type_name_ident();
+        let type_name_snake = type_name.to_string().to_snake_case();
+        vec![
+            format!(
+                "// MARK: - CaseIterable
+extension {type_name}: CaseIterable {{
+    public static var allCases: [{type_name}] {{
+        [{type_name}].fromRust({type_name_snake}_all_variants())
+    }}
+}}",
+                type_name = type_name,
+                type_name_snake = type_name_snake,
+            ),
+            format!(
+                "// MARK: - Variant names
+public extension {type_name} {{
+    /// The name of this variant, which is the same as its name in Rust.
+    var variantName: String {{
+        String.fromRust({type_name_snake}_variant_name(clone()))
+    }}
+
+    /// Returns the variant named `variantName`, or `nil` if there isn't one.
+    init?(variantName: String) {{
+        guard let variant = Optional<{type_name}>.fromRust({type_name_snake}_variant_from_name(variantName.clone())) else {{
+            return nil
+        }}
+        self = variant
+    }}
+}}",
+                type_name = type_name,
+                type_name_snake = type_name_snake,
+            ),
+        ]
+    }
 }
 
-impl<'a> From<&reprc::EnumFFI<'a>> for ReprCConsumerEnum<'a> {
-    fn from(ffi: &reprc::EnumFFI<'a>) -> Self {
+impl<'a> From<&'a reprc::EnumFFI<'_>> for ReprCConsumerEnum<'a> {
+    fn from(ffi: &'a reprc::EnumFFI<'_>) -> Self {
         Self {
             ident: ffi.type_name,
+            repr: ffi.repr.as_ref(),
+            discriminants: &ffi.discriminants,
         }
     }
 }
@@ -210,6 +318,11 @@ extension FFIArrayTestType: FFIArray {
     public static func free(_ array: Self) {
         ffi_array_TestType_free(array)
     }
+}
+
+// MARK: - FFISlice
+extension FFISliceTestType: FFISlice {
+    public typealias Value = TestType
 }"#
         );
     }
@@ -270,4 +383,47 @@ public extension Optional where Wrapped == TestType {
 }"#
         );
     }
+
+    #[test]
+    fn integer_repr_type_definition() {
+        let ident = format_ident!("Status");
+        let repr = format_ident!("u8");
+        let discriminants = [(format_ident!("Active"), 1), (format_ident!("InReview"), 7)];
+        let integer_enum = ReprCConsumerEnum {
+            ident: &ident,
+            repr: Some(&repr),
+            discriminants: &discriminants,
+        };
+        assert_eq!(
+            integer_enum.type_definition().unwrap(),
+            r"public enum Status: UInt8 {
+    case active = 1
+    case inReview = 7
+}"
+        );
+        assert!(integer_enum
+            .native_data_impl()
+            .contains("public typealias ForeignType = UInt8"));
+        assert!(integer_enum
+            .option_impl()
+            .contains("static func fromRust(_ ptr: UnsafePointer<UInt8>?) -> Self {"));
+    }
+
+    #[test]
+    fn additional_impls() {
+        let ident = format_ident!("TestType");
+        let repr_c_enum = ReprCConsumerEnum::new(&ident);
+        let impls = repr_c_enum.additional_impls();
+        assert_eq!(
+            impls[0],
+            r"// MARK: - CaseIterable
+extension TestType: CaseIterable {
+    public static var allCases: [TestType] {
+        [TestType].fromRust(test_type_all_variants())
+    }
+}"
+        );
+        assert!(impls[1].contains("String.fromRust(test_type_variant_name(clone()))"));
+        assert!(impls[1].contains("test_type_variant_from_name(variantName.clone())"));
+    }
 }
@@ -7,6 +7,7 @@ use crate::{
     heck::MixedCase,
     items::fn_ffi::{FnFFI, FnReceiver},
     syn::Ident,
+    type_ffi::{TypeFFI, TypeIdentifier},
 };
 
 impl FnFFI {
@@ -27,31 +28,79 @@ impl FnFFI {
         let (return_conversion, close_conversion, return_sig) =
             self.return_type.as_ref().map_or_else(
                 || (String::new(), String::new(), String::new()),
-                crate::type_ffi::TypeFFI::consumer_return_type_components,
+                TypeFFI::consumer_return_type_components,
             );
         let mut result = module_docs.map_or(String::default(), |docs| {
             crate::consumer::consumer_docs_from(docs, 1)
         });
         result.push_str(&crate::consumer::consumer_docs_from(&*self.doc_comments, 1));
+        let ffi_parameters = self.ffi_calling_arguments();
+        // If this function has an arena variant, this calls it while an arena is open (if the
+        // return type can be read from an arena).
+        let from_arena = match &self.return_type {
+            Some(r) if self.arena => {
+                let arena_parameters = if ffi_parameters.is_empty() {
+                    "arena.pointer".to_string()
+                } else {
+                    format!("{ffi_parameters}, arena.pointer")
+                };
+                r.consumer_from_arena(
+                    &format!(
+                        "{}({})",
+                        self.arena_ffi_fn_name(module_name),
+                        arena_parameters
+                    ),
+                    "arena",
+                    None,
+                )
+            }
+            _ => None,
+        };
         result.push_str(&format!(
 "{spacer:l1$}{static_keyword}func {consumer_fn_name}({consumer_parameters}) {return_sig} {{
-{spacer:l2$}{return_conversion}{ffi_fn_name}({ffi_parameters}){close_conversion}
+{body}
 {spacer:l1$}}}",
             spacer = " ",
             l1 = TAB_SIZE,
-            l2 = TAB_SIZE * 2,
             static_keyword = static_keyword,
             consumer_fn_name = self.fn_name.to_string().to_mixed_case(),
             consumer_parameters = self.consumer_parameters(),
             return_sig = return_sig,
-            return_conversion = return_conversion,
-            ffi_fn_name = self.ffi_fn_name(module_name).to_string(),
-            ffi_parameters = self.ffi_calling_arguments(),
-            close_conversion = close_conversion,
+            body = crate::consumer::arena_aware_body(
+                &format!(
+                    "{}{}({}){}",
+                    return_conversion,
+                    self.ffi_fn_name(module_name),
+                    ffi_parameters,
+                    close_conversion
+                ),
+                from_arena.as_deref(),
+                2
+            ),
         ));
         result
     }
 
+    /// The consumer names and conformances of the generic FFI types that this function's
+    /// parameters and return value are exposed with (see `consumer::write_generic_conformances`).
+    ///
+    #[must_use]
+    pub fn consumer_generic_conformances(&self) -> Vec<(String, String)> {
+        // Batched `Option`s, `Vec`s, and tuples are returned in an `FFINestedArray`.
+        let batch_results = self
+            .return_type
+            .as_ref()
+            .filter(|_| self.batch)
+            .map(TypeFFI::batch_results);
+        self.parameters
+            .iter()
+            .map(|arg| &arg.native_type_data)
+            .chain(self.return_type.as_ref())
+            .chain(batch_results.as_ref())
+            .flat_map(|ty| ty.consumer_generic_conformances(None))
+            .collect()
+    }
+
     /// Generates the contents of a consumer extension for this function, extending the original
     /// type with the behaviors described by `self`. This is primarily for use with
     /// `ffi_derive::expose_fn`, where we want to generate an FFI and consumer for a standalone
@@ -68,7 +117,7 @@ impl FnFFI {
         let (return_conversion, close_conversion, return_sig) =
             self.return_type.as_ref().map_or_else(
                 || (String::new(), String::new(), String::new()),
-                crate::type_ffi::TypeFFI::consumer_return_type_components,
+                TypeFFI::consumer_return_type_components,
             );
 
         let mut result = format!("extension {} {{", consumer_type);
@@ -96,6 +145,67 @@ impl FnFFI {
         [super::header_and_imports(&[]), result].join("\n")
     }
 
+    /// If this function is batched, generates a consumer function (for an extension on a
+    /// `Collection` of the receiver type) for calling the foreign function produced by
+    /// `self.generate_batch_ffi(...)`.
+    ///
+    pub(super) fn generate_batch_consumer(&self, module_name: &Ident) -> Option<String> {
+        let r = match &self.return_type {
+            Some(r) if self.batch => r,
+            _ => return None,
+        };
+        let ty = r.consumer_type(None);
+        let results = r.batch_results();
+        let (return_sig, body) = if r.is_result {
+            // Strings in an `FFIArrayString` are freed along with their array, so they need to be
+            // copied rather than consumed with `fromRust`.
+            let element = match (&r.native_type, &results.wrapped) {
+                (TypeIdentifier::String, None) => "String(cString: $0!)".to_string(),
+                (_, Some(element)) => element.consumer_from_rust("$0", None),
+                _ => format!("{ty}.fromRust($0)"),
+            };
+            (
+                format!("[Result<{ty}, RustError>]"),
+                format!(
+                    "{spacer:l3$}var errors = FFIArrayString(ptr: nil, len: 0, cap: 0)
+{spacer:l3$}let values = {ffi_fn_name}(receivers, len, &errors)
+{spacer:l3$}return batchResults(values: values, errors: errors) {{ {element} }}",
+                    spacer = " ",
+                    l3 = TAB_SIZE * 3,
+                    ffi_fn_name = self.batch_ffi_fn_name(module_name),
+                    element = element,
+                ),
+            )
+        } else {
+            (
+                results.consumer_type(None),
+                format!(
+                    "{spacer:l3$}{conversion}",
+                    spacer = " ",
+                    l3 = TAB_SIZE * 3,
+                    conversion = results.consumer_from_rust(
+                        &format!("{}(receivers, len)", self.batch_ffi_fn_name(module_name)),
+                        None
+                    ),
+                ),
+            )
+        };
+        Some(format!(
+            "{docs}{spacer:l1$}func {consumer_fn_name}Batch() -> {return_sig} {{
+{spacer:l2$}withBatchReceivers(self) {{ receivers, len in
+{body}
+{spacer:l2$}}}
+{spacer:l1$}}}",
+            docs = crate::consumer::consumer_docs_from(&self.doc_comments, 1),
+            spacer = " ",
+            l1 = TAB_SIZE,
+            l2 = TAB_SIZE * 2,
+            consumer_fn_name = self.fn_name.to_string().to_mixed_case(),
+            return_sig = return_sig,
+            body = body,
+        ))
+    }
+
     fn consumer_parameters(&self) -> String {
         self.parameters
             .iter()
@@ -115,12 +225,12 @@ impl FnFFI {
             .parameters
             .iter()
             .map(|arg| {
-                let clone_or_borrow = if arg.native_type_data.argument_borrows_supported() {
-                    "borrowReference"
+                let name = arg.name.to_string();
+                if arg.native_type_data.argument_borrows_supported() {
+                    format!("{name}.borrowReference()")
                 } else {
-                    "clone"
-                };
-                format!("{}.{}()", arg.name.to_string(), clone_or_borrow)
+                    arg.native_type_data.consumer_clone(&name, None)
+                }
             })
             .collect();
         if self.receiver != FnReceiver::None {
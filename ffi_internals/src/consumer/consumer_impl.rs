@@ -2,9 +2,20 @@
 //! Generates a wrapping implementation in the consumer's language.
 //!
 
-use crate::items::impl_ffi::ImplFFI;
+use crate::items::{fn_ffi::FnFFI, impl_ffi::ImplFFI};
 
 impl ImplFFI {
+    /// The consumer names and conformances of the generic FFI types that this impl's functions take
+    /// or return (see `consumer::write_generic_conformances`).
+    ///
+    #[must_use]
+    pub fn consumer_generic_conformances(&self) -> Vec<(String, String)> {
+        self.fns
+            .iter()
+            .flat_map(FnFFI::consumer_generic_conformances)
+            .collect()
+    }
+
     /// Generates an appropriate consumer file name for this impl (by joining the trait and type
     /// names).
     ///
@@ -57,7 +68,24 @@ impl ImplFFI {
                 .collect::<Vec<String>>()
                 .join("\n\n"),
         ));
-        result
+        let batch_fns: Vec<String> = self
+            .fns
+            .iter()
+            .filter_map(|f| f.generate_batch_consumer(&self.module_name()))
+            .collect();
+        if batch_fns.is_empty() {
+            return result;
+        }
+        format!(
+            "{result}
+
+// MARK: - Batch calls
+public extension Collection where Element == {native_type} {{
+{functions}
+}}",
+            native_type = self.type_name,
+            functions = batch_fns.join("\n\n"),
+        )
     }
 }
 
@@ -10,7 +10,9 @@ use crate::{
 };
 
 mod custom;
+mod flags;
 mod standard;
+mod transparent;
 
 /// Contains the data required to generate a consumer type, and associated functions for doing so.
 ///
@@ -31,6 +33,11 @@ pub struct ConsumerStruct {
     /// struct field values).
     ///
     consumer_getters: String,
+    /// Consumer getters for reading a field from a collection of instances of this type in a
+    /// single call. These are declared in an extension on `Collection`, so they're empty if no
+    /// fields support batching.
+    ///
+    batch_getters: String,
     /// The name of the Rust type's initializer function.
     ///
     pub init_fn_name: String,
@@ -52,6 +59,10 @@ pub struct ConsumerStruct {
     /// Documentation comments on this struct.
     ///
     docs: String,
+    /// The consumer names and conformances of the generic FFI types that this struct's fields are
+    /// exposed with.
+    ///
+    generic_conformances: Vec<(String, String)>,
 }
 
 impl ConsumerStruct {
@@ -59,8 +70,12 @@ impl ConsumerStruct {
         format!("FFIArray{}", self.type_name)
     }
 
+    /// The consuming variant of the array initializer, which takes ownership of the elements that
+    /// are passed in. Our `NativeArrayData` conformance always clones the elements before building
+    /// an array, so there's no need for Rust to copy them again.
+    ///
     fn array_init(&self) -> String {
-        format!("ffi_array_{}_init", self.type_name)
+        format!("ffi_array_{}_init_consuming", self.type_name)
     }
 
     fn array_free(&self) -> String {
@@ -124,7 +139,10 @@ impl ConsumerType for ConsumerStruct {
         result.push_str(&format!(
             "public final class {class} {{
 
-{spacer:l1$}internal let pointer: OpaquePointer",
+{spacer:l1$}internal let pointer: OpaquePointer
+{spacer:l1$}/// If this instance was borrowed from another instance's field, this is the instance that
+{spacer:l1$}/// owns `pointer`; it's retained to keep `pointer` valid, and `pointer` isn't freed here.
+{spacer:l1$}private var owner: AnyObject?",
             spacer = " ",
             l1 = TAB_SIZE,
             class = self.type_name,
@@ -144,8 +162,15 @@ impl ConsumerType for ConsumerStruct {
 {spacer:l2$}self.pointer = pointer
 {spacer:l1$}}}
 
+{spacer:l1$}internal init(borrowing pointer: OpaquePointer, owner: AnyObject) {{
+{spacer:l2$}self.pointer = pointer
+{spacer:l2$}self.owner = owner
+{spacer:l1$}}}
+
 {spacer:l1$}deinit {{
-{spacer:l2$}{free_fn_name}(pointer)
+{spacer:l2$}if owner == nil {{
+{spacer:l3$}{free_fn_name}(pointer)
+{spacer:l2$}}}
 {spacer:l1$}}}
 
 {getters}
@@ -153,10 +178,23 @@ impl ConsumerType for ConsumerStruct {
             spacer = " ",
             l1 = TAB_SIZE,
             l2 = TAB_SIZE * 2,
+            l3 = TAB_SIZE * 3,
             free_fn_name = self.free_fn_name,
             getters = self.consumer_getters
         ));
-        Some(result)
+        if self.batch_getters.is_empty() {
+            return Some(result);
+        }
+        Some(format!(
+            "{result}
+
+// MARK: - Batch getters
+public extension Collection where Element == {class} {{
+{getters}
+}}",
+            class = self.type_name,
+            getters = self.batch_getters,
+        ))
     }
 
     fn native_data_impl(&self) -> String {
@@ -183,6 +221,15 @@ extension {type_name}: NativeData {{
 {spacer:l1$}public static func fromRust(_ foreignObject: ForeignType) -> Self {{
 {spacer:l2$}return Self(foreignObject!)
 {spacer:l1$}}}
+}}
+
+// MARK: - BorrowableNativeData
+extension {type_name}: BorrowableNativeData {{
+{spacer:l1$}/// Initializes an instance of this type that borrows a pointer into `owner`, which it retains
+{spacer:l1$}/// instead of freeing the pointer.
+{spacer:l1$}public static func fromRust(borrowing foreignObject: ForeignType, owner: AnyObject) -> Self {{
+{spacer:l2$}return Self(borrowing: foreignObject!, owner: owner)
+{spacer:l1$}}}
 }}",
             spacer = " ",
             l1 = TAB_SIZE,
@@ -268,4 +315,8 @@ public extension Optional where Wrapped == {type_name} {{
     fn consumer_imports(&self) -> &[Path] {
         &*self.consumer_imports
     }
+
+    fn generic_conformances(&self) -> Vec<(String, String)> {
+        self.generic_conformances.clone()
+    }
 }
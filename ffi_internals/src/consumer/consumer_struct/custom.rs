@@ -122,12 +122,14 @@ impl From<&custom::StructFFI<'_>> for ConsumerStruct {
             consumer_init_args: init_args.consumer,
             ffi_init_args: init_args.ffi,
             consumer_getters,
+            batch_getters: String::new(),
             init_fn_name: inputs.init_fn_name.to_string(),
             free_fn_name: inputs.free_fn_name.to_string(),
             clone_fn_name: inputs.clone_fn_name.to_string(),
             failable_init: inputs.custom_attributes.failable_init,
             forbid_memberwise_init: inputs.forbid_memberwise_init,
             docs: crate::consumer::consumer_docs_from(inputs.doc_comments, 0),
+            generic_conformances: Vec::new(),
         }
     }
 }
@@ -0,0 +1,75 @@
+use crate::{
+    consumer::{primitives_conformance, ConsumerOutput},
+    items::struct_ffi::flags,
+};
+use heck::MixedCase;
+
+// Bit-flag types are exposed as an `OptionSet` whose raw value is the integer that the Rust type
+// wraps, so Swift's own set operations work on them, and each named constant is a static member
+// that's read from Rust.
+impl ConsumerOutput for flags::StructFFI<'_> {
+    fn write_output(&self, out_dir: &str) {
+        let type_name = self.transparent.type_name;
+        let native_type = &self.native_type;
+        let raw_type = crate::consumer_type_for(native_type, false);
+        let members: Vec<String> = self
+            .flags
+            .iter()
+            .map(|flag| {
+                format!(
+                    "    public static let {} = {type_name}(rawValue: {}())",
+                    flag.to_string().to_mixed_case(),
+                    self.flag_getter_name(flag)
+                )
+            })
+            .collect();
+        let contents = [
+            crate::consumer::header_and_imports(self.transparent.consumer_imports),
+            format!(
+                "{docs}public struct {type_name}: OptionSet {{
+    public let rawValue: {raw_type}
+
+    public init(rawValue: {raw_type}) {{
+        self.rawValue = rawValue
+    }}
+
+{members}
+}}",
+                docs = crate::consumer::consumer_docs_from(self.transparent.doc_comments, 0),
+                members = members.join("\n"),
+            ),
+            format!(
+                "// MARK: - NativeData
+extension {type_name}: NativeData {{
+    public typealias ForeignType = {raw_type}
+
+    public func clone() -> ForeignType {{
+        return rawValue
+    }}
+
+    public func borrowReference() -> ForeignType {{
+        return rawValue
+    }}
+
+    public static func fromRust(_ foreignObject: ForeignType) -> Self {{
+        return Self(rawValue: foreignObject)
+    }}
+}}"
+            ),
+            primitives_conformance::consumer_array_type(
+                &type_name.to_string(),
+                &format!("FFIArray{native_type}"),
+            ),
+            primitives_conformance::option_conformance(
+                &type_name.to_string(),
+                &raw_type,
+                &format!("option_{native_type}_init"),
+                &format!("option_{native_type}_free"),
+            ),
+        ]
+        .join("\n\n");
+        let file_name = format!("{type_name}.swift");
+        crate::write_consumer_file(&file_name, contents, out_dir)
+            .unwrap_or_else(|err| proc_macro_error::abort!("Error writing consumer file: {}", err));
+    }
+}
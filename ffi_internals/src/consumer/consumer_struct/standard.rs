@@ -1,6 +1,7 @@
 use crate::{
     consumer::{consumer_struct::ConsumerStruct, TAB_SIZE},
-    items::struct_ffi::standard,
+    items::{field_ffi::FieldFFI, struct_ffi::standard},
+    type_ffi::TypeIdentifier,
 };
 
 #[derive(Debug, Clone, PartialEq)]
@@ -8,6 +9,7 @@ struct ExpandedFields {
     consumer_init_args: String,
     ffi_init_args: String,
     consumer_getters: String,
+    batch_getters: String,
 }
 
 // This implements some additional consumer-related behavior for the type from
@@ -20,53 +22,75 @@ impl standard::StructFFI<'_> {
     fn expand_fields(&self) -> ExpandedFields {
         let (consumer_init_args, ffi_init_args, consumer_getters) =
             self.fields.iter().enumerate().fold(
-                (String::new(), String::new(), String::new()),
+                (Vec::new(), Vec::new(), String::new()),
                 |mut acc, (index, f)| {
-                    // Swift rejects trailing commas on argument lists.
-                    let trailing_punctuation = if index < self.fields.len() - 1 {
-                        ",\n"
-                    } else {
-                        ""
-                    };
-                    // This looks like `foo: Bar,`.
-                    acc.0.push_str(&format!(
-                        "{spacer:level$}{field}: {type_name}{punct}",
-                        spacer = " ",
-                        level = TAB_SIZE * 2,
-                        field = f.field_name.consumer_ident(),
-                        type_name = f
-                            .native_type_data
-                            .consumer_type(f.attributes.expose_as_ident()),
-                        punct = trailing_punctuation
-                    ));
-                    let clone_or_borrow = if f.native_type_data.is_borrow {
-                        "borrowReference"
+                    let field = f.field_name.consumer_ident();
+                    // `readonly` fields aren't arguments of the memberwise init.
+                    if !f.attributes.readonly {
+                        // This looks like `foo: Bar`.
+                        acc.0.push(format!(
+                            "{spacer:level$}{field}: {type_name}",
+                            spacer = " ",
+                            level = TAB_SIZE * 2,
+                            field = field,
+                            type_name = f
+                                .native_type_data
+                                .consumer_type(f.attributes.expose_as_ident()),
+                        ));
+                        let clone_or_borrow = if f.native_type_data.is_borrow {
+                            format!("{field}.borrowReference()")
+                        } else {
+                            f.native_type_data
+                                .consumer_clone(&field, f.attributes.expose_as_ident())
+                        };
+                        // This looks like `foo.clone()` or `foo.borrowReference()`.
+                        acc.1.push(format!(
+                            "{:level$}{}",
+                            " ",
+                            clone_or_borrow,
+                            level = TAB_SIZE * 3
+                        ));
+                    }
+                    // If this struct has arena getters, this reads from the open arena (if
+                    // there is one), as in `Bar.fromRust(inArena: get_bar_foo_in_arena(pointer,
+                    // arena.pointer), arena: arena)`.
+                    let from_arena = if self.arena {
+                        f.native_type_data.consumer_from_arena(
+                            &format!("{}(pointer, arena.pointer)", f.arena_getter_name()),
+                            "arena",
+                            f.attributes.expose_as_ident(),
+                        )
                     } else {
-                        "clone"
+                        None
                     };
-                    // This looks like `foo.clone(),` or `foo.borrowReference(),`.
-                    acc.1.push_str(&format!(
-                        "{:level$}{}.{}(){}",
-                        " ",
-                        f.field_name.consumer_ident(),
-                        clone_or_borrow,
-                        trailing_punctuation,
-                        level = TAB_SIZE * 3,
-                    ));
                     // This looks like `public var foo: Bar { Bar.fromRust(get_bar_foo(pointer) }`.
                     acc.2.push_str(&format!(
                         "{spacer:l1$}public var {field}: {type_name} {{
-{spacer:l2$}{type_name}.fromRust({getter}(pointer))
+{body}
 {spacer:l1$}}}",
                         spacer = " ",
                         l1 = TAB_SIZE,
-                        l2 = TAB_SIZE * 2,
-                        field = f.field_name.consumer_ident(),
+                        field = field,
                         type_name = f
                             .native_type_data
                             .consumer_type(f.attributes.expose_as_ident()),
-                        getter = f.getter_name().to_string()
+                        body = crate::consumer::arena_aware_body(
+                            &f.native_type_data.consumer_from_rust(
+                                &format!("{}(pointer)", f.getter_name()),
+                                f.attributes.expose_as_ident()
+                            ),
+                            from_arena.as_deref(),
+                            2
+                        ),
                     ));
+                    if let Some(borrowed_getter) = borrowed_consumer_getter(f) {
+                        acc.2.push_str("\n\n");
+                        acc.2.push_str(&borrowed_getter);
+                    }
+                    if let Some(lazy_getter) = lazy_consumer_getter(f) {
+                        acc.2.push_str("\n\n");
+                        acc.2.push_str(&lazy_getter);
+                    }
                     // Push an extra line between var declarations.
                     if index < self.fields.len() - 1 {
                         acc.2.push_str("\n\n");
@@ -75,12 +99,241 @@ impl standard::StructFFI<'_> {
                 },
             );
 
+        // Swift rejects trailing commas on argument lists.
+        let consumer_init_args = consumer_init_args.join(",\n");
+        let ffi_init_args = ffi_init_args.join(",\n");
+
+        let consumer_getters = match self.consumer_snapshot() {
+            Some(snapshot) => format!("{consumer_getters}\n\n{snapshot}"),
+            None => consumer_getters,
+        };
+
+        let batch_getters = self
+            .fields
+            .iter()
+            .filter_map(batch_consumer_getter)
+            .collect::<Vec<String>>()
+            .join("\n\n");
+
         ExpandedFields {
             consumer_init_args,
             ffi_init_args,
             consumer_getters,
+            batch_getters,
+        }
+    }
+
+    /// If this struct has the `snapshot` attribute, returns a nested `Snapshot` struct and a
+    /// `snapshot` property that fills it in with a single FFI call.
+    ///
+    /// Each field of the snapshot is owned by the consumer and hydrated with `fromRust`, which
+    /// takes ownership of that field's memory, so the snapshot itself never needs to be freed.
+    ///
+    fn consumer_snapshot(&self) -> Option<String> {
+        if !self.snapshot {
+            return None;
         }
+        // This looks like `public let foo: Bar`.
+        let members: Vec<String> = self
+            .fields
+            .iter()
+            .map(|f| {
+                format!(
+                    "\n{spacer:level$}public let {field}: {type_name}",
+                    spacer = " ",
+                    level = TAB_SIZE * 2,
+                    field = f.field_name.consumer_ident(),
+                    type_name = f
+                        .native_type_data
+                        .consumer_type(f.attributes.expose_as_ident()),
+                )
+            })
+            .collect();
+        let members = members.concat();
+        // This looks like `foo: Bar.fromRust(snapshot.foo)`.
+        let hydrations: Vec<String> = self
+            .fields
+            .iter()
+            .map(|f| {
+                format!(
+                    "{spacer:level$}{field}: {from_rust}",
+                    spacer = " ",
+                    level = TAB_SIZE * 3,
+                    field = f.field_name.consumer_ident(),
+                    from_rust = f.native_type_data.consumer_from_rust(
+                        &format!("snapshot.{}", f.field_name.ffi_ident()),
+                        f.attributes.expose_as_ident()
+                    ),
+                )
+            })
+            .collect();
+        // Swift rejects trailing commas on argument lists.
+        let hydrations = hydrations.join(",\n");
+        Some(format!(
+            "{spacer:l1$}public struct Snapshot {{{members}
+{spacer:l1$}}}
+
+{spacer:l1$}public var snapshot: Snapshot {{
+{spacer:l2$}let snapshot = {getter}(pointer)
+{spacer:l2$}return Snapshot(
+{hydrations}
+{spacer:l2$})
+{spacer:l1$}}}",
+            spacer = " ",
+            l1 = TAB_SIZE,
+            l2 = TAB_SIZE * 2,
+            members = members,
+            getter = self.snapshot_getter_name(),
+            hydrations = hydrations,
+        ))
+    }
+}
+
+/// If `field` has a `borrow` attribute, returns a consumer getter that wraps the borrowed slice
+/// without copying it. Byte buffers are exposed as `Data`; anything else is exposed as a
+/// `BorrowedBuffer`. Either way, the wrapper retains `self` so the memory it points to outlives it.
+///
+/// Fields of other FFI types are exposed as a wrapper around the borrowed pointer (see
+/// `borrowed_pointer_consumer_getter`).
+///
+fn borrowed_consumer_getter(field: &FieldFFI<'_>) -> Option<String> {
+    if !field.attributes.borrow {
+        return None;
     }
+    let element_type = match &field.native_type_data.native_type {
+        TypeIdentifier::Raw(inner) => crate::consumer_type_for(&inner.to_string(), false),
+        TypeIdentifier::Boxed(_) => return Some(borrowed_pointer_consumer_getter(field)),
+        _ => return None,
+    };
+    let (name, initializer, wrapper) = if element_type == "UInt8" {
+        (
+            format!("{}_data", field.field_name.consumer_ident()),
+            "Data",
+            "Data".to_string(),
+        )
+    } else {
+        (
+            format!("{}_buffer", field.field_name.consumer_ident()),
+            "BorrowedBuffer",
+            format!("BorrowedBuffer<{element_type}>"),
+        )
+    };
+    let (type_name, label) = if field.native_type_data.is_option {
+        (format!("{wrapper}?"), "borrowingIfPresent")
+    } else {
+        (wrapper, "borrowing")
+    };
+    // This looks like
+    // `public var foo_buffer: BorrowedBuffer<Double> { BorrowedBuffer(borrowing: get_borrowed_bar_foo(pointer), owner: self) }`.
+    Some(format!(
+        "{spacer:l1$}public var {name}: {type_name} {{
+{spacer:l2$}{initializer}({label}: {getter}(pointer), owner: self)
+{spacer:l1$}}}",
+        spacer = " ",
+        l1 = TAB_SIZE,
+        l2 = TAB_SIZE * 2,
+        name = name,
+        type_name = type_name,
+        initializer = initializer,
+        label = label,
+        getter = field.borrowed_getter_name(),
+    ))
+}
+
+/// Returns a consumer getter that wraps the pointer returned by `field`'s borrowed getter in a
+/// native wrapper that retains `self` (so the pointer stays valid) and never frees it.
+///
+fn borrowed_pointer_consumer_getter(field: &FieldFFI<'_>) -> String {
+    // This looks like
+    // `public var foo_borrowed: Bar { Bar.fromRust(borrowing: get_borrowed_baz_foo(pointer), owner: self) }`.
+    format!(
+        "{spacer:l1$}public var {field}_borrowed: {type_name} {{
+{spacer:l2$}{type_name}.fromRust(borrowing: {getter}(pointer), owner: self)
+{spacer:l1$}}}",
+        spacer = " ",
+        l1 = TAB_SIZE,
+        l2 = TAB_SIZE * 2,
+        field = field.field_name.consumer_ident(),
+        type_name = field.native_type_data.consumer_type(None),
+        getter = field.borrowed_getter_name(),
+    )
+}
+
+/// If `field` has a `batch` attribute, returns a consumer getter (for an extension on a
+/// `Collection` of the type that `field` belongs to) that reads `field` from every element of the
+/// collection in a single call.
+///
+fn batch_consumer_getter(field: &FieldFFI<'_>) -> Option<String> {
+    if !field.attributes.batch {
+        return None;
+    }
+    // This looks like
+    // ```
+    // var foo_batch: [Bar] {
+    //     withBatchReceivers(self) { receivers, len in
+    //         [Bar].fromRust(get_baz_foo_batch(receivers, len))
+    //     }
+    // }
+    // ```
+    let expose_as = field.attributes.expose_as_ident();
+    let results = field.native_type_data.batch_results();
+    Some(format!(
+        "{spacer:l1$}var {field}_batch: {type_name} {{
+{spacer:l2$}withBatchReceivers(self) {{ receivers, len in
+{spacer:l3$}{conversion}
+{spacer:l2$}}}
+{spacer:l1$}}}",
+        spacer = " ",
+        l1 = TAB_SIZE,
+        l2 = TAB_SIZE * 2,
+        l3 = TAB_SIZE * 3,
+        field = field.field_name.consumer_ident(),
+        type_name = results.consumer_type(expose_as),
+        conversion = results.consumer_from_rust(
+            &format!("{}(receivers, len)", field.batch_getter_name()),
+            expose_as
+        ),
+    ))
+}
+
+/// If `field` supports lazy access, returns a consumer getter that wraps it in a
+/// `LazyFFICollection`, which only fetches the elements that are accessed.
+///
+fn lazy_consumer_getter(field: &FieldFFI<'_>) -> Option<String> {
+    if !field.has_lazy_access() {
+        return None;
+    }
+    let element_type = match &field.native_type_data.native_type {
+        TypeIdentifier::Boxed(inner) => field
+            .attributes
+            .expose_as_ident()
+            .unwrap_or(inner)
+            .to_string(),
+        _ => return None,
+    };
+    // This looks like
+    // ```
+    // public var foo_lazy: LazyFFICollection<Bar> {
+    //     LazyFFICollection(count: get_baz_foo_count(pointer), owner: self) { index in
+    //         Bar.fromRust(get_baz_foo_at(self.pointer, index))
+    //     }
+    // }
+    // ```
+    Some(format!(
+        "{spacer:l1$}public var {field}_lazy: LazyFFICollection<{element_type}> {{
+{spacer:l2$}LazyFFICollection(count: {count_fn}(pointer), owner: self) {{ index in
+{spacer:l3$}{element_type}.fromRust({element_at_fn}(self.pointer, index))
+{spacer:l2$}}}
+{spacer:l1$}}}",
+        spacer = " ",
+        l1 = TAB_SIZE,
+        l2 = TAB_SIZE * 2,
+        l3 = TAB_SIZE * 3,
+        field = field.field_name.consumer_ident(),
+        element_type = element_type,
+        count_fn = field.count_fn_name(),
+        element_at_fn = field.element_at_fn_name(),
+    ))
 }
 
 impl From<&standard::StructFFI<'_>> for ConsumerStruct {
@@ -92,12 +345,39 @@ impl From<&standard::StructFFI<'_>> for ConsumerStruct {
             consumer_init_args: expanded_fields.consumer_init_args,
             ffi_init_args: expanded_fields.ffi_init_args,
             consumer_getters: expanded_fields.consumer_getters,
+            batch_getters: expanded_fields.batch_getters,
             init_fn_name: struct_ffi.init_fn_name().to_string(),
-            free_fn_name: struct_ffi.free_fn_name().to_string(),
-            clone_fn_name: struct_ffi.clone_fn_name().to_string(),
+            // Shared instances are retained instead of cloned, so that copies share one instance.
+            free_fn_name: if struct_ffi.shared {
+                struct_ffi.release_fn_name()
+            } else {
+                struct_ffi.free_fn_name()
+            }
+            .to_string(),
+            clone_fn_name: if struct_ffi.shared {
+                struct_ffi.retain_fn_name()
+            } else {
+                struct_ffi.clone_fn_name()
+            }
+            .to_string(),
             failable_init: false,
             forbid_memberwise_init: struct_ffi.forbid_memberwise_init,
             docs: crate::consumer::consumer_docs_from(struct_ffi.doc_comments, 0),
+            generic_conformances: struct_ffi
+                .fields
+                .iter()
+                .flat_map(|f| {
+                    // Batched `Option`s, `Vec`s, and tuples are read in an `FFINestedArray`.
+                    let batch_results =
+                        Some(f.native_type_data.batch_results()).filter(|_| f.attributes.batch);
+                    std::iter::once(&f.native_type_data)
+                        .chain(batch_results.as_ref())
+                        .flat_map(|ty| {
+                            ty.consumer_generic_conformances(f.attributes.expose_as_ident())
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect(),
         }
     }
 }
@@ -0,0 +1,27 @@
+use crate::{
+    consumer::ConsumerOutput, items::struct_ffi::transparent, parsing::TypeAttributes,
+    type_ffi::TypeFFI,
+};
+
+// Transparent types are exposed just like the type they wrap, so the consumer only needs a
+// `typealias`, which keeps the type's name in consumer signatures without any extra conversions.
+impl ConsumerOutput for transparent::StructFFI<'_> {
+    fn write_output(&self, out_dir: &str) {
+        let inner = TypeFFI::from(TypeAttributes::initial(
+            self.inner.clone(),
+            vec![],
+            vec![],
+            None,
+        ));
+        let contents = format!(
+            "{header}\n\n{docs}public typealias {type_name} = {inner}\n",
+            header = crate::consumer::header_and_imports(self.consumer_imports),
+            docs = crate::consumer::consumer_docs_from(self.doc_comments, 0),
+            type_name = self.type_name,
+            inner = inner.consumer_type(None),
+        );
+        let file_name = format!("{}.swift", self.type_name);
+        crate::write_consumer_file(&file_name, contents, out_dir)
+            .unwrap_or_else(|err| proc_macro_error::abort!("Error writing consumer file: {}", err));
+    }
+}
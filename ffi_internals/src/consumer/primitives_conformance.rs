@@ -30,9 +30,14 @@ pub(super) fn generate(native_type: &str, ffi_type: &str, consumer_type: &str) -
             &format!("option_{}_init", native_type),
             &format!("option_{}_free", native_type),
         ),
+        slice_conformance(native_type, ffi_type),
         consumer_type_base(consumer_type, ffi_type),
         consumer_array_type(consumer_type, &format!("FFIArray{}", native_type)),
     ]
+    .iter()
+    .filter(|conformance| !conformance.is_empty())
+    .cloned()
+    .collect::<Vec<String>>()
     .join("\n\n")
 }
 
@@ -56,9 +61,31 @@ extension {}: FFIArray {{
     )
 }
 
+/// Conformance for the borrowed `FFISlice` type for `native_type`.
+///
+/// Types that are converted to a different FFI type (like `u128`) don't have a slice type, so this
+/// is empty for those.
+///
+fn slice_conformance(native_type: &str, ffi_type: &str) -> String {
+    if crate::type_ffi::raw_type_is_wrapped(&quote::format_ident!("{}", native_type)) {
+        return String::new();
+    }
+    format!(
+        "// MARK: - FFISlice
+extension FFISlice{native_type}: FFISlice {{
+    public typealias Value = {ffi_type}
+}}"
+    )
+}
+
 /// Conversion from the consumer's native optional type to the Option type for `native_type`.
 ///
-fn option_conformance(consumer_type: &str, ffi_type: &str, init: &str, free: &str) -> String {
+pub(super) fn option_conformance(
+    consumer_type: &str,
+    ffi_type: &str,
+    init: &str,
+    free: &str,
+) -> String {
     format!(
         "// MARK: - Optional
 public extension Optional where Wrapped == {} {{
@@ -101,7 +128,36 @@ public extension Optional where Wrapped == {} {{
 
 /// Linking between the Rust and consumer base types.
 ///
+/// A `Character` is passed through the FFI as the value of its Unicode scalar, so it needs an
+/// actual conversion; every other primitive is passed as-is.
+///
 fn consumer_type_base(consumer_type: &str, ffi_type: &str) -> String {
+    if consumer_type == "Character" {
+        return format!(
+            "// MARK: - NativeData
+extension Character: NativeData {{
+    public typealias ForeignType = {ffi_type}
+
+    /// A Rust `char` is a single Unicode scalar, so a `Character` made up of several scalars (like
+    /// an emoji with a skin tone modifier) can't be passed to Rust; use a `String` instead.
+    public func clone() -> ForeignType {{
+        precondition(
+            unicodeScalars.count == 1,
+            \"`\\(self)` is made up of \\(unicodeScalars.count) Unicode scalars, so it can't be passed to Rust as a `char`.\"
+        )
+        return unicodeScalars.first!.value
+    }}
+
+    public func borrowReference() -> ForeignType {{
+        return clone()
+    }}
+
+    public static func fromRust(_ foreignObject: ForeignType) -> Self {{
+        return Character(Unicode.Scalar(foreignObject) ?? \"\\u{{FFFD}}\")
+    }}
+}}"
+        );
+    }
     format!(
         "// MARK: - NativeData
 extension {}: NativeData {{
@@ -125,7 +181,7 @@ extension {}: NativeData {{
 
 /// Linking between the Rust and consumer array types.
 ///
-fn consumer_array_type(consumer_type: &str, ffi_array_type: &str) -> String {
+pub(super) fn consumer_array_type(consumer_type: &str, ffi_array_type: &str) -> String {
     format!(
         "// MARK: - NativeArrayData
 extension {}: NativeArrayData {{
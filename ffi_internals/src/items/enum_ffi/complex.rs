@@ -3,7 +3,7 @@
 //! building the related FFI.
 //!
 
-use crate::items::field_ffi::FieldFFI;
+use crate::items::field_ffi::{FieldFFI, FieldIdent};
 use heck::SnakeCase;
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
@@ -39,6 +39,120 @@ impl<'a> VariantFFI<'a> {
             self.ident.to_string().to_snake_case()
         )
     }
+
+    /// The name of the `repr(C)` type that holds the FFI representation of each of this variant's
+    /// fields.
+    ///
+    #[must_use]
+    pub fn payload_type_name(&self, type_name: &Ident) -> Ident {
+        format_ident!("{}{}Payload", type_name, self.ident)
+    }
+
+    /// The name of the function that returns this variant's payload, or null if an instance is a
+    /// different variant.
+    ///
+    #[must_use]
+    pub fn try_as_fn_name(&self, type_name: &Ident) -> Ident {
+        format_ident!(
+            "{}_try_as_{}",
+            type_name.to_string().to_snake_case(),
+            self.ident.to_string().to_snake_case()
+        )
+    }
+
+    /// The name of the function that frees this variant's payload.
+    ///
+    #[must_use]
+    pub fn payload_free_fn_name(&self, type_name: &Ident) -> Ident {
+        format_ident!(
+            "free_{}_{}_payload",
+            type_name.to_string().to_snake_case(),
+            self.ident.to_string().to_snake_case()
+        )
+    }
+
+    /// If this variant has any fields, returns its payload type, along with functions for reading
+    /// and freeing a payload. Otherwise, returns an empty `TokenStream`.
+    ///
+    fn payload_items(&self, type_name: &Ident, other_variants: &[(Ident, usize)]) -> TokenStream {
+        if self.fields.is_empty() {
+            return quote!();
+        }
+        let variant_ident = self.ident;
+        let payload_type_name = self.payload_type_name(type_name);
+        let try_as_fn_name = self.try_as_fn_name(type_name);
+        let payload_free_fn_name = self.payload_free_fn_name(type_name);
+        let (fields, bindings, assignments, frees) = self.fields.iter().fold(
+            (quote!(), quote!(), quote!(), quote!()),
+            |mut acc, field| {
+                let ffi_ident = field.field_name.ffi_ident();
+                // Bind each field to its FFI identifier (as in `{ 0: unnamed_field_0, 1: unnamed_field_1 }`).
+                let binding = match &field.field_name {
+                    FieldIdent::NamedField(_) => quote!(#ffi_ident,),
+                    FieldIdent::UnnamedField(_) | FieldIdent::Renamed(..) => {
+                        let rust_token = field.field_name.rust_token();
+                        quote!(#rust_token: #ffi_ident,)
+                    }
+                };
+                let conversion = field
+                    .native_type_data
+                    .rust_to_ffi_value(&quote!((*#ffi_ident)), &field.attributes);
+                acc.0.extend(field.snapshot_field());
+                acc.1.extend(binding);
+                acc.2.extend(quote!(#ffi_ident: #conversion,));
+                acc.3.extend(field.payload_free());
+                acc
+            },
+        );
+        let wrong_variant_arms = crate::items::field_ffi::wrong_variant_arms(
+            type_name,
+            variant_ident,
+            other_variants,
+            &quote!(ptr::null()),
+        );
+        quote! {
+            ffi_common::core::paste! {
+                #[repr(C)]
+                #[derive(Debug)]
+                #[doc = "The FFI representation of every field of a `" #type_name "::" #variant_ident "`."]
+                pub struct #payload_type_name {
+                    #fields
+                }
+
+                #[no_mangle]
+                #[doc = "Get every field of this `" #type_name "` if it's a `" #variant_ident "`."]
+                #[doc = ""]
+                #[doc = "If this is a different variant, this sets the last error message and returns a null pointer. Otherwise, the payload is owned by the caller, and must be freed with `" #payload_free_fn_name "`."]
+                pub unsafe extern "C" fn #try_as_fn_name(ptr: *const #type_name) -> *const #payload_type_name {
+                    match &*ptr {
+                        #type_name::#variant_ident { #bindings } => {
+                            ffi_common::core::allocator::export_box(#payload_type_name {
+                                #assignments
+                            })
+                        }
+                        #wrong_variant_arms
+                    }
+                }
+
+                #[no_mangle]
+                #[doc = "Free a `" #payload_type_name "` and each of its fields."]
+                pub unsafe extern "C" fn #payload_free_fn_name(payload: *const #payload_type_name) {
+                    if payload.is_null() {
+                        return;
+                    }
+                    let payload = ffi_common::core::allocator::import_box(payload);
+                    #frees
+                }
+            }
+        }
+    }
+
+    /// True if this is a struct-like variant (as in `Variant { field: Type }`), whose fields are
+    /// labeled in the consumer.
+    ///
+    pub(crate) fn has_named_fields(&self) -> bool {
+        self.fields.iter().any(|field| field.field_name.is_named())
+    }
 }
 
 /// Represents the components of an enum for generating an FFI.
@@ -180,6 +294,7 @@ impl<'a> EnumFFI<'a> {
 }
 
 impl<'a> From<EnumFFI<'_>> for TokenStream {
+    #[allow(clippy::too_many_lines)]
     fn from(enum_ffi: EnumFFI<'_>) -> Self {
         let type_name = enum_ffi.type_name;
         let module_name = enum_ffi.module_name;
@@ -207,7 +322,7 @@ impl<'a> From<EnumFFI<'_>> for TokenStream {
             let variant_case = if variant.fields.is_empty() {
                 quote!(#variant_ident)
             } else {
-                quote!(#variant_ident(..))
+                quote!(#variant_ident { .. })
             };
             acc.extend(quote! {
                 #type_name::#variant_case => #reprc_enum::#variant_ident,
@@ -215,6 +330,17 @@ impl<'a> From<EnumFFI<'_>> for TokenStream {
             acc
         });
 
+        let payload_items = enum_ffi.variants.iter().fold(quote!(), |mut acc, variant| {
+            let other_variants: Vec<(Ident, usize)> = enum_ffi
+                .variants
+                .iter()
+                .filter(|other| other.ident != variant.ident)
+                .map(|other| (other.ident.clone(), other.fields.len()))
+                .collect();
+            acc.extend(variant.payload_items(type_name, &other_variants));
+            acc
+        });
+
         let initializers = enum_ffi.variants.iter().fold(quote!(), |mut acc, variant| {
             let variant_ident = &variant.ident;
             let init_fn_name = variant.init_fn_name(enum_ffi.type_name);
@@ -231,15 +357,15 @@ impl<'a> From<EnumFFI<'_>> for TokenStream {
                     .iter()
                     .map(FieldFFI::assignment_expression)
                     .collect();
-                quote!((#(#assignments),*))
+                quote!({ #(#assignments)* })
             };
             let init_fn = quote! {
                 /// # Safety
                 /// `data` must not be a null pointer, and it must point to the appropriate type for `variant`. Otherwise, this will panic.
                 ///
                 #[no_mangle]
-                pub unsafe extern "C" fn #init_fn_name(#(#args),*) -> *const #type_name {
-                    Box::into_raw(Box::new(#type_name::#variant_ident#assignment))
+                pub unsafe extern "C" fn #init_fn_name(#(#args)*) -> *const #type_name {
+                    <#type_name as ffi_common::core::handle::OpaqueHandle>::into_handle(#type_name::#variant_ident#assignment)
                 }
             };
             acc.extend(init_fn);
@@ -255,8 +381,10 @@ impl<'a> From<EnumFFI<'_>> for TokenStream {
         quote! {
             #[allow(box_pointers, missing_docs)]
             pub mod #module_name {
-                use ffi_common::core::{error, paste, declare_opaque_type_ffi};
+                use ffi_common::core::{*, paste, datetime::*, string::FFIArrayString};
                 use std::any::Any;
+                use std::os::raw::c_char;
+                use std::{ffi::{CStr, CString}, mem::ManuallyDrop, ptr};
                 #(#ffi_mod_imports)*
                 use super::*;
 
@@ -275,11 +403,13 @@ impl<'a> From<EnumFFI<'_>> for TokenStream {
 
                 #variant_value_getters
 
+                #payload_items
+
                 #initializers
 
                 #[no_mangle]
                 pub unsafe extern "C" fn #clone_fn_name(ptr: *const #type_name) -> *const #type_name {
-                    Box::into_raw(Box::new((&*ptr).clone()))
+                    <#type_name as ffi_common::core::handle::OpaqueHandle>::retain_handle(ptr)
                 }
 
                 /// # Safety
@@ -287,7 +417,7 @@ impl<'a> From<EnumFFI<'_>> for TokenStream {
                 ///
                 #[no_mangle]
                 pub unsafe extern "C" fn #free_fn_name(data: *const #type_name) {
-                    drop(Box::from_raw(data as *mut #type_name));
+                    <#type_name as ffi_common::core::handle::OpaqueHandle>::release_handle(data);
                 }
 
                 declare_opaque_type_ffi! { #type_name }
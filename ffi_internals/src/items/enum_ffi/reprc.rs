@@ -1,11 +1,20 @@
 //!
 //! Contains structures describing a repr(C) enum, and implementations for building the related FFI.
 //!
+//! Enums with an integer `repr` (like `repr(u8)`) are exposed the same way, but they also record
+//! the discriminant of each variant, so that the consumer can use them as raw values, and get a
+//! checked conversion from a raw value (`{type}_from_raw`).
+//!
+//! Every variant can also be listed (`{type}_all_variants`), and converted to and from its name
+//! (`{type}_variant_name` and `{type}_variant_from_name`), for consumers that persist variants by
+//! name or need to present a list of them.
+//!
 
 use heck::SnakeCase;
 use proc_macro2::TokenStream;
+use proc_macro_error::abort;
 use quote::{format_ident, quote};
-use syn::Ident;
+use syn::{spanned::Spanned, DataEnum, Expr, ExprLit, ExprUnary, Ident, Lit, UnOp};
 
 /// Represents a `repr(C)` enum. This type can be converted into a `proc_macro2::TokenStream` to
 /// produce an FFI for the type it represents.
@@ -15,22 +24,198 @@ pub struct EnumFFI<'a> {
     /// The name of the type that this represents.
     ///
     pub type_name: &'a Ident,
+    /// The integer representation of this enum (like `u8` for `repr(u8)`), if it has one.
+    ///
+    pub repr: Option<Ident>,
+    /// The name of each variant of this enum.
+    ///
+    pub variants: Vec<Ident>,
+    /// Each variant of this enum, and its discriminant. This is only populated for enums with an
+    /// integer `repr`, since we don't need the discriminants of a `repr(C)` enum.
+    ///
+    pub discriminants: Vec<(Ident, i128)>,
 }
 
 impl<'a> EnumFFI<'a> {
     /// Create a new `EnumFFI` from derive macro inputs.
     ///
+    /// # Proc Macro Errors
+    ///
+    /// Fails if `repr` is provided and any of the discriminants in `data` isn't an integer literal.
+    ///
     #[must_use]
-    pub const fn new(module_name: &'a Ident, type_name: &'a Ident) -> Self {
+    pub fn new(
+        module_name: &'a Ident,
+        type_name: &'a Ident,
+        data: &DataEnum,
+        repr: Option<Ident>,
+    ) -> Self {
+        let discriminants = if repr.is_some() {
+            discriminants(data)
+        } else {
+            Vec::new()
+        };
         Self {
             module_name,
             type_name,
+            repr,
+            variants: data
+                .variants
+                .iter()
+                .map(|variant| variant.ident.clone())
+                .collect(),
+            discriminants,
         }
     }
 
     fn free_fn_name(&self) -> Ident {
         format_ident!("free_{}", &self.type_name.to_string().to_snake_case())
     }
+
+    fn raw_conversion_fn_name(&self) -> Ident {
+        format_ident!("{}_from_raw", &self.type_name.to_string().to_snake_case())
+    }
+
+    /// Generates `{type}_all_variants`, `{type}_variant_name`, and `{type}_variant_from_name`.
+    ///
+    fn variant_name_fns(&self) -> TokenStream {
+        let type_name = self.type_name;
+        let type_name_snake = type_name.to_string().to_snake_case();
+        let all_variants_fn_name = format_ident!("{}_all_variants", type_name_snake);
+        let variant_name_fn_name = format_ident!("{}_variant_name", type_name_snake);
+        let variant_from_name_fn_name = format_ident!("{}_variant_from_name", type_name_snake);
+        let array_name = format_ident!("FFIArray{}", type_name);
+        let option_init_fn_name = format_ident!("option_{}_init", type_name);
+        let option_free_fn_name = format_ident!("option_{}_free", type_name);
+        let variants = &self.variants;
+        let names: Vec<String> = variants.iter().map(ToString::to_string).collect();
+        quote! {
+            paste! {
+                #[no_mangle]
+                #[doc = "Returns every variant of `" #type_name "`, in the order they're declared."]
+                #[doc = ""]
+                #[doc = "# Safety"]
+                #[doc = ""]
+                #[doc = "You must pass the returned array to `ffi_array_" #type_name "_free` once you're finished with it."]
+                pub extern "C" fn #all_variants_fn_name() -> #array_name {
+                    #array_name::from(vec![#(#type_name::#variants),*])
+                }
+
+                #[no_mangle]
+                #[doc = "Returns the name of `variant`, which is the same as the name of the variant in Rust."]
+                #[doc = ""]
+                #[doc = "# Safety"]
+                #[doc = ""]
+                #[doc = "You must pass the returned string to `free_rust_string` once you're finished with it."]
+                pub extern "C" fn #variant_name_fn_name(variant: #type_name) -> *const std::os::raw::c_char {
+                    let name = match variant {
+                        #(#type_name::#variants => #names,)*
+                    };
+                    ffi_common::core::ffi_string!(name)
+                }
+
+                #[no_mangle]
+                #[doc = "Returns the `" #type_name "` variant named `name`, or a null pointer if there isn't one (in which case the reason is available through the last error)."]
+                #[doc = ""]
+                #[doc = "# Safety"]
+                #[doc = ""]
+                #[doc = "`name` must not be a null pointer. If the returned pointer is not null, you must pass it to `" #option_free_fn_name "` once you're finished with it."]
+                pub unsafe extern "C" fn #variant_from_name_fn_name(name: *const std::os::raw::c_char) -> *const #type_name {
+                    match ffi_common::core::string::string_from_c(name).as_str() {
+                        #(#names => #option_init_fn_name(true, #type_name::#variants),)*
+                        other => {
+                            error::set_last_err_msg(&format!(
+                                "`{}` isn't the name of a `{}` variant.",
+                                other,
+                                stringify!(#type_name)
+                            ));
+                            std::ptr::null()
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Generates `{type}_from_raw`, which checks `raw` against each variant's discriminant instead
+    /// of transmuting it.
+    ///
+    fn raw_conversion_fn(&self) -> Option<TokenStream> {
+        let repr = self.repr.as_ref()?;
+        let type_name = self.type_name;
+        let raw_conversion_fn_name = self.raw_conversion_fn_name();
+        let option_init_fn_name = format_ident!("option_{}_init", type_name);
+        let option_free_fn_name = format_ident!("option_{}_free", type_name);
+        let variants = self.discriminants.iter().map(|(variant, _)| variant);
+        Some(quote! {
+            paste! {
+                #[no_mangle]
+                #[doc = "Returns the `" #type_name "` whose discriminant is `raw`, or a null pointer if there isn't one (in which case the reason is available through the last error)."]
+                #[doc = ""]
+                #[doc = "# Safety"]
+                #[doc = ""]
+                #[doc = "If the returned pointer is not null, you must pass it to `" #option_free_fn_name "` once you're finished with it."]
+                pub extern "C" fn #raw_conversion_fn_name(raw: #repr) -> *const #type_name {
+                    match raw {
+                        #(raw if raw == #type_name::#variants as #repr => {
+                            #option_init_fn_name(true, #type_name::#variants)
+                        })*
+                        _ => {
+                            error::set_last_err_msg(&format!(
+                                "`{}` isn't a valid raw value for `{}`.",
+                                raw,
+                                stringify!(#type_name)
+                            ));
+                            std::ptr::null()
+                        }
+                    }
+                }
+            }
+        })
+    }
+}
+
+/// Returns each variant in `data` with its discriminant, which is either the integer literal it's
+/// assigned, or one more than the previous variant's discriminant (starting at `0`), just like
+/// Rust's own rules.
+///
+/// # Proc Macro Errors
+///
+/// Fails if any variant's discriminant isn't an integer literal.
+///
+fn discriminants(data: &DataEnum) -> Vec<(Ident, i128)> {
+    let mut next = 0;
+    data.variants
+        .iter()
+        .map(|variant| {
+            let discriminant = variant
+                .discriminant
+                .as_ref()
+                .map_or(next, |(_, expr)| discriminant_value(expr));
+            next = discriminant + 1;
+            (variant.ident.clone(), discriminant)
+        })
+        .collect()
+}
+
+fn discriminant_value(expr: &Expr) -> i128 {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(int), ..
+        }) => int
+            .base10_parse()
+            .unwrap_or_else(|err| abort!(int.span(), "{}", err)),
+        Expr::Unary(ExprUnary {
+            op: UnOp::Neg(_),
+            expr,
+            ..
+        }) => -discriminant_value(expr),
+        Expr::Group(group) => discriminant_value(&group.expr),
+        _ => abort!(
+            expr.span(),
+            "Discriminants of enums with an integer `repr` must be integer literals."
+        ),
+    }
 }
 
 impl From<EnumFFI<'_>> for TokenStream {
@@ -38,6 +223,8 @@ impl From<EnumFFI<'_>> for TokenStream {
         let module_name = enum_ffi.module_name;
         let type_name = enum_ffi.type_name;
         let free_fn_name = enum_ffi.free_fn_name();
+        let raw_conversion_fn = enum_ffi.raw_conversion_fn();
+        let variant_name_fns = enum_ffi.variant_name_fns();
         quote! {
             #[allow(missing_docs)]
             pub mod #module_name {
@@ -49,6 +236,10 @@ impl From<EnumFFI<'_>> for TokenStream {
                     let _ = data;
                 }
 
+                #raw_conversion_fn
+
+                #variant_name_fns
+
                 declare_value_type_ffi! { #type_name }
             }
         }
@@ -12,7 +12,7 @@ use parsing::FieldAttributes;
 use proc_macro2::TokenStream;
 use proc_macro_error::abort;
 use quote::{format_ident, quote};
-use syn::{spanned::Spanned, Attribute, Fields, Ident};
+use syn::{spanned::Spanned, Fields, Ident};
 
 #[derive(Debug, Clone)]
 pub(crate) enum FieldSource<'a> {
@@ -117,29 +117,32 @@ impl<'a> FieldFFI<'a> {
                         "Internal error: `other_variants` contains `variant`"
                     );
                 }
-                let accessor = quote!(data);
+                let field_name = &self.field_name.rust_token();
+                // `data` is a reference to the field, so `(*data)` can be converted just like a
+                // struct's `data.field`.
+                let accessor = quote!((*data));
                 let conversion = self
                     .native_type_data
                     .rust_to_ffi_value(&accessor, &self.attributes);
 
-                let valid_arm = quote!(#type_name::#variant_ident(#accessor) => #conversion,);
-
-                let invalid_arms = other_variants
-                    .iter()
-                    .fold(quote!(), |mut acc, variant| {
-                        let (variant_ident, variant_field_count) = &variant;
-                        let argument = if *variant_field_count == 0 {
-                            quote!()
-                        } else {
-                            quote!((..))
-                        };
-                        acc.extend(quote!(#type_name::#variant_ident#argument => unreachable!("This arm is unreachable."),));
-                        acc
-                    });
+                // Braced patterns work for both tuple and struct-like variants (as in
+                // `Type::Variant { 0: data, .. }`).
+                let valid_arm =
+                    quote!(#type_name::#variant_ident { #field_name: data, .. } => #conversion,);
+
+                let invalid_arms = wrong_variant_arms(
+                    type_name,
+                    variant_ident,
+                    other_variants,
+                    &self.native_type_data.ffi_placeholder_value(),
+                );
 
                 quote! {
                     ffi_common::core::paste! {
                         #[no_mangle]
+                        #[doc = "Get `" #field_name "` for this `" #type_name "::" #variant_ident "`."]
+                        #[doc = ""]
+                        #[doc = "If this is a different variant, this sets the last error message and returns a null pointer (or a default value, for types that are passed by value)."]
                         pub unsafe extern "C" fn #getter_name(
                             ptr: *const #type_name
                         ) -> #ffi_type {
@@ -154,11 +157,319 @@ impl<'a> FieldFFI<'a> {
         }
     }
 
+    /// The name of the generated borrowed getter function, which returns a view into this field's
+    /// data instead of a copy of it.
+    ///
+    #[must_use]
+    pub fn borrowed_getter_name(&self) -> Ident {
+        format_ident!(
+            "get_borrowed_{}_{}",
+            self.type_name.to_string().to_snake_case(),
+            self.field_name.ffi_ident().to_string().to_snake_case()
+        )
+    }
+
+    /// If this field has a `borrow` attribute, returns an extern "C" function for returning a
+    /// non-owning view of the field's data: an `FFISlice*` for collections of primitives, as in
+    /// `pub extern "C" fn get_borrowed_some_type_field(ptr: *const SomeType) -> FFISliceT`, or a
+    /// pointer to the field itself for other FFI types (or null, for `None`), as in
+    /// `pub extern "C" fn get_borrowed_some_type_field(ptr: *const SomeType) -> *const T`.
+    ///
+    /// The view is only valid while the instance at `ptr` is alive, so there's no matching free
+    /// function; the consumer must keep the owner alive for as long as it reads from the view, and
+    /// must never free it.
+    ///
+    #[must_use]
+    pub fn borrowed_getter_fn(&self) -> Option<TokenStream> {
+        if !self.attributes.borrow {
+            return None;
+        }
+        let type_name = self.type_name;
+        let getter_name = self.borrowed_getter_name();
+        let field_name = &self.field_name.rust_token();
+        let (return_type, conversion) =
+            if let Some(slice_type) = self.native_type_data.borrowed_slice_type() {
+                let conversion = if self.native_type_data.is_option {
+                    quote!(data.#field_name.as_deref().into())
+                } else {
+                    quote!((&*data.#field_name).into())
+                };
+                (slice_type, conversion)
+            } else {
+                let pointer_type = self.native_type_data.borrowed_pointer_type()?;
+                let conversion = if self.native_type_data.is_option {
+                    quote!(data.#field_name.as_ref().map_or(ptr::null(), |f| f as #pointer_type))
+                } else {
+                    quote!(&data.#field_name as #pointer_type)
+                };
+                (pointer_type, conversion)
+            };
+        Some(quote! {
+            ffi_common::core::paste! {
+                #[no_mangle]
+                #[doc = "Borrow `" #field_name "` for this `" #type_name "` without copying it."]
+                pub unsafe extern "C" fn #getter_name(
+                    ptr: *const #type_name
+                ) -> #return_type {
+                    let data = &*ptr;
+                    #conversion
+                }
+            }
+        })
+    }
+
+    /// True if this is a `Vec` (or `Option<Vec>`) field of a boxed type on a struct, which means
+    /// that we expose its count and its elements individually, so that consumers can avoid cloning
+    /// every element up front. A `None` collection has a count of 0.
+    ///
+    #[must_use]
+    pub const fn has_lazy_access(&self) -> bool {
+        matches!(self.field_source, FieldSource::Struct)
+            && matches!(self.native_type_data.native_type, TypeIdentifier::Boxed(_))
+            && self.native_type_data.is_vec
+            && !self.native_type_data.is_composite()
+    }
+
+    /// The name of the generated function that returns the number of elements in this field.
+    ///
+    #[must_use]
+    pub fn count_fn_name(&self) -> Ident {
+        format_ident!("{}_count", self.getter_name())
+    }
+
+    /// The name of the generated function that returns a single element of this field.
+    ///
+    #[must_use]
+    pub fn element_at_fn_name(&self) -> Ident {
+        format_ident!("{}_at", self.getter_name())
+    }
+
+    /// If this field has lazy access (see `has_lazy_access`), returns extern "C" functions for
+    /// getting the number of elements in the field, and for getting a clone of the element at a
+    /// specific index, as in `pub extern "C" fn get_some_type_field_count(ptr: *const SomeType) ->
+    /// usize` and `pub extern "C" fn get_some_type_field_at(ptr: *const SomeType, index: usize) ->
+    /// *const T`.
+    ///
+    /// If `index` is out of bounds, the element getter sets the last error message and returns a
+    /// null pointer.
+    ///
+    #[must_use]
+    pub fn lazy_access_fns(&self) -> Option<TokenStream> {
+        if !self.has_lazy_access() {
+            return None;
+        }
+        let inner = match &self.native_type_data.native_type {
+            TypeIdentifier::Boxed(inner) => self.attributes.expose_as_ident().unwrap_or(inner),
+            _ => return None,
+        };
+        let type_name = self.type_name;
+        let field_name = &self.field_name.rust_token();
+        let count_fn_name = self.count_fn_name();
+        let element_at_fn_name = self.element_at_fn_name();
+        let (count, element_at) = if self.native_type_data.is_option {
+            (
+                quote!(data.#field_name.as_ref().map_or(0, |elements| elements.len())),
+                quote!(data.#field_name.as_ref().and_then(|elements| elements.get(index))),
+            )
+        } else {
+            (
+                quote!(data.#field_name.len()),
+                quote!(data.#field_name.get(index)),
+            )
+        };
+        let mut element = quote!(element.clone());
+        // If this field is exposed as a different type for FFI, convert it back to the native type.
+        if self.attributes.expose_as.is_some() {
+            element = quote!(#element.into());
+        }
+        Some(quote! {
+            ffi_common::core::paste! {
+                #[no_mangle]
+                #[doc = "Get the number of elements in `" #field_name "` for this `" #type_name "`."]
+                pub unsafe extern "C" fn #count_fn_name(
+                    ptr: *const #type_name
+                ) -> usize {
+                    let data = &*ptr;
+                    #count
+                }
+
+                #[no_mangle]
+                #[doc = "Get the element at `index` in `" #field_name "` for this `" #type_name "`."]
+                #[doc = ""]
+                #[doc = "If `index` is out of bounds, this sets the last error message and returns a null pointer."]
+                pub unsafe extern "C" fn #element_at_fn_name(
+                    ptr: *const #type_name,
+                    index: usize
+                ) -> *const #inner {
+                    let data = &*ptr;
+                    match #element_at {
+                        Some(element) => <#inner as ffi_common::core::handle::OpaqueHandle>::into_handle(#element),
+                        None => {
+                            ffi_common::core::error::set_last_err_msg(&format!(
+                                "Index {} is out of bounds for `{}` with {} elements.",
+                                index,
+                                stringify!(#field_name),
+                                #count
+                            ));
+                            ptr::null()
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    /// The name of the generated function that reads this field from an array of instances.
+    ///
+    #[must_use]
+    pub fn batch_getter_name(&self) -> Ident {
+        format_ident!("{}_batch", self.getter_name())
+    }
+
+    /// If this field has a `batch` attribute, returns an extern "C" function for reading this field
+    /// from each instance in an array of borrowed pointers in a single call, as in
+    /// `pub extern "C" fn get_some_type_field_batch(receivers: *const *const SomeType, len: usize)
+    /// -> FFIArrayT`.
+    ///
+    #[must_use]
+    pub fn batch_getter_fn(&self) -> Option<TokenStream> {
+        if !self.attributes.batch {
+            return None;
+        }
+        let type_name = self.type_name;
+        let getter_name = self.batch_getter_name();
+        let field_name = &self.field_name.rust_token();
+        let expose_as = self.attributes.expose_as_ident();
+        let element_type = self.native_type_data.ffi_type(expose_as, Context::Return);
+        let array_type = self
+            .native_type_data
+            .batch_results()
+            .ffi_type(expose_as, Context::Return);
+        let conversion = self
+            .native_type_data
+            .rust_to_ffi_value(&quote!(data.#field_name), &self.attributes);
+        Some(quote! {
+            ffi_common::core::paste! {
+                #[no_mangle]
+                #[doc = "Get `" #field_name "` for each `" #type_name "` in `receivers` in a single call."]
+                #[doc = ""]
+                #[doc = "The receivers are borrowed; the caller retains ownership of them."]
+                pub unsafe extern "C" fn #getter_name(
+                    receivers: *const *const #type_name,
+                    len: usize
+                ) -> #array_type {
+                    let values: Vec<#element_type> = ffi_common::core::batch::receivers(receivers, len)
+                        .map(|data| -> #element_type { #conversion })
+                        .collect();
+                    values.into()
+                }
+            }
+        })
+    }
+
+    /// The name of the generated getter that returns this field into an arena.
+    ///
+    #[must_use]
+    pub fn arena_getter_name(&self) -> Ident {
+        format_ident!("{}_in_arena", self.getter_name())
+    }
+
+    /// An extern "C" function that returns the value of this field like `getter_fn`, but transfers
+    /// ownership of it to an `FFIArena`, as in `pub extern "C" fn get_some_type_field_in_arena(ptr:
+    /// *const SomeType, arena: *mut FFIArena) -> FFIType`.
+    ///
+    /// Fields that are passed by value (i.e. non-optional raw types) don't allocate anything for
+    /// the caller to release, so they don't get an arena getter.
+    ///
+    #[must_use]
+    pub fn arena_getter_fn(&self) -> TokenStream {
+        if !matches!(self.field_source, FieldSource::Struct) {
+            return quote!();
+        }
+        let expose_as = self.attributes.expose_as_ident();
+        let free = self
+            .native_type_data
+            .free_ffi_value(&quote!(value), expose_as);
+        if free.is_empty() {
+            return quote!();
+        }
+        let type_name = self.type_name;
+        let field_name = &self.field_name.rust_token();
+        let getter_name = self.getter_name();
+        let arena_getter_name = self.arena_getter_name();
+        let ffi_type = self.native_type_data.ffi_type(expose_as, Context::Return);
+        quote! {
+            ffi_common::core::paste! {
+                #[no_mangle]
+                #[doc = "Get `" #field_name "` for this `" #type_name "`, owned by `arena`."]
+                #[doc = ""]
+                #[doc = "The result is released when `arena` is freed with `ffi_arena_free`, and must not be freed individually."]
+                pub unsafe extern "C" fn #arena_getter_name(
+                    ptr: *const #type_name,
+                    arena: *mut ffi_common::core::arena::FFIArena
+                ) -> #ffi_type {
+                    ffi_common::core::arena::adopt(arena, #getter_name(ptr), |value| { #free })
+                }
+            }
+        }
+    }
+
+    /// The declaration of this field in a `repr(C)` snapshot of the type it belongs to (or in the
+    /// payload of the enum variant it belongs to), as in `pub field: FFIType,`.
+    ///
+    #[must_use]
+    pub fn snapshot_field(&self) -> TokenStream {
+        let field_name = &self.field_name.ffi_ident();
+        let ffi_type = &self
+            .native_type_data
+            .ffi_type(self.attributes.expose_as_ident(), Context::Return);
+        quote!(pub #field_name: #ffi_type,)
+    }
+
+    /// Expression for filling in this field of a snapshot from `data`, a reference to the instance
+    /// being snapshotted.
+    ///
+    #[must_use]
+    pub fn snapshot_assignment(&self) -> TokenStream {
+        let ffi_ident = &self.field_name.ffi_ident();
+        let field_name = &self.field_name.rust_token();
+        let conversion = self
+            .native_type_data
+            .rust_to_ffi_value(&quote!(data.#field_name), &self.attributes);
+        quote!(#ffi_ident: #conversion,)
+    }
+
+    /// Statement for releasing this field's data in `snapshot`.
+    ///
+    #[must_use]
+    pub fn snapshot_free(&self) -> TokenStream {
+        let ffi_ident = &self.field_name.ffi_ident();
+        self.native_type_data.free_ffi_value(
+            &quote!(snapshot.#ffi_ident),
+            self.attributes.expose_as_ident(),
+        )
+    }
+
+    /// Statement for releasing this field's data in `payload`, an enum variant's payload (see
+    /// `enum_ffi::complex`).
+    ///
+    #[must_use]
+    pub fn payload_free(&self) -> TokenStream {
+        let ffi_ident = &self.field_name.ffi_ident();
+        self.native_type_data.free_ffi_value(
+            &quote!(payload.#ffi_ident),
+            self.attributes.expose_as_ident(),
+        )
+    }
+
     /// The memberwise initializer argument for passing a value for this field in to an FFI
-    /// initializer.
+    /// initializer (or nothing, if the field is `readonly`).
     ///
     #[must_use]
     pub fn ffi_initializer_argument(&self) -> TokenStream {
+        if self.attributes.readonly {
+            return quote!();
+        }
         let field_name = &self.field_name.ffi_ident();
         let ffi_type = &self
             .native_type_data
@@ -167,26 +478,69 @@ impl<'a> FieldFFI<'a> {
     }
 
     /// Expression for assigning an argument to a field (with any required type conversion
-    /// included).
+    /// included). For enum variants, this is used in a braced initializer, which works for both
+    /// tuple and struct-like variants (as in `Type::Variant { 0: value }`). A `readonly` field
+    /// isn't an initializer argument, so it's assigned its default value instead.
     #[must_use]
     pub fn assignment_expression(&self) -> TokenStream {
+        if self.attributes.readonly {
+            return default_assignment(&self.field_name, &self.attributes);
+        }
         let ffi_ident = &self.field_name.ffi_ident();
         let conversion = self
             .native_type_data
             .argument_into_rust(&quote!(#ffi_ident), self.attributes.expose_as.is_some());
-        match &self.field_source {
-            FieldSource::Struct => {
-                let field_name = &self.field_name.rust_token();
-                quote!(#field_name: #conversion,)
-            }
-            FieldSource::Enum {
-                variant_ident: _,
-                other_variants: _,
-            } => quote!(#conversion,),
-        }
+        // Initializers return a pointer, so they return null if an argument can't be converted.
+        let conversion = self
+            .native_type_data
+            .catch_argument_errors(conversion, &quote!(return std::ptr::null()));
+        let field_name = &self.field_name.rust_token();
+        quote!(#field_name: #conversion,)
     }
 }
 
+/// Expression for filling in a field that isn't an argument of the memberwise initializer (because
+/// it's `skip` or `readonly`), as in `field: Default::default(),` or `field: path::to::default(),`.
+///
+fn default_assignment(field_name: &FieldIdent, attributes: &FieldAttributes) -> TokenStream {
+    let field_name = field_name.rust_token();
+    attributes.default.as_ref().map_or_else(
+        || quote!(#field_name: Default::default(),),
+        |path| quote!(#field_name: #path(),),
+    )
+}
+
+/// Match arms for each of `other_variants` (as pairs of their identifiers and field counts), for a
+/// function that expects an instance of `type_name::variant_ident`. Each one sets the last error
+/// message, and evaluates to `placeholder`.
+///
+pub(crate) fn wrong_variant_arms(
+    type_name: &Ident,
+    variant_ident: &Ident,
+    other_variants: &[(Ident, usize)],
+    placeholder: &TokenStream,
+) -> TokenStream {
+    other_variants
+        .iter()
+        .fold(quote!(), |mut acc, (other_variant, field_count)| {
+            let argument = if *field_count == 0 {
+                quote!()
+            } else {
+                quote!({ .. })
+            };
+            let message = format!(
+                "Expected `{type_name}::{variant_ident}`, found `{type_name}::{other_variant}`."
+            );
+            acc.extend(quote! {
+                #type_name::#other_variant#argument => {
+                    ffi_common::core::error::set_last_err_msg(#message);
+                    #placeholder
+                }
+            });
+            acc
+        })
+}
+
 /// The type of field identifier, which may be identified by the field's name, or, in the case of a
 /// tuple struct, its index.
 ///
@@ -201,6 +555,10 @@ pub enum FieldIdent {
     /// index.
     ///
     UnnamedField(usize),
+    /// A field that's exposed under a different name with `ffi(rename = "...")`. This variant
+    /// contains the field's own identifier and the name it's exposed as.
+    ///
+    Renamed(Box<Self>, Ident),
 }
 
 impl FieldIdent {
@@ -208,26 +566,40 @@ impl FieldIdent {
     /// rather than an `Ident` because `0` is not a valid `Ident`.)
     ///
     #[must_use]
-    fn rust_token(&self) -> TokenStream {
+    pub(crate) fn rust_token(&self) -> TokenStream {
         match self {
-            FieldIdent::NamedField(ident) => quote!(#ident),
-            FieldIdent::UnnamedField(index) => {
+            Self::NamedField(ident) => quote!(#ident),
+            Self::UnnamedField(index) => {
                 let index = syn::Index::from(index.to_owned());
                 quote!(#index)
             }
+            Self::Renamed(field_ident, _) => field_ident.rust_token(),
+        }
+    }
+
+    /// Whether this is a named field (as opposed to a field of a tuple struct or variant).
+    ///
+    #[must_use]
+    pub(crate) fn is_named(&self) -> bool {
+        match self {
+            Self::NamedField(_) => true,
+            Self::UnnamedField(_) => false,
+            Self::Renamed(field_ident, _) => field_ident.is_named(),
         }
     }
 
     /// Returns the FFI identifier for accessing this field. In the case of a
     /// `FieldIdent::NamedField`, this will simply be the field's name. In the case of a
     /// `FieldIdent::UnnamedField`, we can't just use an index like `0` to reference it, so we
-    /// construct an identifier like `unnamed_field_0`.
+    /// construct an identifier like `unnamed_field_0`. A `FieldIdent::Renamed` field uses its new
+    /// name.
     ///
     #[must_use]
-    fn ffi_ident(&self) -> Ident {
+    pub(crate) fn ffi_ident(&self) -> Ident {
         match self {
-            FieldIdent::NamedField(ident) => ident.clone(),
-            FieldIdent::UnnamedField(index) => quote::format_ident!("unnamed_field_{}", index),
+            Self::NamedField(ident) => ident.clone(),
+            Self::UnnamedField(index) => quote::format_ident!("unnamed_field_{}", index),
+            Self::Renamed(_, name) => name.clone(),
         }
     }
 
@@ -245,10 +617,39 @@ pub(super) struct FieldInputs<'a> {
     pub field_ident: FieldIdent,
     pub field_type: &'a syn::Type,
     pub field_source: FieldSource<'a>,
-    pub field_attrs: &'a [Attribute],
+    pub attributes: FieldAttributes,
     pub alias_modules: &'a [String],
 }
 
+impl FieldInputs<'_> {
+    /// Expression for filling in this field in the memberwise initializer if it's `skip`ped. Since
+    /// skipped fields aren't exposed at all, this is the only code generated for them.
+    ///
+    pub(super) fn skipped_assignment(&self) -> TokenStream {
+        let attributes = &self.attributes;
+        if attributes.raw
+            || attributes.duration
+            || attributes.borrow
+            || attributes.batch
+            || attributes.readonly
+            || attributes.expose_as.is_some()
+            || attributes.rename.is_some()
+        {
+            abort!(
+                self.field_type.span(),
+                "`skip` can only be combined with `default`."
+            );
+        }
+        if let FieldSource::Enum { .. } = self.field_source {
+            abort!(
+                self.field_type.span(),
+                "`skip` and `readonly` are not supported on enum variant fields."
+            );
+        }
+        default_assignment(&self.field_ident, attributes)
+    }
+}
+
 #[must_use]
 pub(super) fn field_inputs_from_unnamed_fields<'a>(
     fields: &'a syn::FieldsUnnamed,
@@ -265,7 +666,7 @@ pub(super) fn field_inputs_from_unnamed_fields<'a>(
             field_ident: FieldIdent::UnnamedField(index),
             field_type: &field.ty,
             field_source: field_source.clone(),
-            field_attrs: &*field.attrs,
+            attributes: FieldAttributes::from(&*field.attrs),
             alias_modules,
         })
         .collect()
@@ -290,7 +691,7 @@ pub(super) fn field_inputs_from_named_fields<'a>(
                 field_ident: FieldIdent::NamedField(field_ident.clone()),
                 field_type: &field.ty,
                 field_source: field_source.clone(),
-                field_attrs: &*field.attrs,
+                attributes: FieldAttributes::from(&*field.attrs),
                 alias_modules,
             }
         })
@@ -336,41 +737,160 @@ pub fn fields_for_variant<'a>(
 }
 
 impl<'a> From<FieldInputs<'a>> for FieldFFI<'a> {
+    #[allow(clippy::too_many_lines)]
     fn from(inputs: FieldInputs<'a>) -> Self {
-        let attributes = FieldAttributes::from(inputs.field_attrs);
-        let (wrapping_type, unaliased_field_type) =
-            match parsing::get_segment_for_field(inputs.field_type) {
-                Some(segment) => {
-                    let (ident, wrapping_type) =
-                        parsing::separate_wrapping_type_from_inner_type(segment);
-                    (
-                        wrapping_type,
-                        alias_resolution::resolve_type_alias(&ident, inputs.alias_modules, None)
-                            .unwrap_or_else(|err| {
-                                abort!(&inputs.field_type.span(), "Alias resolution error: {}", err)
-                            }),
-                    )
-                }
-                None => {
-                    abort!(
-                        inputs.field_type.span(),
-                        "No path segment (field without a type?"
-                    )
+        let attributes = inputs.attributes.clone();
+        let native_type_data = if parsing::is_parsed_like_parameter(inputs.field_type) {
+            // `raw` describes the elements of a fixed-size array.
+            let raw_element = match inputs.field_type {
+                syn::Type::Array(array) if attributes.raw => match &*array.elem {
+                    syn::Type::Path(path) => path.path.segments.last().map(|s| s.ident.clone()),
+                    _ => None,
+                },
+                _ => None,
+            };
+            if attributes.expose_as.is_some() {
+                abort!(
+                    inputs.field_type.span(),
+                    "`expose_as` is not supported on tuple, fixed-size array, or smart pointer fields."
+                );
+            }
+            if attributes.duration {
+                abort!(
+                    inputs.field_type.span(),
+                    "`duration` is not supported on tuple, fixed-size array, or smart pointer fields."
+                );
+            }
+            if attributes.raw && raw_element.is_none() {
+                abort!(
+                    inputs.field_type.span(),
+                    "`raw` is only supported on fixed-size array fields (like `[T; 3]`), not on tuples, smart pointers, or `Option`s or `Vec`s of them."
+                );
+            }
+            TypeFFI::from(parsing::TypeAttributes::initial(
+                inputs.field_type.clone(),
+                raw_element.into_iter().collect(),
+                vec![],
+                // Recursive types refer to themselves as `Self` (like `Option<Box<Self>>`).
+                Some(inputs.type_ident.clone()),
+            ))
+        } else {
+            let (wrapping_type, inner_segment, unaliased_field_type) =
+                match parsing::get_segment_for_field(inputs.field_type) {
+                    Some(segment) => {
+                        let (inner_segment, wrapping_type) =
+                            parsing::separate_wrapping_type_from_inner_segment(segment);
+                        let unaliased_field_type = alias_resolution::resolve_type_alias(
+                            &inner_segment.ident,
+                            inputs.alias_modules,
+                            None,
+                        )
+                        .unwrap_or_else(|err| {
+                            abort!(&inputs.field_type.span(), "Alias resolution error: {}", err)
+                        });
+                        (wrapping_type, inner_segment, unaliased_field_type)
+                    }
+                    None => {
+                        abort!(
+                            inputs.field_type.span(),
+                            "No path segment (field without a type?"
+                        )
+                    }
+                };
+
+            // If this has a raw attribute, bypass the normal `NativeType` logic and use
+            // `NativeType::raw`. Likewise, `duration` opts into exposing the type as a
+            // `TimeInterval`, since we can't tell a `Duration` from any other type with that name.
+            let field_type = if attributes.raw {
+                TypeIdentifier::Raw(unaliased_field_type.clone())
+            } else if attributes.duration {
+                TypeIdentifier::Duration(unaliased_field_type.clone())
+            } else if unaliased_field_type == inner_segment.ident {
+                // Unless this is an alias, the generic arguments of the inner type are still
+                // relevant.
+                TypeIdentifier::from(&inner_segment)
+            } else {
+                TypeIdentifier::from(unaliased_field_type.clone())
+            };
+            // Remote date and time types (like a `DateTime<Utc>` exposed as a custom wrapper) are
+            // exposed as their opaque `expose_as` type, as they were before we supported them
+            // directly.
+            let field_type = match field_type {
+                TypeIdentifier::UtcDateTime
+                | TypeIdentifier::OffsetDateTime
+                | TypeIdentifier::Date
+                | TypeIdentifier::Time
+                    if attributes.expose_as.is_some() =>
+                {
+                    TypeIdentifier::Boxed(unaliased_field_type)
                 }
+                other => other,
             };
 
-        // If this has a raw attribute, bypass the normal `NativeType` logic and use `NativeType::raw`.
-        let field_type = if attributes.raw {
-            TypeIdentifier::Raw(unaliased_field_type)
-        } else {
-            TypeIdentifier::from(unaliased_field_type)
+            TypeFFI::from((field_type, wrapping_type))
         };
 
-        let native_type_data = TypeFFI::from((field_type, wrapping_type));
+        if attributes.borrow {
+            if let FieldSource::Enum { .. } = inputs.field_source {
+                abort!(
+                    inputs.field_type.span(),
+                    "`borrow` is not supported on enum variant fields."
+                );
+            }
+            if native_type_data.borrowed_slice_type().is_none()
+                && native_type_data.borrowed_pointer_type().is_none()
+            {
+                abort!(
+                    inputs.field_type.span(),
+                    "`borrow` is only supported on `Vec` fields of primitive or `raw` types, and on fields of other FFI types."
+                );
+            }
+            if native_type_data.borrowed_pointer_type().is_some() && attributes.expose_as.is_some()
+            {
+                abort!(
+                    inputs.field_type.span(),
+                    "`borrow` can't be combined with `expose_as`, because the field isn't stored as the exposed type."
+                );
+            }
+        }
+
+        if attributes.batch {
+            if let FieldSource::Enum { .. } = inputs.field_source {
+                abort!(
+                    inputs.field_type.span(),
+                    "`batch` is not supported on enum variant fields."
+                );
+            }
+            if native_type_data.is_composite() && !native_type_data.is_tuple() {
+                abort!(
+                    inputs.field_type.span(),
+                    "`batch` is not supported on set, map, fixed-size array, smart pointer, transparent, or nested `Option` or `Vec` fields."
+                );
+            }
+        }
+
+        if attributes.is_defaulted() {
+            if let FieldSource::Enum { .. } = inputs.field_source {
+                abort!(
+                    inputs.field_type.span(),
+                    "`skip` and `readonly` are not supported on enum variant fields."
+                );
+            }
+        } else if attributes.default.is_some() {
+            abort!(
+                inputs.field_type.span(),
+                "`default` is only supported on `skip` or `readonly` fields."
+            );
+        }
+
+        let field_name = match attributes.rename.clone() {
+            Some(name) => FieldIdent::Renamed(Box::new(inputs.field_ident), name),
+            None => inputs.field_ident,
+        };
 
         Self {
             type_name: inputs.type_ident,
-            field_name: inputs.field_ident,
+            field_name,
             field_source: inputs.field_source,
             native_type_data,
             attributes,
@@ -7,7 +7,6 @@ use crate::{
     parsing::{FieldAttributes, FnAttributes, TypeAttributes},
     type_ffi::{Context, TypeFFI, TypeIdentifier},
 };
-use lazy_static::__Deref;
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
 use std::collections::HashMap;
@@ -53,6 +52,16 @@ pub struct FnFFI {
     /// Documentation comments on this fn.
     ///
     pub doc_comments: Vec<Attribute>,
+
+    /// If true, also generate a batched FFI function that calls this fn on each instance in an
+    /// array of receivers in a single call.
+    ///
+    pub batch: bool,
+
+    /// If true, also generate an FFI function that transfers ownership of the return value to an
+    /// `FFIArena`.
+    ///
+    pub arena: bool,
 }
 
 /// Representes the inputs for building a `FnFFI`.
@@ -74,6 +83,14 @@ pub struct FnFFIInputs<'a> {
     /// Documentation comments on this fn that will be added to the FFI fn.
     ///
     pub doc_comments: Vec<Attribute>,
+
+    /// If true, also generate a batched FFI function for this fn.
+    ///
+    pub batch: bool,
+
+    /// If true, also generate an `_in_arena` FFI function for this fn.
+    ///
+    pub arena: bool,
 }
 
 impl<'a> FnFFIInputs<'a> {
@@ -81,9 +98,9 @@ impl<'a> FnFFIInputs<'a> {
         if let Type::Path(type_path) = ty {
             self.local_aliases
                 .get(&type_path.path.segments.last().unwrap().ident)
-                .map_or_else(|| ty.deref().clone(), std::clone::Clone::clone)
+                .map_or_else(|| ty.clone(), Clone::clone)
         } else {
-            ty.deref().clone()
+            ty.clone()
         }
     }
 }
@@ -120,17 +137,44 @@ impl<'a> From<FnFFIInputs<'a>> for FnFFI {
                 Some(TypeFFI::from(TypeAttributes::initial(
                     dealiased,
                     inputs.fn_attributes.raw_types.clone(),
+                    inputs.fn_attributes.duration_types.clone(),
                     Some(inputs.fn_attributes.extend_type.clone()),
                 )))
             }
         };
 
+        if inputs.batch {
+            let span = inputs.method.sig.span();
+            if receiver != FnReceiver::Borrowed || !arguments.is_empty() {
+                proc_macro_error::abort!(
+                    span,
+                    "`batch` is only supported on methods that take `&self` and no other parameters."
+                );
+            }
+            match &return_type {
+                Some(r) if !r.is_composite() || r.is_tuple() => (),
+                _ => proc_macro_error::abort!(
+                    span,
+                    "`batch` is only supported on methods that return a value that isn't a set, a map, a fixed-size array, a smart pointer, a transparent type, or a nested `Option` or `Vec`."
+                ),
+            }
+        }
+
+        if inputs.arena && return_type.is_none() {
+            proc_macro_error::abort!(
+                inputs.method.sig.span(),
+                "`arena` is only supported on methods that return a value."
+            );
+        }
+
         Self {
             fn_name,
             receiver,
             parameters: arguments,
             return_type,
             doc_comments: crate::parsing::clone_doc_comments(&*inputs.method.attrs),
+            batch: inputs.batch,
+            arena: inputs.arena,
         }
     }
 }
@@ -174,6 +218,7 @@ impl From<(&ItemFn, &FnAttributes)> for FnFFI {
             syn::ReturnType::Type(_token, ty) => Some(TypeFFI::from(TypeAttributes::initial(
                 *ty.clone(),
                 fn_attributes.raw_types.clone(),
+                fn_attributes.duration_types.clone(),
                 Some(fn_attributes.extend_type.clone()),
             ))),
         };
@@ -184,6 +229,8 @@ impl From<(&ItemFn, &FnAttributes)> for FnFFI {
             parameters: arguments,
             return_type,
             doc_comments: crate::parsing::clone_doc_comments(&*method.attrs),
+            batch: false,
+            arena: false,
         }
     }
 }
@@ -237,13 +284,30 @@ impl FnFFI {
                 quote!(let data = (&*#type_as_parameter_name);),
             ),
         };
+        // If an argument can't be converted, return early with a placeholder value (the last error
+        // message describes the failure).
+        let failure = self.return_type.as_ref().map_or_else(
+            || quote!(return),
+            |r| {
+                let placeholder = r.ffi_placeholder_value();
+                quote!(return #placeholder)
+            },
+        );
         let (signature_args, calling_args, parameter_conversions) = self.parameters.iter().fold(
             (receiver_arg, quote!(), receiver_conversion),
             |mut acc, arg| {
                 let name = arg.name.clone();
                 let ty = arg.native_type_data.ffi_type(None, Context::Argument);
                 let signature_parameter = quote!(#name: #ty, );
-                let symbols = if arg.native_type_data.is_vec {
+                let symbols = if arg.native_type_data.is_composite() {
+                    // Sets, maps, and nested types are converted to owned values, so borrow them if
+                    // needed.
+                    if arg.native_type_data.is_borrow {
+                        quote!(&)
+                    } else {
+                        quote!()
+                    }
+                } else if arg.native_type_data.is_vec {
                     quote!(&*)
                 } else {
                     quote!()
@@ -256,11 +320,15 @@ impl FnFFI {
                     .argument_into_rust(&quote!(#name), false);
                 let conversion = if arg.native_type_data.is_borrow
                     && arg.native_type_data.native_type == TypeIdentifier::String
+                    && !arg.native_type_data.is_composite()
                 {
                     quote!(&*#conversion)
                 } else {
                     conversion
                 };
+                let conversion = arg
+                    .native_type_data
+                    .catch_argument_errors(conversion, &failure);
                 let assignment_and_conversion = quote!(let #name: #native_type = #conversion;);
                 acc.0.extend(signature_parameter);
                 acc.1.extend(calling_arg);
@@ -291,32 +359,41 @@ impl FnFFI {
                     TypeIdentifier::Boxed(_)
                     | TypeIdentifier::String
                     | TypeIdentifier::DateTime
-                        if !r.is_vec =>
+                    | TypeIdentifier::UtcDateTime
+                        if !r.is_vec && !r.is_composite() =>
                     {
-                        let conversion = r.rust_to_ffi_value(
-                            &quote!(r),
-                            &FieldAttributes {
-                                expose_as: None,
-                                raw: false,
-                            },
-                        );
+                        let conversion =
+                            r.rust_to_ffi_value(&quote!(r), &FieldAttributes::default());
                         quote!(
                             ffi_common::core::try_or_set_error!(return_value.map(|r| #conversion))
                         )
                     }
                     _ => {
                         let native_type = r.native_type();
-                        let conversion = r.rust_to_ffi_value(
-                            &quote!(r),
-                            &FieldAttributes {
-                                expose_as: None,
-                                raw: false,
-                            },
-                        );
+                        let conversion =
+                            r.rust_to_ffi_value(&quote!(r), &FieldAttributes::default());
+                        // Raw values may be converted to a different FFI type (like `u128`), so
+                        // the default needs the same conversion.
+                        let default = match &r.native_type {
+                            _ if r.is_composite() => r.ffi_placeholder_value(),
+                            TypeIdentifier::Raw(_) if !r.is_vec && !r.is_option => {
+                                quote!(<#native_type>::default().into())
+                            }
+                            TypeIdentifier::Uuid
+                            | TypeIdentifier::OffsetDateTime
+                            | TypeIdentifier::Date
+                            | TypeIdentifier::Time
+                            | TypeIdentifier::Duration(_)
+                                if !r.is_vec =>
+                            {
+                                r.ffi_placeholder_value()
+                            }
+                            _ => quote!(<#native_type>::default()),
+                        };
                         let map = quote!(
-                            ffi_common::core::try_or_set_error!(return_value.map(|r| #conversion), <#native_type>::default())
+                            ffi_common::core::try_or_set_error!(return_value.map(|r| #conversion), #default)
                         );
-                        if r.is_vec {
+                        if r.is_vec && !r.is_composite() {
                             quote! {
                                 use std::ops::Deref;
                                 #map.deref().into()
@@ -328,13 +405,7 @@ impl FnFFI {
                 }
             } else {
                 let accessor = quote!(return_value);
-                r.rust_to_ffi_value(
-                    &accessor,
-                    &FieldAttributes {
-                        expose_as: None,
-                        raw: false,
-                    },
-                )
+                r.rust_to_ffi_value(&accessor, &FieldAttributes::default())
             };
             quote! {
                 #assignment
@@ -354,9 +425,181 @@ impl FnFFI {
         }
     }
 
+    /// If this fn is batched, generates a function for calling the native fn on each instance in
+    /// an array of borrowed receivers in a single call.
+    ///
+    /// For example, a function with a signature like
+    /// ```ignore
+    /// fn do_something(&self) -> Result<ReturnType, Error> { ... }
+    /// ```
+    /// will produce an FFI function like
+    /// ```ignore
+    /// pub unsafe extern "C" fn do_something_batch(
+    ///     receivers: *const *const SelfType,
+    ///     len: usize,
+    ///     errors: *mut FFIArrayString,
+    /// ) -> FFIArrayReturnType { ... }
+    /// ```
+    /// If the native fn returns a `Result`, each element's error message (or a null pointer, if it
+    /// succeeded) is written to `errors` at the same index as the element's result (which is null
+    /// or the default value for failures). If `errors` is null, the messages are discarded.
+    ///
+    #[must_use]
+    pub fn generate_batch_ffi(&self, module_name: &Ident, type_name: &Ident) -> TokenStream {
+        let r = match &self.return_type {
+            Some(r) if self.batch => r,
+            _ => return quote!(),
+        };
+        let batch_fn_name = self.batch_ffi_fn_name(module_name);
+        let native_fn_name = &self.fn_name;
+        let element_type = r.ffi_type(None, Context::Return);
+        let array_type = r.batch_results().ffi_type(None, Context::Return);
+        let fn_doc = format!(
+            "Call `{native_fn_name}` on each `{type_name}` in `receivers` in a single call."
+        );
+        let receivers = quote!(ffi_common::core::batch::receivers(receivers, len));
+        if r.is_result {
+            let conversion = r.rust_to_ffi_value(&quote!(r), &FieldAttributes::default());
+            let failure_value = match &r.native_type {
+                TypeIdentifier::Raw(inner) if !r.is_option && !r.is_vec => {
+                    quote!(<#inner>::default().into())
+                }
+                _ => r.ffi_placeholder_value(),
+            };
+            quote! {
+                #[doc = #fn_doc]
+                #[no_mangle]
+                pub unsafe extern "C" fn #batch_fn_name(
+                    receivers: *const *const #type_name,
+                    len: usize,
+                    errors: *mut ffi_common::core::string::FFIArrayString,
+                ) -> #array_type {
+                    let mut error_messages: Vec<*const std::os::raw::c_char> = Vec::with_capacity(len);
+                    let values: Vec<#element_type> = #receivers
+                        .map(|data| -> #element_type {
+                            match data.#native_fn_name() {
+                                Ok(r) => {
+                                    error_messages.push(std::ptr::null());
+                                    #conversion
+                                }
+                                Err(error) => {
+                                    error_messages.push(ffi_common::core::ffi_string!(error.to_string()));
+                                    #failure_value
+                                }
+                            }
+                        })
+                        .collect();
+                    if errors.is_null() {
+                        ffi_common::core::string::ffi_array_string_free(error_messages.into());
+                    } else {
+                        *errors = error_messages.into();
+                    }
+                    values.into()
+                }
+            }
+        } else {
+            let conversion =
+                r.rust_to_ffi_value(&quote!(return_value), &FieldAttributes::default());
+            quote! {
+                #[doc = #fn_doc]
+                #[no_mangle]
+                pub unsafe extern "C" fn #batch_fn_name(
+                    receivers: *const *const #type_name,
+                    len: usize,
+                ) -> #array_type {
+                    let values: Vec<#element_type> = #receivers
+                        .map(|data| -> #element_type {
+                            let return_value = data.#native_fn_name();
+                            #conversion
+                        })
+                        .collect();
+                    values.into()
+                }
+            }
+        }
+    }
+
+    /// If this fn has the `arena` attribute, generates a function that calls the FFI function for
+    /// this fn, and transfers ownership of its return value to an `FFIArena`.
+    ///
+    /// For example, a function with a signature like
+    /// ```ignore
+    /// fn do_something(&self, another_param: u8) -> Vec<ReturnType> { ... }
+    /// ```
+    /// will produce an FFI function like
+    /// ```ignore
+    /// pub unsafe extern "C" fn do_something_in_arena(
+    ///     a_receiver: *const SelfType,
+    ///     another_param: u8,
+    ///     arena: *mut FFIArena,
+    /// ) -> FFIArrayReturnType {
+    ///     ffi_common::core::arena::adopt(arena, do_something(a_receiver, another_param), |value| {
+    ///         drop(Option::<Vec<ReturnType>>::from(value));
+    ///     })
+    /// }
+    /// ```
+    ///
+    #[must_use]
+    pub fn generate_arena_ffi(
+        &self,
+        module_name: &Ident,
+        type_name: Option<&Ident>,
+        type_as_parameter_name: Option<&Ident>,
+    ) -> TokenStream {
+        let r = match &self.return_type {
+            Some(r) if self.arena => r,
+            _ => return quote!(),
+        };
+        let (receiver_arg, receiver_name) = match self.receiver {
+            FnReceiver::None => (quote!(), quote!()),
+            FnReceiver::Owned | FnReceiver::Borrowed => (
+                quote!(#type_as_parameter_name: *const #type_name, ),
+                quote!(#type_as_parameter_name, ),
+            ),
+        };
+        let (signature_args, calling_args) =
+            self.parameters
+                .iter()
+                .fold((receiver_arg, receiver_name), |mut acc, arg| {
+                    let name = &arg.name;
+                    let ty = arg.native_type_data.ffi_type(None, Context::Argument);
+                    acc.0.extend(quote!(#name: #ty, ));
+                    acc.1.extend(quote!(#name, ));
+                    acc
+                });
+        let ffi_fn_name = self.ffi_fn_name(module_name);
+        let arena_fn_name = self.arena_ffi_fn_name(module_name);
+        let return_type = r.ffi_type(None, Context::Return);
+        let free = r.free_ffi_value(&quote!(value), None);
+        let fn_doc = format!(
+            "Call `{}`, transferring ownership of the result to `arena`. The result is released when \
+`arena` is freed with `ffi_arena_free`, and must not be freed individually. If `arena` is null, the \
+caller owns the result, as with `{}`.",
+            self.fn_name, ffi_fn_name
+        );
+        quote! {
+            #[doc = #fn_doc]
+            #[no_mangle]
+            pub unsafe extern "C" fn #arena_fn_name(
+                #signature_args
+                arena: *mut ffi_common::core::arena::FFIArena,
+            ) -> #return_type {
+                ffi_common::core::arena::adopt(arena, #ffi_fn_name(#calling_args), |value| { #free })
+            }
+        }
+    }
+
     pub(crate) fn ffi_fn_name(&self, module_name: &Ident) -> Ident {
         format_ident!("{}_{}", module_name, self.fn_name)
     }
+
+    pub(crate) fn batch_ffi_fn_name(&self, module_name: &Ident) -> Ident {
+        format_ident!("{}_{}_batch", module_name, self.fn_name)
+    }
+
+    pub(crate) fn arena_ffi_fn_name(&self, module_name: &Ident) -> Ident {
+        format_ident!("{}_{}_in_arena", module_name, self.fn_name)
+    }
 }
 
 /// Represents a parameter for to a Rust function.
@@ -403,6 +646,7 @@ impl<'a> From<FnParameterFFIInputs<'a>> for FnParameterFFI {
         let native_type_data = TypeFFI::from(TypeAttributes::initial(
             concrete_type,
             inputs.fn_attributes.raw_types.clone(),
+            inputs.fn_attributes.duration_types.clone(),
             Some(inputs.fn_attributes.extend_type.clone()),
         ));
         Self {
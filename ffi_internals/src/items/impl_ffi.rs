@@ -7,6 +7,7 @@ use super::fn_ffi::{FnFFI, FnFFIInputs};
 use crate::parsing::FnAttributes;
 use heck::SnakeCase;
 use proc_macro2::TokenStream;
+use proc_macro_error::abort;
 use quote::{format_ident, quote};
 use std::collections::HashMap;
 use syn::{Attribute, Ident, ImplItem, Path, Type};
@@ -34,6 +35,11 @@ pub struct ImplInputs {
     ///
     pub raw_types: Vec<Ident>,
 
+    /// Any types referenced in the impl that should be exposed as a `TimeInterval`, like
+    /// `chrono::Duration` or `std::time::Duration`.
+    ///
+    pub duration_types: Vec<Ident>,
+
     /// A hashmap whose keys are `Type`s for the generics used throughout this impl and whose
     /// values are `Type`s for the concrete types to use in place of the generic for FFI.
     ///
@@ -56,6 +62,14 @@ pub struct ImplInputs {
     /// Documentation comments on this impl that will be added to the FFI module.
     ///
     pub doc_comments: Vec<Attribute>,
+
+    /// The names of methods in this impl that should also get a batched FFI function.
+    ///
+    pub batch_fns: Vec<Ident>,
+
+    /// The names of methods in this impl that should also get an `_in_arena` FFI function.
+    ///
+    pub arena_fns: Vec<Ident>,
 }
 
 impl From<ImplInputs> for ImplFFI {
@@ -81,6 +95,20 @@ impl From<ImplInputs> for ImplFFI {
                 _ => acc,
             });
 
+        for (attribute, names) in [("batch", &inputs.batch_fns), ("arena", &inputs.arena_fns)] {
+            if let Some(missing) = names
+                .iter()
+                .find(|name| !methods.iter().any(|method| &method.sig.ident == *name))
+            {
+                abort!(
+                    missing.span(),
+                    "`{}` refers to `{}`, which isn't a method in this impl.",
+                    attribute,
+                    missing
+                );
+            }
+        }
+
         let fns = methods
             .iter()
             .map(|item| {
@@ -89,10 +117,13 @@ impl From<ImplInputs> for ImplFFI {
                     fn_attributes: &FnAttributes {
                         extend_type: inputs.type_name.clone(),
                         raw_types: inputs.raw_types.clone(),
+                        duration_types: inputs.duration_types.clone(),
                         generics: inputs.generics.clone(),
                     },
                     local_aliases: aliases.clone(),
                     doc_comments: crate::parsing::clone_doc_comments(&*item.attrs),
+                    batch: inputs.batch_fns.contains(&item.sig.ident),
+                    arena: inputs.arena_fns.contains(&item.sig.ident),
                 })
             })
             .collect();
@@ -176,6 +207,12 @@ impl ImplFFI {
                 Some(&self.type_name),
                 Some(&self.type_name_as_parameter_name()),
             ));
+            stream.extend(f.generate_batch_ffi(&self.module_name(), &self.type_name));
+            stream.extend(f.generate_arena_ffi(
+                &self.module_name(),
+                Some(&self.type_name),
+                Some(&self.type_name_as_parameter_name()),
+            ));
             stream
         });
         quote! {
@@ -4,8 +4,13 @@
 //! `ffi(custom = "crate/relative/path")` attribute to reference a file with a handwritten FFI for
 //! the type. We still generate some supporting wrappers and conveniences, as well as the wrapping
 //! consumer, but we expect the file specified in the `custom` attributes to do the heavy lifting
-//! (initializer and getter fns).
+//! (initializer and getter fns). `transparent` refers to a newtype with the `ffi(transparent)`
+//! attribute, which is exposed just like the type it wraps. `flags` refers to a bit-flag newtype
+//! with the `ffi(flags(...))` attribute, which is exposed like a transparent type, along with its
+//! named constants.
 //!
 
 pub mod custom;
+pub mod flags;
 pub mod standard;
+pub mod transparent;
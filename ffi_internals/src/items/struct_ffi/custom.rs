@@ -119,7 +119,7 @@ impl From<StructFFI<'_>> for TokenStream {
 
                 #[no_mangle]
                 pub unsafe extern "C" fn #free_fn_name(data: *const #type_name) {
-                    drop(Box::from_raw(data as *mut #type_name));
+                    <#type_name as ffi_common::core::handle::OpaqueHandle>::release_handle(data);
                 }
 
                 declare_opaque_type_ffi! { #type_name }
@@ -0,0 +1,139 @@
+//!
+//! Contains structures describing a bit-flag newtype with the `ffi(flags(...))` attribute, and
+//! implementations for building its FFI support.
+//!
+//! A bit-flag type is a newtype over an unsigned integer (like `pub struct Permissions(u32)`) with
+//! an associated constant for each flag (like `Permissions::READ`). Bit-flag types are exposed like
+//! transparent types (see `transparent`), so they cross the FFI as the integer they wrap. On top of
+//! that, there's a getter for each named constant, and helpers for combining and testing sets of
+//! flags.
+//!
+
+use super::transparent;
+use heck::SnakeCase;
+use proc_macro2::TokenStream;
+use proc_macro_error::abort;
+use quote::{format_ident, quote};
+use syn::{spanned::Spanned, Ident, Type};
+
+/// Represents the components of a bit-flag newtype.
+///
+pub struct StructFFI<'a> {
+    /// The transparent newtype that holds the flags.
+    ///
+    pub transparent: transparent::StructFFI<'a>,
+    /// The names of the associated constants for each flag.
+    ///
+    pub flags: &'a [Ident],
+    /// The unsigned integer type that's wrapped (like `u32`).
+    ///
+    pub native_type: String,
+}
+
+impl<'a> StructFFI<'a> {
+    /// Returns a new `StructFFI` for the bit-flag newtype described by `transparent`, with the
+    /// associated constants named in `flags`.
+    ///
+    /// # Proc Macro Errors
+    ///
+    /// Fails if the newtype doesn't wrap an unsigned integer.
+    ///
+    #[must_use]
+    pub fn new(transparent: transparent::StructFFI<'a>, flags: &'a [Ident]) -> Self {
+        let native_type = match transparent.inner {
+            Type::Path(path) => path.path.get_ident().map(ToString::to_string),
+            _ => None,
+        };
+        match native_type {
+            Some(native_type)
+                if ["u8", "u16", "u32", "u64", "usize"].contains(&native_type.as_str()) =>
+            {
+                Self {
+                    transparent,
+                    flags,
+                    native_type,
+                }
+            }
+            _ => abort!(
+                transparent.inner.span(),
+                "`ffi(flags)` is only supported for newtypes over an unsigned integer (like `u32`)."
+            ),
+        }
+    }
+
+    /// The name of the getter for `flag`'s value.
+    ///
+    #[must_use]
+    pub fn flag_getter_name(&self, flag: &Ident) -> Ident {
+        format_ident!(
+            "get_{}_{}",
+            self.transparent.type_name.to_string().to_snake_case(),
+            flag.to_string().to_snake_case()
+        )
+    }
+
+    /// The name of the helper for the set operation `operation` (`union`, `intersection`, or
+    /// `contains`).
+    ///
+    #[must_use]
+    pub fn operation_fn_name(&self, operation: &str) -> Ident {
+        format_ident!(
+            "{}_{}",
+            self.transparent.type_name.to_string().to_snake_case(),
+            operation
+        )
+    }
+}
+
+impl From<StructFFI<'_>> for TokenStream {
+    fn from(ffi: StructFFI<'_>) -> Self {
+        let module_name = ffi.transparent.module_name;
+        let type_name = ffi.transparent.type_name;
+        let inner = ffi.transparent.inner;
+        let transparent_impl = ffi.transparent.transparent_impl();
+        let flag_getters = ffi.flags.iter().map(|flag| {
+            let getter_name = ffi.flag_getter_name(flag);
+            quote! {
+                #[no_mangle]
+                pub extern "C" fn #getter_name() -> #inner {
+                    *ffi_common::core::transparent::Transparent::inner(&#type_name::#flag)
+                }
+            }
+        });
+        let union_fn_name = ffi.operation_fn_name("union");
+        let intersection_fn_name = ffi.operation_fn_name("intersection");
+        let contains_fn_name = ffi.operation_fn_name("contains");
+
+        quote!(
+            #[allow(missing_docs)]
+            pub mod #module_name {
+                use super::*;
+
+                #transparent_impl
+
+                #(#flag_getters)*
+
+                /// Returns the flags that are set in either `lhs` or `rhs`.
+                ///
+                #[no_mangle]
+                pub extern "C" fn #union_fn_name(lhs: #inner, rhs: #inner) -> #inner {
+                    lhs | rhs
+                }
+
+                /// Returns the flags that are set in both `lhs` and `rhs`.
+                ///
+                #[no_mangle]
+                pub extern "C" fn #intersection_fn_name(lhs: #inner, rhs: #inner) -> #inner {
+                    lhs & rhs
+                }
+
+                /// Returns true if every flag that's set in `other` is also set in `flags`.
+                ///
+                #[no_mangle]
+                pub extern "C" fn #contains_fn_name(flags: #inner, other: #inner) -> bool {
+                    flags & other == other
+                }
+            }
+        )
+    }
+}
@@ -3,7 +3,7 @@
 //! consumer implementations.
 //!
 
-use crate::items::field_ffi::{FieldFFI, FieldSource};
+use crate::items::field_ffi::{FieldFFI, FieldInputs, FieldSource};
 use heck::SnakeCase;
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
@@ -12,6 +12,7 @@ use syn::{spanned::Spanned, Attribute, Fields, Ident, Path};
 
 /// Represents the components of a struct for generating a standard derived FFI.
 ///
+#[allow(clippy::struct_excessive_bools)]
 pub struct StructFFI<'a> {
     /// The identifier for the FFI module to be generated.
     ///
@@ -33,6 +34,17 @@ pub struct StructFFI<'a> {
     /// generated memberwise init bypasses those restrictions.
     ///
     pub forbid_memberwise_init: bool,
+    /// If true, generate a `repr(C)` snapshot type with the FFI representation of every field, and
+    /// a getter for reading all of them in a single call.
+    ///
+    pub snapshot: bool,
+    /// If true, generate an `_in_arena` variant of each getter.
+    ///
+    pub arena: bool,
+    /// If true, instances of this struct are allocated in an `Arc`, and retained and released
+    /// instead of being cloned and freed.
+    ///
+    pub shared: bool,
     /// The initializer arguments, as a `TokenStream` that we can just inject into the right place
     /// in the generated module's initializer.
     ///
@@ -73,6 +85,149 @@ impl StructFFI<'_> {
         format_ident!("rust_ffi_clone_{}", self.name.to_string().to_snake_case())
     }
 
+    /// The name of the function that retains a shared instance of this struct.
+    ///
+    #[must_use]
+    pub fn retain_fn_name(&self) -> Ident {
+        format_ident!("{}_rust_ffi_retain", self.name.to_string().to_snake_case())
+    }
+
+    /// The name of the function that releases a shared instance of this struct.
+    ///
+    #[must_use]
+    pub fn release_fn_name(&self) -> Ident {
+        format_ident!("{}_rust_ffi_release", self.name.to_string().to_snake_case())
+    }
+
+    /// If this struct has the `shared` attribute, returns its retain and release functions.
+    /// Otherwise, returns an empty `TokenStream`.
+    ///
+    fn shared_items(&self) -> TokenStream {
+        if !self.shared {
+            return quote!();
+        }
+        let type_name = self.name;
+        let retain_fn_name = self.retain_fn_name();
+        let release_fn_name = self.release_fn_name();
+        quote! {
+            ffi_common::core::paste! {
+                #[no_mangle]
+                #[doc = "Add a reference to a shared `" #type_name "`, and return it."]
+                #[doc = ""]
+                #[doc = "Every reference must be released with `" #release_fn_name "`."]
+                pub unsafe extern "C" fn #retain_fn_name(ptr: *const #type_name) -> *const #type_name {
+                    <#type_name as ffi_common::core::handle::OpaqueHandle>::retain_handle(ptr)
+                }
+
+                #[no_mangle]
+                #[doc = "Release a reference to a shared `" #type_name "`, which is freed once there are no references left."]
+                pub unsafe extern "C" fn #release_fn_name(ptr: *const #type_name) {
+                    <#type_name as ffi_common::core::handle::OpaqueHandle>::release_handle(ptr);
+                }
+            }
+        }
+    }
+
+    /// The name of the `repr(C)` snapshot type for this struct.
+    ///
+    #[must_use]
+    pub fn snapshot_type_name(&self) -> Ident {
+        format_ident!("{}Snapshot", self.name)
+    }
+
+    /// The name of the function that returns a snapshot of this struct.
+    ///
+    #[must_use]
+    pub fn snapshot_getter_name(&self) -> Ident {
+        format_ident!("get_{}_snapshot", self.name.to_string().to_snake_case())
+    }
+
+    /// The name of the function that frees a snapshot of this struct.
+    ///
+    #[must_use]
+    pub fn snapshot_free_fn_name(&self) -> Ident {
+        format_ident!("free_{}_snapshot", self.name.to_string().to_snake_case())
+    }
+
+    /// The name of the function that returns a snapshot of this struct into an arena.
+    ///
+    #[must_use]
+    pub fn snapshot_arena_getter_name(&self) -> Ident {
+        format_ident!("{}_in_arena", self.snapshot_getter_name())
+    }
+
+    /// If this struct has the `snapshot` attribute, returns the snapshot type, its getter, and its
+    /// free function. Otherwise, returns an empty `TokenStream`.
+    ///
+    fn snapshot_items(&self) -> TokenStream {
+        if !self.snapshot {
+            return quote!();
+        }
+        let type_name = self.name;
+        let snapshot_type_name = self.snapshot_type_name();
+        let snapshot_getter_name = self.snapshot_getter_name();
+        let snapshot_free_fn_name = self.snapshot_free_fn_name();
+        let arena_getter = if self.arena {
+            let snapshot_arena_getter_name = self.snapshot_arena_getter_name();
+            quote! {
+                #[no_mangle]
+                #[doc = "Get a snapshot of every field of this `" #type_name "`, owned by `arena`."]
+                #[doc = ""]
+                #[doc = "The snapshot's fields are released when `arena` is freed with `ffi_arena_free`, and must not be freed individually."]
+                pub unsafe extern "C" fn #snapshot_arena_getter_name(
+                    ptr: *const #type_name,
+                    arena: *mut ffi_common::core::arena::FFIArena
+                ) -> #snapshot_type_name {
+                    ffi_common::core::arena::adopt(arena, #snapshot_getter_name(ptr), |snapshot| {
+                        #snapshot_free_fn_name(snapshot);
+                    })
+                }
+            }
+        } else {
+            quote!()
+        };
+        let (fields, assignments, frees) =
+            self.fields
+                .iter()
+                .fold((quote!(), quote!(), quote!()), |mut acc, field_ffi| {
+                    acc.0.extend(field_ffi.snapshot_field());
+                    acc.1.extend(field_ffi.snapshot_assignment());
+                    acc.2.extend(field_ffi.snapshot_free());
+                    acc
+                });
+        quote! {
+            ffi_common::core::paste! {
+                #[repr(C)]
+                #[derive(Debug)]
+                #[doc = "The FFI representation of every field of `" #type_name "`, for reading them all in a single call."]
+                pub struct #snapshot_type_name {
+                    #fields
+                }
+
+                #[no_mangle]
+                #[doc = "Get a snapshot of every field of this `" #type_name "`."]
+                #[doc = ""]
+                #[doc = "Each field of the snapshot is owned by the caller, exactly as if it had been returned by its own getter."]
+                pub unsafe extern "C" fn #snapshot_getter_name(ptr: *const #type_name) -> #snapshot_type_name {
+                    let data = &*ptr;
+                    #snapshot_type_name {
+                        #assignments
+                    }
+                }
+
+                #[no_mangle]
+                #[doc = "Free every field of a `" #snapshot_type_name "`."]
+                #[doc = ""]
+                #[doc = "Only call this if you haven't taken ownership of the snapshot's fields individually."]
+                pub unsafe extern "C" fn #snapshot_free_fn_name(snapshot: #snapshot_type_name) {
+                    #frees
+                }
+
+                #arena_getter
+            }
+        }
+    }
+
     /// Find any extra imports from `expose_as` attributes on this struct's fields, and return them
     /// as a `TokenStream`.
     ///
@@ -92,6 +247,7 @@ impl StructFFI<'_> {
 
 /// Representes the inputs for building a `StructFFI`.
 ///
+#[allow(clippy::struct_excessive_bools)]
 pub struct StructInputs<'a> {
     /// The identifier for the FFI module to be generated.
     ///
@@ -116,13 +272,22 @@ pub struct StructInputs<'a> {
     /// generated memberwise init bypasses those restrictions.
     ///
     pub forbid_memberwise_init: bool,
+    /// If true, generate a `repr(C)` snapshot type and getter for this struct.
+    ///
+    pub snapshot: bool,
+    /// If true, generate an `_in_arena` variant of each getter for this struct.
+    ///
+    pub arena: bool,
+    /// If true, allocate instances of this struct in an `Arc`.
+    ///
+    pub shared: bool,
     /// Documentation comments on this struct.
     pub doc_comments: &'a [Attribute],
 }
 
 impl<'a> From<&StructInputs<'a>> for StructFFI<'a> {
     fn from(derive: &StructInputs<'a>) -> Self {
-        let fields: Vec<FieldFFI<'_>> = match &derive.data.fields {
+        let (skipped_fields, fields): (Vec<_>, Vec<_>) = match &derive.data.fields {
             Fields::Named(fields) => crate::items::field_ffi::field_inputs_from_named_fields(
                 fields,
                 &FieldSource::Struct,
@@ -141,18 +306,32 @@ impl<'a> From<&StructInputs<'a>> for StructFFI<'a> {
             ),
         }
         .into_iter()
-        .map(FieldFFI::from)
-        .collect();
+        .partition(|inputs| inputs.attributes.skip);
+        let fields: Vec<FieldFFI<'_>> = fields.into_iter().map(FieldFFI::from).collect();
 
-        let (init_arguments, assignment_expressions, getter_fns) =
+        let (init_arguments, mut assignment_expressions, getter_fns) =
             fields
                 .iter()
                 .fold((quote!(), quote!(), quote!()), |mut acc, field_ffi| {
                     acc.0.extend(field_ffi.ffi_initializer_argument());
                     acc.1.extend(field_ffi.assignment_expression());
                     acc.2.extend(field_ffi.getter_fn());
+                    if let Some(borrowed_getter) = field_ffi.borrowed_getter_fn() {
+                        acc.2.extend(borrowed_getter);
+                    }
+                    if let Some(lazy_access_fns) = field_ffi.lazy_access_fns() {
+                        acc.2.extend(lazy_access_fns);
+                    }
+                    if let Some(batch_getter) = field_ffi.batch_getter_fn() {
+                        acc.2.extend(batch_getter);
+                    }
+                    if derive.arena {
+                        acc.2.extend(field_ffi.arena_getter_fn());
+                    }
                     acc
                 });
+        // Skipped fields aren't exposed, so they're only filled in by the initializer.
+        assignment_expressions.extend(skipped_fields.iter().map(FieldInputs::skipped_assignment));
 
         Self {
             module: derive.module_name,
@@ -164,6 +343,9 @@ impl<'a> From<&StructInputs<'a>> for StructFFI<'a> {
             assignment_expressions,
             getter_fns,
             forbid_memberwise_init: derive.forbid_memberwise_init,
+            snapshot: derive.snapshot,
+            arena: derive.arena,
+            shared: derive.shared,
             doc_comments: derive.doc_comments,
         }
     }
@@ -180,6 +362,13 @@ impl<'a> From<StructFFI<'_>> for TokenStream {
         let free_fn_name = struct_ffi.free_fn_name();
         let init_fn_name = struct_ffi.init_fn_name();
         let clone_fn_name = struct_ffi.clone_fn_name();
+        let snapshot_items = struct_ffi.snapshot_items();
+        let shared_items = struct_ffi.shared_items();
+        let declare_opaque_type = if struct_ffi.shared {
+            quote!(declare_opaque_type_ffi! { shared #type_name })
+        } else {
+            quote!(declare_opaque_type_ffi! { #type_name })
+        };
         let ffi_mod_imports: Vec<Self> = struct_ffi
             .ffi_mod_imports
             .iter()
@@ -197,7 +386,7 @@ impl<'a> From<StructFFI<'_>> for TokenStream {
                     let data = #type_name {
                         #assignment_expressions
                     };
-                    Box::into_raw(Box::new(data))
+                    <#type_name as ffi_common::core::handle::OpaqueHandle>::into_handle(data)
                 }
             }
         };
@@ -215,19 +404,23 @@ impl<'a> From<StructFFI<'_>> for TokenStream {
 
                 #[no_mangle]
                 pub unsafe extern "C" fn #free_fn_name(data: *const #type_name) {
-                    drop(Box::from_raw(data as *mut #type_name));
+                    <#type_name as ffi_common::core::handle::OpaqueHandle>::release_handle(data);
                 }
 
-                declare_opaque_type_ffi! { #type_name }
+                #declare_opaque_type
 
                 #initializer
 
                 #[no_mangle]
                 pub unsafe extern "C" fn #clone_fn_name(ptr: *const #type_name) -> *const #type_name {
-                    Box::into_raw(Box::new((&*ptr).clone()))
+                    <#type_name as ffi_common::core::handle::OpaqueHandle>::retain_handle(ptr)
                 }
 
+                #shared_items
+
                 #getter_fns
+
+                #snapshot_items
             }
         )
     }
@@ -0,0 +1,135 @@
+//!
+//! Contains structures describing a newtype with the `ffi(transparent)` attribute, and
+//! implementations for building its FFI support.
+//!
+//! A transparent newtype (like `pub struct Acres(f64)`) is exposed just like the type it wraps.
+//! There's no opaque type or getters for a transparent type. Instead, we record the type it wraps
+//! (see `transparent_resolution`), so that other types that use it can expose the wrapped value, and
+//! implement `ffi_core::transparent::Transparent` for converting to and from it.
+//!
+
+use crate::{parsing::TypeAttributes, type_ffi::TypeFFI};
+use proc_macro2::TokenStream;
+use proc_macro_error::abort;
+use quote::quote;
+use syn::{spanned::Spanned, Attribute, DataStruct, Ident, Member, Path, Type};
+
+/// Represents the components of a transparent newtype.
+///
+pub struct StructFFI<'a> {
+    /// The name of the struct we're working with.
+    ///
+    pub type_name: &'a Ident,
+    /// The identifier for the FFI module to be generated.
+    ///
+    pub module_name: &'a Ident,
+    /// Paths that need to be imported into the consumer module.
+    ///
+    pub consumer_imports: &'a [Path],
+    /// The type that's wrapped by this struct.
+    ///
+    pub inner: &'a Type,
+    /// The wrapped field (`0` for a tuple struct).
+    ///
+    member: Member,
+    /// Documentation comments on this struct.
+    ///
+    pub doc_comments: &'a [Attribute],
+}
+
+impl<'a> StructFFI<'a> {
+    /// Returns a new `StructFFI` for the newtype described by `data`, and records the type it wraps
+    /// for any types that use it.
+    ///
+    /// # Proc Macro Errors
+    ///
+    /// Fails if `data` doesn't have exactly one field, if that field's type isn't a type that we
+    /// can expose on its own (like a primitive, a `String`, or another FFI type), or if it can't be
+    /// recorded.
+    ///
+    #[must_use]
+    pub fn new(
+        type_name: &'a Ident,
+        module_name: &'a Ident,
+        data: &'a DataStruct,
+        consumer_imports: &'a [Path],
+        doc_comments: &'a [Attribute],
+    ) -> Self {
+        let [field] = data.fields.iter().collect::<Vec<_>>()[..] else {
+            abort!(
+                type_name.span(),
+                "`ffi(transparent)` is only supported for structs with exactly one field."
+            )
+        };
+        let inner = TypeFFI::from(TypeAttributes::initial(
+            field.ty.clone(),
+            vec![],
+            vec![],
+            None,
+        ));
+        if inner.is_composite() || inner.is_result || inner.is_borrow {
+            abort!(
+                field.ty.span(),
+                "Transparent types must wrap a single type that isn't an `Option`, a `Vec`, or \
+another transparent type."
+            );
+        }
+        crate::transparent_resolution::register_transparent_type(type_name, &field.ty)
+            .unwrap_or_else(|err| {
+                abort!(
+                    type_name.span(),
+                    "Error recording transparent type: {}",
+                    err
+                )
+            });
+        let member = field
+            .ident
+            .clone()
+            .map_or_else(|| Member::from(0), Member::Named);
+        Self {
+            type_name,
+            module_name,
+            consumer_imports,
+            inner: &field.ty,
+            member,
+            doc_comments,
+        }
+    }
+
+    /// The implementation of `ffi_core::transparent::Transparent` for this type.
+    ///
+    pub(crate) fn transparent_impl(&self) -> TokenStream {
+        let type_name = self.type_name;
+        let inner = self.inner;
+        let member = &self.member;
+        quote! {
+            impl ffi_common::core::transparent::Transparent for #type_name {
+                type Inner = #inner;
+
+                fn from_inner(inner: Self::Inner) -> Self {
+                    Self { #member: inner }
+                }
+
+                fn inner(&self) -> &Self::Inner {
+                    &self.#member
+                }
+            }
+        }
+    }
+}
+
+impl From<StructFFI<'_>> for TokenStream {
+    fn from(ffi: StructFFI<'_>) -> Self {
+        let module_name = ffi.module_name;
+        let transparent_impl = ffi.transparent_impl();
+
+        quote!(
+            #[allow(missing_docs)]
+            pub mod #module_name {
+                use super::*;
+
+                #transparent_impl
+            }
+        )
+    }
+}
@@ -27,6 +27,7 @@ pub mod alias_resolution;
 pub mod consumer;
 pub mod items;
 pub mod parsing;
+pub mod transparent_resolution;
 pub mod type_ffi;
 
 // Reexports
@@ -56,13 +57,18 @@ pub fn consumer_type_for(native_type: &str, option: bool) -> String {
         "u16" => "UInt16".to_string(),
         "u32" => "UInt32".to_string(),
         "u64" => "UInt64".to_string(),
+        "usize" => "UInt".to_string(),
+        "u128" => "FFIu128".to_string(),
         "i8" => "Int8".to_string(),
         "i16" => "Int16".to_string(),
         "i32" => "Int32".to_string(),
         "i64" => "Int64".to_string(),
+        "isize" => "Int".to_string(),
+        "i128" => "FFIi128".to_string(),
         "f32" => "Float32".to_string(),
         "f64" => "Double".to_string(),
         "bool" => "Bool".to_string(),
+        "char" => "Character".to_string(),
         _ => native_type.to_string(),
     };
     if option {
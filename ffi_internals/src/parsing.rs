@@ -3,7 +3,9 @@
 //!
 
 use proc_macro_error::{abort, OptionExt, ResultExt};
+use std::collections::HashMap;
 use std::fs::File;
+use std::hash::BuildHasher;
 use std::io::Read;
 use syn::{
     spanned::Spanned, Attribute, GenericArgument, Ident, Item, Meta, NestedMeta, Path,
@@ -19,7 +21,7 @@ mod type_attributes;
 pub use field_attributes::FieldAttributes;
 pub use fn_attributes::FnAttributes;
 pub use impl_attributes::ImplAttributes;
-pub use struct_attributes::{CustomAttributes, StructAttributes};
+pub use struct_attributes::{CustomAttributes, Instantiation, StructAttributes};
 pub use type_attributes::TypeAttributes;
 
 /// If the path of the `Attribute` parameter is `"ffi"`, this will return a Vec of the attribute's
@@ -41,7 +43,7 @@ fn parse_ffi_meta(attr: &Attribute) -> Vec<NestedMeta> {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub(super) enum WrappingType {
     /// An `Option<T>`.
     Option,
@@ -51,6 +53,18 @@ pub(super) enum WrappingType {
     /// optional collections should be avoided because empty and nil almost always mean the same
     /// thing.
     OptionVec,
+    /// A `HashSet<T>` or a `BTreeSet<T>` (whichever the `Ident` refers to).
+    Set(Ident),
+    /// An `Option<HashSet<T>>` or an `Option<BTreeSet<T>>`, which is supported for the same reason
+    /// as `OptionVec`.
+    OptionSet(Ident),
+    /// A `HashMap<K, T>` or a `BTreeMap<K, T>` (whichever the `Ident` refers to), where `K` is the
+    /// `PathSegment`.
+    Map(Ident, PathSegment),
+    /// A type wrapped in the first `WrappingType` (an `Option`, a `Vec`, or an `Option<Vec>`),
+    /// whose elements are wrapped in the second `WrappingType` (an `Option`, a `Vec`, an
+    /// `Option<Vec>`, or another `Nested` type), like `Vec<Vec<T>>` or `Option<Option<T>>`.
+    Nested(Box<Self>, Box<Self>),
     /// A `T`.
     None,
 }
@@ -81,6 +95,69 @@ pub fn is_repr_c(attrs: &[Attribute]) -> bool {
     })
 }
 
+/// Returns the integer type named by a `repr` attribute in `attrs` (like `u8` for `repr(u8)`), if
+/// there is one.
+///
+#[must_use]
+pub fn integer_repr(attrs: &[Attribute]) -> Option<Ident> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("repr"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(Meta::List(l)) => Some(l.nested),
+            _ => None,
+        })
+        .flatten()
+        .find_map(|nested| match nested {
+            NestedMeta::Meta(Meta::Path(path)) => path.get_ident().cloned().filter(|ident| {
+                [
+                    "u8", "u16", "u32", "u64", "usize", "i8", "i16", "i32", "i64", "isize",
+                ]
+                .iter()
+                .any(|int| ident == int)
+            }),
+            _ => None,
+        })
+}
+
+/// Returns `ty` with every occurrence of a key in `generics` (like `T`, including in `Vec<T>`,
+/// `Option<Box<T>>`, or `&[T]`) replaced by its value.
+///
+#[must_use]
+pub fn substitute_generics<S: BuildHasher>(ty: &Type, generics: &HashMap<Type, Type, S>) -> Type {
+    if let Some(concrete) = generics.get(ty) {
+        return concrete.clone();
+    }
+    let mut ty = ty.clone();
+    match &mut ty {
+        Type::Path(path) => {
+            for segment in &mut path.path.segments {
+                if let PathArguments::AngleBracketed(args) = &mut segment.arguments {
+                    for arg in &mut args.args {
+                        if let GenericArgument::Type(inner) = arg {
+                            *inner = substitute_generics(inner, generics);
+                        }
+                    }
+                }
+            }
+        }
+        Type::Reference(reference) => {
+            *reference.elem = substitute_generics(&reference.elem, generics);
+        }
+        Type::Tuple(tuple) => {
+            for elem in &mut tuple.elems {
+                *elem = substitute_generics(elem, generics);
+            }
+        }
+        Type::Array(array) => *array.elem = substitute_generics(&array.elem, generics),
+        Type::Slice(slice) => *slice.elem = substitute_generics(&slice.elem, generics),
+        Type::Paren(paren) => *paren.elem = substitute_generics(&paren.elem, generics),
+        Type::Group(group) => *group.elem = substitute_generics(&group.elem, generics),
+        _ => (),
+    }
+    ty
+}
+
 /// Filters and clones a slice of attributes into a `Vec<Attribute>` containing doc comment
 /// attributes only.
 ///
@@ -220,21 +297,65 @@ pub(super) fn get_segment_for_field(field_type: &Type) -> Option<PathSegment> {
     }
 }
 
+/// True if `field_type` is a tuple, a fixed-size array, a smart pointer (`Box`, `Rc`, or `Arc`), or a
+/// transparent type (see `transparent_resolution`), or an `Option` or a `Vec` (at any depth) of one.
+/// Fields like these are parsed with `TypeAttributes`, just like function parameters.
+///
+pub(super) fn is_parsed_like_parameter(field_type: &Type) -> bool {
+    match field_type {
+        Type::Tuple(_) | Type::Array(_) => true,
+        Type::Path(path) => path.path.segments.last().is_some_and(|segment| {
+            if segment.ident == "Box" || segment.ident == "Rc" || segment.ident == "Arc" {
+                return true;
+            }
+            // Resolution errors are reported when the field is parsed.
+            if crate::transparent_resolution::resolve_transparent_type(&path.path)
+                .map_or(true, |inner| inner.is_some())
+            {
+                return true;
+            }
+            let argument = match &segment.arguments {
+                PathArguments::AngleBracketed(arguments) => arguments.args.first(),
+                PathArguments::None | PathArguments::Parenthesized(_) => None,
+            };
+            match argument {
+                Some(GenericArgument::Type(ty))
+                    if segment.ident == "Option" || segment.ident == "Vec" =>
+                {
+                    is_parsed_like_parameter(ty)
+                }
+                _ => false,
+            }
+        }),
+        _ => false,
+    }
+}
+
 /// Given a `PathSegment`, flatten an outer generic (if any) so that we can work with the inner type
-/// directly to build the FFI.
+/// directly to build the FFI. The inner type is returned as a whole `PathSegment`, so that its own
+/// generic arguments (like the time zone in `DateTime<Utc>`) are still available.
 ///
 /// If `field_type_path` describes an `Option<Vec<T>>` (gross and rare, but necessary to support
-/// some structures), this will call itself to unwrap `Vec<T>`, then return the `Ident` for `T` and
-/// `WrappingType::OptionVec`.
+/// some structures), this will call itself to unwrap `Vec<T>`, then return the `PathSegment` for
+/// `T` and `WrappingType::OptionVec` (and likewise for an optional set).
 ///
-pub(super) fn separate_wrapping_type_from_inner_type(
+/// If `field_type_path` describes a map, the inner type is the type of its values, and the type of
+/// its keys is returned in `WrappingType::Map`.
+///
+/// If `field_type_path` describes an `Option` or a `Vec` of another `Option` or `Vec`, this will
+/// call itself to unwrap the inner type, then return the `PathSegment` for the innermost type and a
+/// `WrappingType::Nested` describing each level of wrapping.
+///
+pub(super) fn separate_wrapping_type_from_inner_segment(
     field_type_path: PathSegment,
-) -> (Ident, WrappingType) {
+) -> (PathSegment, WrappingType) {
     let wrapping_type = match field_type_path.ident.to_string().as_ref() {
         "Option" => WrappingType::Option,
         "Vec" => WrappingType::Vec,
+        "HashSet" | "BTreeSet" => WrappingType::Set(field_type_path.ident.clone()),
+        "HashMap" | "BTreeMap" => return separate_map_key_and_value(field_type_path),
         _ => {
-            return (field_type_path.ident, WrappingType::None);
+            return (field_type_path, WrappingType::None);
         }
     };
 
@@ -245,22 +366,10 @@ pub(super) fn separate_wrapping_type_from_inner_type(
         ),
         PathArguments::AngleBracketed(generic) => {
             if let Some(GenericArgument::Type(t)) = generic.args.first() {
-                if let Some(inner_segment) = get_segment_for_field(t) {
-                    if wrapping_type == WrappingType::Option && inner_segment.ident == "Vec" {
-                        let unwrapped =
-                            separate_wrapping_type_from_inner_type(inner_segment.clone());
-                        assert!(
-                            unwrapped.1 == WrappingType::Vec,
-                            "Expected Vec<T>, found {:?}",
-                            inner_segment
-                        );
-                        (unwrapped.0, WrappingType::OptionVec)
-                    } else {
-                        (inner_segment.ident, wrapping_type)
-                    }
-                } else {
-                    abort!(t.span(), "Unsupported path type in generic position")
-                }
+                get_segment_for_field(t).map_or_else(
+                    || abort!(t.span(), "Unsupported path type in generic position"),
+                    |inner_segment| unwrap_inner_segment(inner_segment, wrapping_type, t),
+                )
             } else {
                 abort!(generic.span(), "No generic args...?")
             }
@@ -272,6 +381,109 @@ pub(super) fn separate_wrapping_type_from_inner_type(
     }
 }
 
+/// Finishes separating `inner_segment` (the first type argument of a generic, `ty`) from
+/// `wrapping_type`, unwrapping it again if it's a collection inside of an `Option`, or if it's an
+/// `Option` or a `Vec` inside of another one.
+///
+fn unwrap_inner_segment(
+    inner_segment: PathSegment,
+    wrapping_type: WrappingType,
+    ty: &Type,
+) -> (PathSegment, WrappingType) {
+    let is_wrapper = |wrappers: &[&str]| wrappers.iter().any(|w| inner_segment.ident == w);
+    match wrapping_type {
+        WrappingType::Option
+            if is_wrapper(&["Vec", "HashSet", "BTreeSet", "HashMap", "BTreeMap"]) =>
+        {
+            match separate_wrapping_type_from_inner_segment(inner_segment) {
+                (inner, WrappingType::Vec) => (inner, WrappingType::OptionVec),
+                (inner, WrappingType::Set(set)) => (inner, WrappingType::OptionSet(set)),
+                (inner, WrappingType::Nested(_, elements)) => (
+                    inner,
+                    WrappingType::Nested(Box::new(WrappingType::OptionVec), elements),
+                ),
+                _ => abort!(
+                    ty.span(),
+                    "Optional maps are not supported; use an empty map instead."
+                ),
+            }
+        }
+        WrappingType::Option | WrappingType::Vec if is_wrapper(&["Option", "Vec"]) => {
+            let (inner, elements) = separate_wrapping_type_from_inner_segment(inner_segment);
+            if let WrappingType::Set(_) | WrappingType::OptionSet(_) | WrappingType::Map(..) =
+                elements
+            {
+                abort!(
+                    ty.span(),
+                    "Sets and maps can't be nested inside of an `Option` or a `Vec`."
+                );
+            }
+            (
+                inner,
+                WrappingType::Nested(Box::new(wrapping_type), Box::new(elements)),
+            )
+        }
+        WrappingType::Vec if is_wrapper(&["HashSet", "BTreeSet", "HashMap", "BTreeMap"]) => {
+            abort!(
+                ty.span(),
+                "Sets and maps can't be nested inside of an `Option` or a `Vec`."
+            )
+        }
+        WrappingType::Set(_) => {
+            abort_if_wrapped(&inner_segment, ty);
+            (inner_segment, wrapping_type)
+        }
+        _ => (inner_segment, wrapping_type),
+    }
+}
+
+/// Separates the type of the values of the map described by `map_type_path` from the type of its
+/// keys, returning the `PathSegment` for the values and a `WrappingType::Map` for the keys.
+///
+fn separate_map_key_and_value(map_type_path: PathSegment) -> (PathSegment, WrappingType) {
+    let mut segments = match &map_type_path.arguments {
+        PathArguments::AngleBracketed(generic) => generic.args.iter().map(|arg| match arg {
+            GenericArgument::Type(t) => get_segment_for_field(t).map_or_else(
+                || abort!(t.span(), "Unsupported path type in generic position"),
+                |segment| (segment, t),
+            ),
+            _ => abort!(arg.span(), "Expected a type argument."),
+        }),
+        PathArguments::None | PathArguments::Parenthesized(_) => abort!(
+            map_type_path.span(),
+            "Expected a map with key and value type arguments."
+        ),
+    };
+    match (segments.next(), segments.next()) {
+        (Some((key, key_type)), Some((value, value_type))) => {
+            abort_if_wrapped(&key, key_type);
+            abort_if_wrapped(&value, value_type);
+            (value, WrappingType::Map(map_type_path.ident, key))
+        }
+        _ => abort!(
+            map_type_path.span(),
+            "Expected a map with key and value type arguments."
+        ),
+    }
+}
+
+/// Aborts if `segment` (the element type of a set, or the key or value type of a map) is wrapped in
+/// another generic that we'd need to expose, since collections are only supported one level deep.
+///
+fn abort_if_wrapped(segment: &PathSegment, ty: &Type) {
+    if [
+        "Option", "Vec", "HashSet", "BTreeSet", "HashMap", "BTreeMap",
+    ]
+    .iter()
+    .any(|wrapper| segment.ident == wrapper)
+    {
+        abort!(
+            ty.span(),
+            "Set elements and map keys and values can't be wrapped in an `Option` or another collection."
+        );
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -308,11 +520,58 @@ mod tests {
         assert!(!is_repr_c(&*item.attrs));
     }
 
+    #[test]
+    fn test_integer_repr() {
+        let Ok(Item::Enum(item)) = syn::parse_str::<Item>(
+            r"
+            #[derive(Clone, Copy, Debug, PartialEq)]
+            #[repr(u8)]
+            enum TestEnum { A = 1, B = 7 }
+        ",
+        ) else {
+            panic!("Unexpected item type")
+        };
+        assert_eq!(integer_repr(&item.attrs), Some(format_ident!("u8")));
+        assert!(!is_repr_c(&item.attrs));
+
+        let Ok(Item::Enum(item)) = syn::parse_str::<Item>(
+            r"
+            #[repr(C)]
+            enum TestEnum { A, B }
+        ",
+        ) else {
+            panic!("Unexpected item type")
+        };
+        assert_eq!(integer_repr(&item.attrs), None);
+    }
+
+    #[test]
+    fn test_substitute_generics() {
+        let generics: HashMap<Type, Type> = std::iter::once((
+            syn::parse_str::<Type>("T").unwrap(),
+            syn::parse_str::<Type>("f64").unwrap(),
+        ))
+        .collect();
+        let ty = syn::parse_str::<Type>("Option<Vec<(T, String)>>").unwrap();
+        assert_eq!(
+            substitute_generics(&ty, &generics),
+            syn::parse_str::<Type>("Option<Vec<(f64, String)>>").unwrap()
+        );
+        let ty = syn::parse_str::<Type>("([T; 3], &[Option<T>])").unwrap();
+        assert_eq!(
+            substitute_generics(&ty, &generics),
+            syn::parse_str::<Type>("([f64; 3], &[Option<f64>])").unwrap()
+        );
+        let ty = syn::parse_str::<Type>("Tx").unwrap();
+        assert_eq!(substitute_generics(&ty, &generics), ty);
+    }
+
     #[test]
     fn test_no_wrapping_type() {
         let segment = syn::parse_str::<PathSegment>("SomeType").unwrap();
+        let (inner, wrapping_type) = separate_wrapping_type_from_inner_segment(segment);
         assert_eq!(
-            separate_wrapping_type_from_inner_type(segment),
+            (inner.ident, wrapping_type),
             (format_ident!("SomeType"), WrappingType::None)
         );
     }
@@ -320,8 +579,9 @@ mod tests {
     #[test]
     fn test_wrapping_vec() {
         let segment = syn::parse_str::<PathSegment>("Vec<SomeType>").unwrap();
+        let (inner, wrapping_type) = separate_wrapping_type_from_inner_segment(segment);
         assert_eq!(
-            separate_wrapping_type_from_inner_type(segment),
+            (inner.ident, wrapping_type),
             (format_ident!("SomeType"), WrappingType::Vec)
         );
     }
@@ -329,18 +589,86 @@ mod tests {
     #[test]
     fn test_wrapping_option() {
         let segment = syn::parse_str::<PathSegment>("Option<SomeType>").unwrap();
+        let (inner, wrapping_type) = separate_wrapping_type_from_inner_segment(segment);
         assert_eq!(
-            separate_wrapping_type_from_inner_type(segment),
+            (inner.ident, wrapping_type),
             (format_ident!("SomeType"), WrappingType::Option)
         );
     }
 
+    #[test]
+    fn test_wrapping_set() {
+        let segment = syn::parse_str::<PathSegment>("Option<BTreeSet<SomeType>>").unwrap();
+        let (inner, wrapping_type) = separate_wrapping_type_from_inner_segment(segment);
+        assert_eq!(
+            (inner.ident, wrapping_type),
+            (
+                format_ident!("SomeType"),
+                WrappingType::OptionSet(format_ident!("BTreeSet"))
+            )
+        );
+    }
+
+    #[test]
+    fn test_wrapping_map() {
+        let segment = syn::parse_str::<PathSegment>("HashMap<String, SomeType>").unwrap();
+        let (inner, wrapping_type) = separate_wrapping_type_from_inner_segment(segment);
+        let key = syn::parse_str::<PathSegment>("String").unwrap();
+        assert_eq!(
+            (inner.ident, wrapping_type),
+            (
+                format_ident!("SomeType"),
+                WrappingType::Map(format_ident!("HashMap"), key)
+            )
+        );
+    }
+
     #[test]
     fn test_wrapping_option_vec() {
         let segment = syn::parse_str::<PathSegment>("Option<Vec<SomeType>>").unwrap();
+        let (inner, wrapping_type) = separate_wrapping_type_from_inner_segment(segment);
         assert_eq!(
-            separate_wrapping_type_from_inner_type(segment),
+            (inner.ident, wrapping_type),
             (format_ident!("SomeType"), WrappingType::OptionVec)
         );
     }
+
+    #[test]
+    fn test_wrapping_nested() {
+        let segment = syn::parse_str::<PathSegment>("Vec<Vec<SomeType>>").unwrap();
+        let (inner, wrapping_type) = separate_wrapping_type_from_inner_segment(segment);
+        assert_eq!(
+            (inner.ident, wrapping_type),
+            (
+                format_ident!("SomeType"),
+                WrappingType::Nested(Box::new(WrappingType::Vec), Box::new(WrappingType::Vec))
+            )
+        );
+
+        let segment = syn::parse_str::<PathSegment>("Option<Vec<Option<SomeType>>>").unwrap();
+        let (inner, wrapping_type) = separate_wrapping_type_from_inner_segment(segment);
+        assert_eq!(
+            (inner.ident, wrapping_type),
+            (
+                format_ident!("SomeType"),
+                WrappingType::Nested(
+                    Box::new(WrappingType::OptionVec),
+                    Box::new(WrappingType::Option)
+                )
+            )
+        );
+
+        let segment = syn::parse_str::<PathSegment>("Option<Option<Vec<SomeType>>>").unwrap();
+        let (inner, wrapping_type) = separate_wrapping_type_from_inner_segment(segment);
+        assert_eq!(
+            (inner.ident, wrapping_type),
+            (
+                format_ident!("SomeType"),
+                WrappingType::Nested(
+                    Box::new(WrappingType::Option),
+                    Box::new(WrappingType::OptionVec)
+                )
+            )
+        );
+    }
 }
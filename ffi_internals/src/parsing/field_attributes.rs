@@ -8,7 +8,8 @@ use syn::{spanned::Spanned, Attribute, Ident, Lit, Meta, NestedMeta, Path};
 
 /// Field-level FFI helper attributes.
 ///
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct FieldAttributes {
     /// If `Some`, a path to the type that this field should be exposed as. This type must meet
     /// some prerequisites:
@@ -26,6 +27,46 @@ pub struct FieldAttributes {
     /// only be applied to fields whose type is `repr(C)` and safe to expose over FFI.
     ///
     pub raw: bool,
+
+    /// Whether the field's type is a `chrono::Duration` (or `TimeDelta`) or a
+    /// `std::time::Duration`, which is exposed as a `TimeInterval` (an `f64` number of seconds).
+    /// This has to be opted into, since other types can have the same name.
+    ///
+    pub duration: bool,
+
+    /// Whether to generate an additional getter that borrows this field's data instead of copying
+    /// it. On `Vec` fields whose elements are exposed as raw values (i.e., primitives or `raw`
+    /// fields), the getter returns an `FFISlice*` that points into the owning struct's memory. On
+    /// fields of other FFI types, the getter returns a non-owning pointer to the field.
+    ///
+    pub borrow: bool,
+
+    /// Whether to generate an additional getter that reads this field from an array of instances
+    /// in a single call. This is only supported on fields that aren't `Vec`s or `Option`s.
+    ///
+    pub batch: bool,
+
+    /// Whether to leave this field out of the FFI entirely. Skipped fields don't need to be
+    /// FFI-safe; the memberwise initializer fills them in with `default`, or with
+    /// `Default::default()` if there's no `default` path.
+    ///
+    pub skip: bool,
+
+    /// Whether to leave this field out of the memberwise initializer, while still exposing a
+    /// getter for it. Like a `skip`ped field, the initializer fills it in with `default`, or with
+    /// `Default::default()`.
+    ///
+    pub readonly: bool,
+
+    /// If `Some`, the name this field is exposed as in getter names and on the consumer, instead
+    /// of the field's own name.
+    ///
+    pub rename: Option<Ident>,
+
+    /// If `Some`, a path to a function that returns the value of a `skip` or `readonly` field in
+    /// the memberwise initializer.
+    ///
+    pub default: Option<Path>,
 }
 
 impl FieldAttributes {
@@ -38,12 +79,27 @@ impl FieldAttributes {
             .as_ref()
             .and_then(|p| p.segments.last().map(|s| &s.ident))
     }
+
+    /// Whether this field is left out of the memberwise initializer's arguments (i.e., it's
+    /// `skip`ped or `readonly`).
+    ///
+    #[must_use]
+    pub const fn is_defaulted(&self) -> bool {
+        self.skip || self.readonly
+    }
 }
 
 impl From<&[Attribute]> for FieldAttributes {
     fn from(attrs: &[Attribute]) -> Self {
         let mut expose_as: Option<Path> = None;
         let mut raw = false;
+        let mut duration = false;
+        let mut borrow = false;
+        let mut batch = false;
+        let mut skip = false;
+        let mut readonly = false;
+        let mut rename: Option<Ident> = None;
+        let mut default: Option<Path> = None;
         for meta_item in attrs.iter().flat_map(super::parse_ffi_meta) {
             match &meta_item {
                 NestedMeta::Meta(Meta::NameValue(m)) if m.path.is_ident("expose_as") => {
@@ -54,12 +110,71 @@ impl From<&[Attribute]> for FieldAttributes {
                 NestedMeta::Meta(Meta::Path(p)) if p.is_ident("raw") => {
                     raw = true;
                 }
+                NestedMeta::Meta(Meta::Path(p)) if p.is_ident("duration") => {
+                    duration = true;
+                }
+                NestedMeta::Meta(Meta::Path(p)) if p.is_ident("borrow") => {
+                    borrow = true;
+                }
+                NestedMeta::Meta(Meta::Path(p)) if p.is_ident("batch") => {
+                    batch = true;
+                }
+                NestedMeta::Meta(Meta::Path(p)) if p.is_ident("skip") => {
+                    skip = true;
+                }
+                NestedMeta::Meta(Meta::Path(p)) if p.is_ident("readonly") => {
+                    readonly = true;
+                }
+                NestedMeta::Meta(Meta::NameValue(m)) if m.path.is_ident("rename") => match &m.lit {
+                    Lit::Str(lit) => {
+                        if let Ok(ident) = syn::parse_str::<Ident>(&lit.value()) {
+                            rename = Some(ident);
+                        } else {
+                            emit_error!(
+                                lit.span(),
+                                "`rename` must be a valid identifier, like `rename = \"new_name\"`."
+                            );
+                        }
+                    }
+                    other => emit_error!(
+                        other.span(),
+                        "`rename` expects a string, like `rename = \"new_name\"`."
+                    ),
+                },
+                NestedMeta::Meta(Meta::NameValue(m)) if m.path.is_ident("default") => {
+                    match &m.lit {
+                        Lit::Str(lit) => {
+                            if let Ok(path) = syn::parse_str::<Path>(&lit.value()) {
+                                default = Some(path);
+                            } else {
+                                emit_error!(
+                                    lit.span(),
+                                    "`default` must be a path to a function, like `default = \"path::to::function\"`."
+                                );
+                            }
+                        }
+                        other => emit_error!(
+                            other.span(),
+                            "`default` expects a string, like `default = \"path::to::function\"`."
+                        ),
+                    }
+                }
                 _other => {
-                    emit_error!(meta_item.span(), "Unsupported ffi attribute -- only `raw` and `expose_as` are valid in this position");
+                    emit_error!(meta_item.span(), "Unsupported ffi attribute -- only `raw`, `duration`, `borrow`, `batch`, `expose_as`, `skip`, `readonly`, `rename`, and `default` are valid in this position");
                 }
             }
         }
-        Self { expose_as, raw }
+        Self {
+            expose_as,
+            raw,
+            duration,
+            borrow,
+            batch,
+            skip,
+            readonly,
+            rename,
+            default,
+        }
     }
 }
 
@@ -120,4 +235,36 @@ mod tests {
         .clone();
         assert!(!FieldAttributes::from(&*field.attrs).raw);
     }
+
+    #[test]
+    fn test_skip_rename_readonly_and_default() {
+        let Ok(Item::Struct(item)) = syn::parse_str::<Item>(
+            r#"
+                struct TestStruct {
+                    #[ffi(skip, default = "some::function")]
+                    skipped: Cache,
+                    #[ffi(readonly, rename = "identifier")]
+                    id: String,
+                }
+            "#,
+        ) else {
+            panic!("Unexpected item type")
+        };
+        let fields: Vec<FieldAttributes> = item
+            .fields
+            .iter()
+            .map(|field| FieldAttributes::from(&*field.attrs))
+            .collect();
+        assert!(fields[0].skip && fields[0].is_defaulted() && !fields[0].readonly);
+        assert_eq!(
+            fields[0].default,
+            Some(syn::parse_str::<Path>("some::function").unwrap())
+        );
+        assert!(fields[1].readonly && fields[1].is_defaulted() && !fields[1].skip);
+        assert_eq!(
+            fields[1].rename.as_ref().map(ToString::to_string),
+            Some("identifier".to_string())
+        );
+        assert!(fields[1].default.is_none());
+    }
 }
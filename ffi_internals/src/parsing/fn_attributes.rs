@@ -18,6 +18,11 @@ pub struct FnAttributes {
     ///
     pub raw_types: Vec<Ident>,
 
+    /// Any types in this function that should be exposed as a `TimeInterval`, like
+    /// `chrono::Duration` or `std::time::Duration`.
+    ///
+    pub duration_types: Vec<Ident>,
+
     /// A hashmap whose keys are `Ident`s for the generics used in this function and whose values
     /// are `Ident`s for the concrete types to use in place of the generic for FFI.
     ///
@@ -28,6 +33,7 @@ impl From<syn::AttributeArgs> for FnAttributes {
     fn from(args: syn::AttributeArgs) -> Self {
         let mut extend_type: Option<Ident> = None;
         let mut raw_types = vec![];
+        let mut duration_types = vec![];
         let mut generics = HashMap::<Type, Type>::new();
         for arg in &args {
             match arg {
@@ -54,11 +60,13 @@ impl From<syn::AttributeArgs> for FnAttributes {
                         if !raw_types.is_empty() {
                             abort!(m.span(), "Duplicate `raw_types` attribute defined for a single call. This attribute must be set once at most.")
                         }
-                        raw_types = paths
-                            .iter()
-                            .filter_map(syn::Path::get_ident)
-                            .cloned()
-                            .collect();
+                        raw_types = paths.iter().filter_map(Path::get_ident).cloned().collect();
+                    } else if m.path().is_ident("duration_types") {
+                        if !duration_types.is_empty() {
+                            abort!(m.span(), "Duplicate `duration_types` attribute defined for a single call. This attribute must be set once at most.")
+                        }
+                        duration_types =
+                            paths.iter().filter_map(Path::get_ident).cloned().collect();
                     } else if m.path().is_ident("generic") {
                         if let Meta::List(l) = m {
                             generics = l.nested.iter().fold(generics, |mut acc, n| {
@@ -87,7 +95,7 @@ impl From<syn::AttributeArgs> for FnAttributes {
                     } else {
                         abort!(
                             m.span(),
-                            "Unsupported ffi attribute {:?} -- expected `generic`, `raw_types`, or `extend_type`.",
+                            "Unsupported ffi attribute {:?} -- expected `generic`, `raw_types`, `duration_types`, or `extend_type`.",
                             m.path()
                         )
                     }
@@ -111,6 +119,7 @@ impl From<syn::AttributeArgs> for FnAttributes {
         Self {
             extend_type,
             raw_types,
+            duration_types,
             generics,
         }
     }
@@ -22,6 +22,11 @@ pub struct ImplAttributes {
     ///
     pub raw_types: Vec<Ident>,
 
+    /// Any types in this function that should be exposed as a `TimeInterval`, like
+    /// `chrono::Duration` or `std::time::Duration`.
+    ///
+    pub duration_types: Vec<Ident>,
+
     /// A description of this impl, to be used in generating a unique name for the type and impl.
     ///
     /// When operating on a trait impl, we can use the trait name, so this is unnecessary. However,
@@ -40,6 +45,16 @@ pub struct ImplAttributes {
     /// parameter: https://github.com/agrian-inc/ffi_common/issues/27.
     ///
     pub generics: HashMap<Type, Type>,
+
+    /// The names of methods in this impl that should also get a batched FFI function, which calls
+    /// the method on each instance in an array in a single call.
+    ///
+    pub batch: Vec<Ident>,
+
+    /// The names of methods in this impl that should also get an `_in_arena` FFI function, which
+    /// transfers ownership of the return value to an `FFIArena`.
+    ///
+    pub arena: Vec<Ident>,
 }
 
 impl From<syn::AttributeArgs> for ImplAttributes {
@@ -47,8 +62,11 @@ impl From<syn::AttributeArgs> for ImplAttributes {
         let mut ffi_imports = vec![];
         let mut consumer_imports = vec![];
         let mut raw_types = vec![];
+        let mut duration_types = vec![];
         let mut description: Option<Ident> = None;
         let mut generics = HashMap::<Type, Type>::new();
+        let mut batch = vec![];
+        let mut arena = vec![];
         for arg in &args {
             if let NestedMeta::Meta(m) = arg {
                 let paths: Vec<Path> = match m {
@@ -75,52 +93,34 @@ impl From<syn::AttributeArgs> for ImplAttributes {
                     if !raw_types.is_empty() {
                         abort!(m.span(), "Duplicate `raw_types` attribute defined for a single call. This attribute must be set once at most.")
                     }
-                    raw_types = paths
-                        .iter()
-                        .filter_map(syn::Path::get_ident)
-                        .cloned()
-                        .collect();
+                    raw_types = paths.iter().filter_map(Path::get_ident).cloned().collect();
+                } else if m.path().is_ident("duration_types") {
+                    if !duration_types.is_empty() {
+                        abort!(m.span(), "Duplicate `duration_types` attribute defined for a single call. This attribute must be set once at most.")
+                    }
+                    duration_types = paths.iter().filter_map(Path::get_ident).cloned().collect();
+                } else if m.path().is_ident("batch") {
+                    if !batch.is_empty() {
+                        abort!(m.span(), "Duplicate `batch` attribute defined for a single call. This attribute must be set once at most.")
+                    }
+                    batch = paths.iter().filter_map(Path::get_ident).cloned().collect();
+                } else if m.path().is_ident("arena") {
+                    if !arena.is_empty() {
+                        abort!(m.span(), "Duplicate `arena` attribute defined for a single call. This attribute must be set once at most.")
+                    }
+                    arena = paths.iter().filter_map(Path::get_ident).cloned().collect();
                 } else if m.path().is_ident("description") {
                     if description.is_some() {
                         abort!(m.span(), "Duplicate `description` attribute defined for a single call. This attribute must be set once at most.")
                     }
-                    if let Meta::List(l) = m {
-                        let nested = l.nested.first()
-                            .expect_or_abort("Attribute `description` missing nested value. Use it like `description(\"some description\"");
-                        if let NestedMeta::Lit(syn::Lit::Str(lit)) = nested {
-                            description = Some(quote::format_ident!("{}", lit.value()));
-                        }
-                    }
+                    description = parse_description(m);
                 } else if m.path().is_ident("generic") {
-                    if let Meta::List(l) = m {
-                        generics = l.nested.iter().fold(generics, |mut acc, n| {
-                            if let NestedMeta::Meta(nested_meta) = n {
-                                let generic = Type::Path(TypePath {
-                                    qself: None,
-                                    path: nested_meta.path().clone(),
-                                });
-                                if let Meta::NameValue(name_value) = nested_meta {
-                                    if let syn::Lit::Str(lit) = name_value.lit.clone() {
-                                        let ty: Type =
-                                            syn::parse_str(&lit.value()).unwrap_or_abort();
-                                        if acc.insert(generic.clone(), ty).is_some() {
-                                            abort!(
-                                                m.span(),
-                                                "Multiple definitions for generic {:?} found.",
-                                                generic
-                                            )
-                                        }
-                                    }
-                                }
-                            }
-                            acc
-                        });
-                    }
+                    parse_generics(m, &mut generics);
                 } else {
                     abort!(
                         m.span(),
                         "Unsupported ffi attribute {:?} -- expected `ffi_imports`, \
-`consumer_imports`, `raw_types`, `description`, or `generic`, ",
+`consumer_imports`, `raw_types`, `duration_types`, `description`, `generic`, `batch`, or `arena`, ",
                         m.path()
                     )
                 }
@@ -132,8 +132,52 @@ impl From<syn::AttributeArgs> for ImplAttributes {
             ffi_imports,
             consumer_imports,
             raw_types,
+            duration_types,
             description,
             generics,
+            batch,
+            arena,
+        }
+    }
+}
+
+/// Parses the `description("...")` attribute into an `Ident`.
+///
+fn parse_description(m: &Meta) -> Option<Ident> {
+    if let Meta::List(l) = m {
+        let nested = l.nested.first()
+            .expect_or_abort("Attribute `description` missing nested value. Use it like `description(\"some description\"");
+        if let NestedMeta::Lit(syn::Lit::Str(lit)) = nested {
+            return Some(quote::format_ident!("{}", lit.value()));
+        }
+    }
+    None
+}
+
+/// Parses the `generic(T = "ConcreteType", ...)` attribute, adding each generic and its concrete
+/// type to `generics`.
+///
+fn parse_generics(m: &Meta, generics: &mut HashMap<Type, Type>) {
+    if let Meta::List(l) = m {
+        for n in &l.nested {
+            if let NestedMeta::Meta(nested_meta) = n {
+                let generic = Type::Path(TypePath {
+                    qself: None,
+                    path: nested_meta.path().clone(),
+                });
+                if let Meta::NameValue(name_value) = nested_meta {
+                    if let syn::Lit::Str(lit) = name_value.lit.clone() {
+                        let ty: Type = syn::parse_str(&lit.value()).unwrap_or_abort();
+                        if generics.insert(generic.clone(), ty).is_some() {
+                            abort!(
+                                m.span(),
+                                "Multiple definitions for generic {:?} found.",
+                                generic
+                            )
+                        }
+                    }
+                }
+            }
         }
     }
 }
@@ -3,10 +3,20 @@
 //! attributes.
 //!
 
-use syn::{spanned::Spanned, Attribute, Lit, Meta, NestedMeta, Path};
+use proc_macro_error::abort;
+use std::collections::HashMap;
+use syn::{
+    parenthesized,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    spanned::Spanned,
+    Attribute, DataStruct, GenericArgument, GenericParam, Generics, Ident, Lit, Meta, NestedMeta,
+    Path, PathArguments, Token, Type,
+};
 
 /// Struct-level FFI helper attributes.
 ///
+#[allow(clippy::struct_excessive_bools)]
 pub struct StructAttributes {
     /// Alias modules that are referenced by the types of this struct's fields.
     ///
@@ -27,6 +37,31 @@ pub struct StructAttributes {
     /// generated memberwise init bypasses those restrictions.
     ///
     pub forbid_memberwise_init: bool,
+    /// If true, generate a `repr(C)` snapshot type containing the FFI representation of every
+    /// field, and a getter that fills it in a single call.
+    ///
+    pub snapshot: bool,
+    /// If true, generate an `_in_arena` variant of each getter, which transfers ownership of the
+    /// returned value to an `FFIArena`.
+    ///
+    pub arena: bool,
+    /// If true, instances of this struct are allocated in an `Arc` when they're passed across the
+    /// FFI, so that copying them only increments a reference count.
+    ///
+    pub shared: bool,
+    /// If true, this struct is a newtype that's exposed just like the type it wraps, instead of as
+    /// an opaque type.
+    ///
+    pub transparent: bool,
+    /// The names of the associated constants of a bit-flag newtype (like `READ` for
+    /// `Permissions::READ`), if this struct is one. Bit-flag types are exposed like transparent
+    /// types, with a getter for each constant.
+    ///
+    pub flags: Option<Vec<Ident>>,
+    /// The concrete instantiations of a generic struct to expose, each of which gets its own FFI
+    /// module and consumer type.
+    ///
+    pub instantiations: Vec<Instantiation>,
 }
 
 /// Helper attributes that describe special behavior for structs with a custom FFI.
@@ -45,14 +80,162 @@ pub struct CustomAttributes {
     pub path: String,
 }
 
+/// A concrete instantiation of a generic struct to expose, and the name to expose it as, as in
+/// `ffi(instantiate(Measurement<f64> as MeasurementF64))`.
+///
+pub struct Instantiation {
+    /// The concrete type (like `Measurement<f64>`).
+    ///
+    pub ty: Type,
+    /// The name of the type alias that's generated for `ty`, which is also the name of its FFI
+    /// module and consumer type.
+    ///
+    pub name: Ident,
+}
+
+impl Parse for Instantiation {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let ty = input.parse()?;
+        let _: Token![as] = input.parse()?;
+        let name = input.parse()?;
+        Ok(Self { ty, name })
+    }
+}
+
+impl Instantiation {
+    /// Returns `data` (the fields of the generic struct `type_name`, with the parameters in
+    /// `generics`) with each type parameter replaced by its type argument in this instantiation.
+    ///
+    /// # Proc Macro Errors
+    ///
+    /// Fails if this instantiation isn't of `type_name`, if `generics` has any parameters that
+    /// aren't types (like lifetimes), or if the number of type arguments doesn't match.
+    ///
+    #[must_use]
+    pub fn monomorphize(
+        &self,
+        type_name: &Ident,
+        generics: &Generics,
+        data: &DataStruct,
+    ) -> DataStruct {
+        let params: Vec<Type> = generics
+            .params
+            .iter()
+            .map(|param| match param {
+                GenericParam::Type(param) => {
+                    let ident = &param.ident;
+                    syn::parse_quote!(#ident)
+                }
+                other => abort!(
+                    other.span(),
+                    "Only type parameters are supported for `ffi(instantiate(...))`."
+                ),
+            })
+            .collect();
+        let args: Vec<Type> = match &self.ty {
+            Type::Path(path) => path
+                .path
+                .segments
+                .last()
+                .filter(|segment| &segment.ident == type_name)
+                .map(|segment| match &segment.arguments {
+                    PathArguments::AngleBracketed(args) => args
+                        .args
+                        .iter()
+                        .filter_map(|arg| match arg {
+                            GenericArgument::Type(ty) => Some(ty.clone()),
+                            _ => None,
+                        })
+                        .collect(),
+                    _ => Vec::new(),
+                }),
+            _ => None,
+        }
+        .unwrap_or_else(|| {
+            abort!(
+                self.ty.span(),
+                "Expected an instantiation of `{}`.",
+                type_name
+            )
+        });
+        if args.len() != params.len() {
+            abort!(
+                self.ty.span(),
+                "Expected {} type arguments for `{}`.",
+                params.len(),
+                type_name
+            );
+        }
+        let generics: HashMap<Type, Type> = params.into_iter().zip(args).collect();
+        let mut data = data.clone();
+        for field in &mut data.fields {
+            field.ty = super::substitute_generics(&field.ty, &generics);
+        }
+        data
+    }
+}
+
+/// Parses the instantiations in `attr` if it's an `ffi(instantiate(...))` attribute. These can't
+/// be parsed as a `Meta`, so they have to be in their own `ffi` attribute.
+///
+fn parse_instantiations(attr: &Attribute) -> Option<Vec<Instantiation>> {
+    if !attr.path.is_ident("ffi") {
+        return None;
+    }
+    let is_instantiate = attr
+        .parse_args_with(|input: ParseStream<'_>| {
+            let ident: Ident = input.parse()?;
+            let _: proc_macro2::TokenStream = input.parse()?;
+            Ok(ident == "instantiate")
+        })
+        .unwrap_or(false);
+    if !is_instantiate {
+        return None;
+    }
+    let instantiations = attr
+        .parse_args_with(|input: ParseStream<'_>| {
+            let _: Ident = input.parse()?;
+            let content;
+            let _ = parenthesized!(content in input);
+            if !input.is_empty() {
+                return Err(
+                    input.error("`instantiate` must be the only argument of its `ffi` attribute")
+                );
+            }
+            Punctuated::<Instantiation, Token![,]>::parse_terminated(&content)
+        })
+        .unwrap_or_else(|err| {
+            abort!(
+                err.span(),
+                "Error parsing `ffi(instantiate(...))`: {}.",
+                err
+            )
+        });
+    Some(instantiations.into_iter().collect())
+}
+
 impl From<&[Attribute]> for StructAttributes {
+    #[allow(clippy::too_many_lines)]
     fn from(attrs: &[Attribute]) -> Self {
         let mut alias_modules = vec![];
         let mut custom_attributes: Option<CustomAttributes> = None;
         let mut consumer_imports = vec![];
         let mut ffi_mod_imports = vec![];
         let mut forbid_memberwise_init = false;
-        for meta_item in attrs.iter().flat_map(super::parse_ffi_meta) {
+        let mut snapshot = false;
+        let mut arena = false;
+        let mut shared = false;
+        let mut transparent = false;
+        let mut flags: Option<Vec<Ident>> = None;
+        let mut instantiations = vec![];
+        let mut meta_attrs = vec![];
+        for attr in attrs {
+            match parse_instantiations(attr) {
+                Some(parsed) => instantiations.extend(parsed),
+                None => meta_attrs.push(attr),
+            }
+        }
+        for meta_item in meta_attrs.into_iter().flat_map(super::parse_ffi_meta) {
             match &meta_item {
                 NestedMeta::Meta(Meta::NameValue(m)) if m.path.is_ident("custom") => {
                     if let Lit::Str(lit) = &m.lit {
@@ -95,12 +278,33 @@ impl From<&[Attribute]> for StructAttributes {
                 NestedMeta::Meta(Meta::Path(m)) if m.is_ident("forbid_memberwise_init") => {
                     forbid_memberwise_init = true;
                 }
+                NestedMeta::Meta(Meta::Path(m)) if m.is_ident("snapshot") => {
+                    snapshot = true;
+                }
+                NestedMeta::Meta(Meta::Path(m)) if m.is_ident("arena") => {
+                    arena = true;
+                }
+                NestedMeta::Meta(Meta::Path(m)) if m.is_ident("shared") => {
+                    shared = true;
+                }
+                NestedMeta::Meta(Meta::Path(m)) if m.is_ident("transparent") => {
+                    transparent = true;
+                }
+                NestedMeta::Meta(Meta::List(l)) if l.path.is_ident("flags") => {
+                    flags.get_or_insert_with(Vec::new).extend(
+                        l.nested
+                            .iter()
+                            .filter_map(super::parse_path_from_nested_meta)
+                            .filter_map(|path| path.get_ident().cloned()),
+                    );
+                }
                 other => {
-                    proc_macro_error::abort!(
+                    abort!(
                         other.span(),
                         "Unsupported ffi attribute -- only \
 `custom`, `alias_modules`, `consumer_imports`, `ffi_mod_imports`, `failable_init`, `failable_fns`, \
-and `forbid_memberwise_init` are allowed in this position."
+`forbid_memberwise_init`, `snapshot`, `arena`, `shared`, `transparent`, `flags`, and `instantiate` are \
+allowed in this position."
                     );
                 }
             }
@@ -111,6 +315,12 @@ and `forbid_memberwise_init` are allowed in this position."
             consumer_imports,
             ffi_mod_imports,
             forbid_memberwise_init,
+            snapshot,
+            arena,
+            shared,
+            transparent,
+            flags,
+            instantiations,
         }
     }
 }
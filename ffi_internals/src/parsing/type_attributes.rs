@@ -6,6 +6,7 @@
 //! straightforward to work with.
 //!
 
+use crate::type_ffi::{CollectionType, TypeIdentifier};
 use proc_macro_error::{abort, OptionExt};
 use quote::format_ident;
 use std::convert::TryFrom;
@@ -21,7 +22,7 @@ use syn::{spanned::Spanned, Ident, Type};
 /// use ffi_internals::{parsing::TypeAttributes, type_ffi::{TypeIdentifier, TypeFFI}};
 ///
 /// let ty: syn::Type = syn::parse_str("Result<Foo>").unwrap();
-/// let initial = TypeAttributes::initial(ty, vec![], None);
+/// let initial = TypeAttributes::initial(ty, vec![], vec![], None);
 /// let native_type_data = TypeFFI::from(initial);
 /// assert_eq!(native_type_data.native_type, TypeIdentifier::Boxed(format_ident!("Foo")));
 /// assert_eq!(native_type_data.is_result, true);
@@ -43,6 +44,10 @@ pub struct TypeAttributes {
     ///
     pub is_collection: bool,
 
+    /// The length of the fixed-size array that `ty` was discovered in, if any.
+    ///
+    pub array_len: Option<usize>,
+
     /// Whether `ty` was discovered in the `Success` variant of a `Result`.
     ///
     pub is_result: bool,
@@ -51,15 +56,28 @@ pub struct TypeAttributes {
     ///
     pub is_cow: bool,
 
+    /// The smart pointer (`Box`, `Rc`, or `Arc`) that `ty` was discovered inside of, if any.
+    ///
+    pub pointer: Option<Ident>,
+
     /// Whether this field or parameter is a borrowed reference to a `ty`.
     ///
     pub is_borrow: bool,
 
+    /// The set or map that `ty` was discovered in (as an element or a value), if any.
+    ///
+    pub collection_type: Option<CollectionType>,
+
     /// `Ident`s of types that ought to be exposed directly to the FFI in a `NativeType::Raw`, as
     /// opposed to being wrapped in a `Box`.
     ///
     pub raw_types: Vec<Ident>,
 
+    /// `Ident`s of types that ought to be exposed as a `NativeType::Duration` (i.e., as a
+    /// `TimeInterval`).
+    ///
+    pub duration_types: Vec<Ident>,
+
     self_type: Option<Ident>,
 }
 
@@ -68,26 +86,42 @@ impl TypeAttributes {
     /// fields are all set to false.
     ///
     #[must_use]
-    pub fn initial(ty: Type, raw_types: Vec<Ident>, self_type: Option<Ident>) -> Self {
+    pub const fn initial(
+        ty: Type,
+        raw_types: Vec<Ident>,
+        duration_types: Vec<Ident>,
+        self_type: Option<Ident>,
+    ) -> Self {
         Self {
             ty,
             is_option: false,
             is_collection: false,
+            array_len: None,
             is_result: false,
             is_cow: false,
+            pointer: None,
             is_borrow: false,
+            collection_type: None,
             raw_types,
+            duration_types,
             self_type,
         }
     }
 }
 
+/// The largest tuple that can be exposed (see `ffi_core::tuple`).
+///
+const MAX_TUPLE_LEN: usize = 6;
+
 #[derive(Debug)]
 enum SupportedGeneric {
     Option,
     Vec,
     Result,
     Cow,
+    Set,
+    Map,
+    Pointer,
 }
 
 impl TryFrom<&str> for SupportedGeneric {
@@ -99,6 +133,9 @@ impl TryFrom<&str> for SupportedGeneric {
             "Vec" => Ok(Self::Vec),
             "Result" => Ok(Self::Result),
             "Cow" => Ok(Self::Cow),
+            "HashSet" | "BTreeSet" => Ok(Self::Set),
+            "HashMap" | "BTreeMap" => Ok(Self::Map),
+            "Box" | "Rc" | "Arc" => Ok(Self::Pointer),
             _ => {
                 Err("Not a supported generic. Assume this is a non-generic type that we can parse.")
             }
@@ -107,6 +144,7 @@ impl TryFrom<&str> for SupportedGeneric {
 }
 
 impl From<TypeAttributes> for crate::type_ffi::TypeFFI {
+    #[allow(clippy::too_many_lines)]
     fn from(mut unparsed: TypeAttributes) -> Self {
         // Note that this match intentionally performs a partial move. If we need to call this
         // recursively, we'll be passing `unparsed` back to the same method, but we should always
@@ -116,6 +154,16 @@ impl From<TypeAttributes> for crate::type_ffi::TypeFFI {
         // generic so we're at the point where we can convert to a `NativeTypeData`.)
         match unparsed.ty {
             Type::Array(ty) => {
+                if unparsed.pointer.is_some() {
+                    abort!(ty.span(), "Smart pointers to arrays are not supported.");
+                }
+                if unparsed.is_collection || unparsed.collection_type.is_some() {
+                    abort!(
+                        ty.span(),
+                        "Fixed-size arrays can't be nested inside of a `Vec`, a set, a map, or another array."
+                    );
+                }
+                unparsed.array_len = Some(array_len(&ty.len));
                 unparsed.ty = *ty.elem;
                 unparsed.is_collection = true;
                 Self::from(unparsed)
@@ -128,11 +176,56 @@ impl From<TypeAttributes> for crate::type_ffi::TypeFFI {
                     .expect_or_abort("Type path has zero segments.");
                 let ident = segment.ident.clone();
                 if let Ok(generic) = SupportedGeneric::try_from(&*ident.to_string()) {
+                    if unparsed.collection_type.is_some() {
+                        abort!(
+                            segment.span(),
+                            "Set elements and map keys and values can't be wrapped in another generic."
+                        );
+                    }
+                    if unparsed.pointer.is_some() {
+                        abort!(
+                            segment.span(),
+                            "Smart pointers can only contain other FFI types and `str`, not another generic."
+                        );
+                    }
+                    let is_nested = match generic {
+                        SupportedGeneric::Option => unparsed.is_option || unparsed.is_collection,
+                        SupportedGeneric::Vec => unparsed.is_collection,
+                        _ => false,
+                    };
+                    if is_nested {
+                        unparsed.ty = Type::Path(ty.clone());
+                        return nested(unparsed);
+                    }
                     match generic {
                         SupportedGeneric::Option => unparsed.is_option = true,
                         SupportedGeneric::Vec => unparsed.is_collection = true,
                         SupportedGeneric::Result => unparsed.is_result = true,
                         SupportedGeneric::Cow => unparsed.is_cow = true,
+                        SupportedGeneric::Pointer => {
+                            if unparsed.array_len.is_some() {
+                                abort!(
+                                    segment.span(),
+                                    "Fixed-size arrays of smart pointers are not supported."
+                                );
+                            }
+                            unparsed.pointer = Some(ident.clone());
+                        }
+                        SupportedGeneric::Set | SupportedGeneric::Map => {
+                            if unparsed.is_collection {
+                                abort!(segment.span(), "Nested collections are not supported.");
+                            }
+                            // The map's key type is set below, once we have its arguments.
+                            unparsed.collection_type = Some(CollectionType::Set(ident.clone()));
+                            if matches!(generic, SupportedGeneric::Set) {
+                                unparsed.is_collection = true;
+                            } else if unparsed.is_option {
+                                abort!(
+                                    segment.span(),
+                                    "Optional maps are not supported; use an empty map instead."
+                                );
+                            }
+                        }
                     };
                     // Dig the argument type out of the generics for the limited cases we're
                     // supporting right now and update `unparsed` with its element type.
@@ -143,10 +236,16 @@ impl From<TypeAttributes> for crate::type_ffi::TypeFFI {
                         }
                     };
                     // If we're looking at a `Cow`, the type wrapped in the smart pointer is the
-                    // last argument. Otherwise we're looking at a `Vec`, `Option`, or `Result`, in
-                    // which case the type we want is the first argument.
+                    // last argument. If we're looking at a map, we want the type of its values (the
+                    // second argument), and we'll need the type of its keys (the first argument).
+                    // Otherwise we're looking at a `Vec`, set, `Option`, or `Result`, in which case
+                    // the type we want is the first argument.
                     let type_argument = if unparsed.is_cow {
                         arguments.args.last()
+                    } else if matches!(generic, SupportedGeneric::Map) {
+                        let key = map_key(arguments.args.first(), &unparsed.raw_types);
+                        unparsed.collection_type = Some(CollectionType::Map(ident, key));
+                        arguments.args.iter().nth(1)
                     } else {
                         arguments.args.first()
                     }
@@ -166,24 +265,43 @@ impl From<TypeAttributes> for crate::type_ffi::TypeFFI {
                     let mut ident = ident;
                     // If we have a `Self`, replace that with the actual type, since `Self` won't be
                     // in scope in our FFI module.
-                    if ident == format_ident!("Self") {
+                    let is_self = ident == format_ident!("Self");
+                    if is_self {
                         ident = unparsed.self_type.expect(
                             "Found 'Self' type, but no `self_type` provided to replace it with.",
                         );
                     }
                     let native_type = if unparsed.raw_types.contains(&ident) {
-                        crate::type_ffi::TypeIdentifier::Raw(ident)
+                        TypeIdentifier::Raw(ident)
+                    } else if unparsed.duration_types.contains(&ident) {
+                        TypeIdentifier::Duration(ident)
+                    } else if is_self {
+                        TypeIdentifier::from(ident)
                     } else {
-                        crate::type_ffi::TypeIdentifier::from(ident)
+                        TypeIdentifier::from(segment)
                     };
+                    let in_unsupported_wrapper = unparsed.pointer.is_some()
+                        || unparsed.array_len.is_some()
+                        || unparsed.collection_type.is_some()
+                        || unparsed.is_cow;
+                    let (native_type, transparent) =
+                        resolve_transparent(native_type, in_unsupported_wrapper, &ty.path);
+                    if unparsed.pointer.is_some() {
+                        pointee(&native_type, segment);
+                    }
 
                     Self {
                         native_type,
                         is_option: unparsed.is_option,
                         is_vec: unparsed.is_collection,
+                        array_len: unparsed.array_len,
                         is_result: unparsed.is_result,
                         is_cow: unparsed.is_cow,
+                        pointer: unparsed.pointer,
+                        transparent,
                         is_borrow: unparsed.is_borrow,
+                        collection_type: unparsed.collection_type,
+                        wrapped: None,
                     }
                 }
             }
@@ -197,13 +315,257 @@ impl From<TypeAttributes> for crate::type_ffi::TypeFFI {
                 Self::from(unparsed)
             }
             Type::Slice(ty) => {
+                if unparsed.pointer.is_some() {
+                    abort!(ty.span(), "Smart pointers to slices are not supported.");
+                }
                 unparsed.ty = *ty.elem;
                 unparsed.is_collection = true;
                 Self::from(unparsed)
             }
+            Type::Tuple(ty) => {
+                unparsed.ty = Type::Tuple(ty.clone());
+                tuple(&ty, &unparsed)
+            }
             _ => {
                 abort!(unparsed.ty.span(), "Unsupported type.")
             }
         }
     }
 }
+
+/// Returns a `TypeFFI` for an `Option` or a `Vec` (described by `unparsed`) of `unparsed.ty`, which
+/// is itself an `Option` or a `Vec`.
+///
+fn nested(unparsed: TypeAttributes) -> crate::type_ffi::TypeFFI {
+    let span = unparsed.ty.span();
+    if unparsed.array_len.is_some() {
+        abort!(
+            span,
+            "Fixed-size arrays of `Option`s or `Vec`s are not supported."
+        );
+    }
+    let wrapped = crate::type_ffi::TypeFFI::from(TypeAttributes::initial(
+        unparsed.ty,
+        unparsed.raw_types,
+        unparsed.duration_types,
+        unparsed.self_type,
+    ));
+    if wrapped.collection_type.is_some() {
+        abort!(
+            span,
+            "Sets and maps can't be nested inside of an `Option` or a `Vec`."
+        );
+    }
+    crate::type_ffi::TypeFFI {
+        native_type: wrapped.native_type.clone(),
+        is_option: unparsed.is_option,
+        is_vec: unparsed.is_collection,
+        array_len: None,
+        is_result: unparsed.is_result,
+        is_cow: unparsed.is_cow,
+        pointer: None,
+        transparent: None,
+        is_borrow: unparsed.is_borrow,
+        collection_type: None,
+        wrapped: Some(Box::new(wrapped)),
+    }
+}
+
+/// Returns a `TypeFFI` for the tuple `ty`, or for an `Option` or a `Vec` of it (described by
+/// `unparsed`).
+///
+fn tuple(ty: &syn::TypeTuple, unparsed: &TypeAttributes) -> crate::type_ffi::TypeFFI {
+    if !(2..=MAX_TUPLE_LEN).contains(&ty.elems.len()) {
+        abort!(
+            ty.span(),
+            "Only tuples of 2 to {} elements are supported.",
+            MAX_TUPLE_LEN
+        );
+    }
+    if unparsed.collection_type.is_some() {
+        abort!(
+            ty.span(),
+            "Tuples can't be set elements or map keys or values."
+        );
+    }
+    if unparsed.array_len.is_some() {
+        abort!(ty.span(), "Fixed-size arrays of tuples are not supported.");
+    }
+    if unparsed.pointer.is_some() {
+        abort!(ty.span(), "Smart pointers to tuples are not supported.");
+    }
+    let elements: Vec<crate::type_ffi::TypeFFI> = ty
+        .elems
+        .iter()
+        .map(|element| {
+            let element_type = crate::type_ffi::TypeFFI::from(TypeAttributes::initial(
+                element.clone(),
+                unparsed.raw_types.clone(),
+                unparsed.duration_types.clone(),
+                unparsed.self_type.clone(),
+            ));
+            if element_type.is_borrow || element_type.is_result {
+                abort!(
+                    element.span(),
+                    "Tuple elements can't be borrowed references or `Result`s."
+                );
+            }
+            element_type
+        })
+        .collect();
+    let tuple = crate::type_ffi::TypeFFI {
+        native_type: TypeIdentifier::Tuple(elements),
+        is_option: false,
+        is_vec: false,
+        array_len: None,
+        is_result: unparsed.is_result,
+        is_cow: false,
+        pointer: None,
+        transparent: None,
+        is_borrow: unparsed.is_borrow,
+        collection_type: None,
+        wrapped: None,
+    };
+    if !unparsed.is_option && !unparsed.is_collection {
+        if tuple.is_result {
+            abort!(ty.span(), "Tuples can't be returned in a `Result`.");
+        }
+        return tuple;
+    }
+    // `Option`s and `Vec`s of tuples are exposed like nested types.
+    crate::type_ffi::TypeFFI {
+        native_type: tuple.native_type.clone(),
+        is_option: unparsed.is_option,
+        is_vec: unparsed.is_collection,
+        array_len: None,
+        is_result: unparsed.is_result,
+        is_cow: unparsed.is_cow,
+        pointer: None,
+        transparent: None,
+        is_borrow: unparsed.is_borrow,
+        collection_type: None,
+        wrapped: Some(Box::new(crate::type_ffi::TypeFFI {
+            is_result: false,
+            is_borrow: false,
+            ..tuple
+        })),
+    }
+}
+
+/// If `native_type` is a transparent type (see `transparent_resolution`), returns the type that it
+/// wraps, along with the transparent type. Otherwise, returns `native_type` as it is.
+///
+/// Transparent types are supported on their own, and in an `Option`, a `Vec`, or a `Result`, so
+/// this aborts if `in_unsupported_wrapper` (a smart pointer, a fixed-size array, a set or a map, or
+/// a `Cow`) is true.
+///
+fn resolve_transparent(
+    native_type: TypeIdentifier,
+    in_unsupported_wrapper: bool,
+    path: &syn::Path,
+) -> (TypeIdentifier, Option<syn::Path>) {
+    let TypeIdentifier::Boxed(ident) = &native_type else {
+        return (native_type, None);
+    };
+    // Types in another crate are referenced by their full path, since they may not be imported.
+    // Anything else (including a `Self` type that was replaced with `ident`) is referenced by name,
+    // like any other FFI type.
+    let is_external = path.segments.len() > 1
+        && path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == *ident)
+        && !path
+            .segments
+            .first()
+            .is_some_and(|first| first.ident == "self" || first.ident == "super");
+    let path = if is_external {
+        path.clone()
+    } else {
+        syn::Path::from(ident.clone())
+    };
+    let inner = crate::transparent_resolution::resolve_transparent_type(&path)
+        .unwrap_or_else(|err| abort!(path.span(), "Transparent type resolution error: {}", err));
+    let inner_segment = match &inner {
+        Some(Type::Path(inner)) => inner
+            .path
+            .segments
+            .last()
+            .expect_or_abort("Type path has zero segments."),
+        Some(_) => abort!(path.span(), "Unsupported transparent type."),
+        None => return (native_type, None),
+    };
+    if in_unsupported_wrapper {
+        abort!(
+            path.span(),
+            "Transparent types are only supported on their own, or in an `Option`, a `Vec`, or a `Result`."
+        );
+    }
+    (TypeIdentifier::from(inner_segment), Some(path))
+}
+
+/// Checks that `native_type`, which was found inside of a smart pointer (as described by `segment`),
+/// is a type that we can expose through one: another FFI type, or a string slice.
+///
+fn pointee(native_type: &TypeIdentifier, segment: &syn::PathSegment) {
+    match native_type {
+        TypeIdentifier::Boxed(_) => (),
+        TypeIdentifier::String if segment.ident == "str" => (),
+        TypeIdentifier::String => abort!(
+            segment.span(),
+            "Use a smart pointer to a `str` (like `Arc<str>`) instead of one to a `String`."
+        ),
+        _ => abort!(
+            segment.span(),
+            "Smart pointers are only supported around other FFI types and `str`."
+        ),
+    }
+}
+
+/// Returns the length of a fixed-size array, given the expression for it in the array's type. This
+/// has to be an integer literal, since we can't evaluate constants in a macro.
+///
+fn array_len(len: &syn::Expr) -> usize {
+    let span = len.span();
+    let len = match len {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(len),
+            ..
+        }) => len
+            .base10_parse::<usize>()
+            .unwrap_or_else(|err| abort!(len.span(), "Invalid array length: {}", err)),
+        other => abort!(
+            other.span(),
+            "Fixed-size array lengths must be integer literals."
+        ),
+    };
+    if len == 0 {
+        abort!(span, "Fixed-size arrays must have at least one element.");
+    }
+    len
+}
+
+/// Returns a `TypeIdentifier` for the type of the keys of a map, given the map's first generic
+/// argument.
+///
+fn map_key(argument: Option<&syn::GenericArgument>, raw_types: &[Ident]) -> TypeIdentifier {
+    let segment = match argument.expect_or_abort("Generic type has no arguments") {
+        syn::GenericArgument::Type(Type::Path(ty)) => ty
+            .path
+            .segments
+            .last()
+            .expect_or_abort("Type path has zero segments."),
+        other => abort!(other.span(), "Unsupported map key type."),
+    };
+    if SupportedGeneric::try_from(&*segment.ident.to_string()).is_ok() {
+        abort!(
+            segment.span(),
+            "Set elements and map keys and values can't be wrapped in another generic."
+        );
+    }
+    if raw_types.contains(&segment.ident) {
+        TypeIdentifier::Raw(segment.ident.clone())
+    } else {
+        TypeIdentifier::from(segment)
+    }
+}
@@ -0,0 +1,274 @@
+//!
+//! This module records the types that derive `FFI` with the `ffi(transparent)` attribute, along
+//! with the type that each of them wraps.
+//!
+//! Other types with fields, parameters, or return values of a transparent type (like
+//! `pub struct Acres(f64)`) can then expose them just like the wrapped type, without any extra
+//! attributes.
+//!
+//! Like aliases (see `alias_resolution`), these are stored on disk, because each crate is compiled
+//! separately, in a file for the crate that defines them. A type in the same crate is picked
+//! up by its name, from the types that have been registered while compiling that crate, so it has to
+//! be derived before any type that uses it (earlier in the same file, or in a module that's declared
+//! first). A type in another crate is picked up when it's referenced by a path that starts with that
+//! crate's name (like `model::FieldId`). Each time a crate is compiled, the types it registers
+//! replace the ones from its previous build.
+//!
+//! The recorded type only decides what a transparent type is exposed as. The derived FFI converts
+//! to and from it through `ffi_core::transparent::Transparent`, which is dispatched on the type
+//! that's actually in scope, so if that type isn't transparent, or doesn't wrap the recorded type,
+//! the FFI won't compile (instead of exposing the wrong type).
+//!
+
+use quote::ToTokens;
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{LazyLock, Mutex},
+};
+use syn::{Ident, Path, Type};
+
+/// The transparent types registered by this process, and the directory they're shared in, behind a
+/// `Mutex` to ensure that multiple operations don't attempt to write to it at once.
+///
+static REGISTRY: LazyLock<Mutex<Registry>> = LazyLock::new(|| {
+    Mutex::new(Registry::new(
+        PathBuf::from(env!("OUT_DIR")).join("transparent_types"),
+    ))
+});
+
+/// Describes errors that can occur during transparent type resolution.
+///
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// An error occurred when (de)serializing with `serde_json`.
+    #[error("serde_json error: `{0}`")]
+    Serde(#[from] serde_json::Error),
+    /// An error occurred when reading from or writing to the disk.
+    #[error("IO error: `{0}`")]
+    Io(#[from] std::io::Error),
+    /// A mutex error occurred.
+    #[error("Mutex error: `{0}`")]
+    Mutex(String),
+    /// A type that was stored on disk couldn't be parsed.
+    #[error("Parse error: `{0}`")]
+    Parse(String),
+}
+
+impl<T> From<std::sync::PoisonError<T>> for Error {
+    fn from(e: std::sync::PoisonError<T>) -> Self {
+        Self::Mutex(e.to_string())
+    }
+}
+
+/// The transparent types registered by a crate, as they're stored on disk.
+///
+#[derive(Debug, Default, serde::Deserialize, serde::Serialize)]
+struct CrateTypes {
+    /// Identifies the compilation that registered `types`, so that the next one can replace them.
+    session: String,
+    /// The names of the transparent types, mapped to the types that they wrap.
+    types: HashMap<String, String>,
+}
+
+/// Registers and resolves transparent types.
+///
+struct Registry {
+    /// The directory that transparent types are shared in, with a file for each crate.
+    map_dir: PathBuf,
+    /// Identifies this process, which registers types for the crate (or crates, in a long-lived
+    /// proc macro host) that it compiles.
+    session: String,
+    /// The transparent types registered by this process, keyed by crate and name.
+    local: HashMap<String, HashMap<String, String>>,
+}
+
+impl Registry {
+    /// Returns a new `Registry` that shares transparent types in `map_dir`.
+    ///
+    fn new(map_dir: PathBuf) -> Self {
+        let started = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_nanos());
+        Self {
+            map_dir,
+            session: format!("{}-{}", std::process::id(), started),
+            local: HashMap::new(),
+        }
+    }
+
+    /// Records that `type_name` in `krate` is a transparent type that wraps `inner`, replacing
+    /// whatever it was registered as before.
+    ///
+    fn register(&mut self, krate: &str, type_name: &Ident, inner: &Type) -> Result<(), Error> {
+        let inner = inner.to_token_stream().to_string();
+        let _previous = self
+            .local
+            .entry(krate.to_string())
+            .or_default()
+            .insert(type_name.to_string(), inner.clone());
+        let mut crate_types = self
+            .read_crate_types(krate)?
+            .filter(|crate_types| crate_types.session == self.session)
+            .unwrap_or_else(|| CrateTypes {
+                session: self.session.clone(),
+                types: HashMap::new(),
+            });
+        let _previous = crate_types.types.insert(type_name.to_string(), inner);
+        // Other crates may be reading this file, so it's replaced all at once.
+        std::fs::create_dir_all(&self.map_dir)?;
+        let file_path = self.crate_file(krate);
+        let temporary_path = file_path.with_extension(&self.session);
+        std::fs::write(&temporary_path, serde_json::to_string(&crate_types)?)?;
+        std::fs::rename(temporary_path, file_path)?;
+        Ok(())
+    }
+
+    /// If `path` (which is referenced in `krate`) names a transparent type, returns the type that
+    /// it wraps.
+    ///
+    fn resolve(&self, krate: &str, path: &Path) -> Result<Option<Type>, Error> {
+        let type_name = match path.segments.last() {
+            Some(segment) => segment.ident.to_string(),
+            None => return Ok(None),
+        };
+        let other_crate = path
+            .segments
+            .first()
+            .filter(|_| path.segments.len() > 1)
+            .map(|segment| segment.ident.to_string())
+            .filter(|first| !["crate", "self", "super", krate].contains(&first.as_str()));
+        let inner = match other_crate {
+            Some(other_crate) => self
+                .read_crate_types(&other_crate)?
+                .and_then(|mut crate_types| crate_types.types.remove(&type_name)),
+            None => None,
+        };
+        inner
+            .or_else(|| {
+                self.local
+                    .get(krate)
+                    .and_then(|types| types.get(&type_name))
+                    .cloned()
+            })
+            .map(|inner| syn::parse_str(&inner).map_err(|err| Error::Parse(err.to_string())))
+            .transpose()
+    }
+
+    /// Returns the path to the file that `krate`'s transparent types are shared in.
+    ///
+    fn crate_file(&self, krate: &str) -> PathBuf {
+        self.map_dir.join(format!("{krate}.json"))
+    }
+
+    /// Reads the transparent types that `krate` shared, if it's shared any.
+    ///
+    fn read_crate_types(&self, krate: &str) -> Result<Option<CrateTypes>, Error> {
+        match std::fs::File::open(self.crate_file(krate)) {
+            Ok(file) => Ok(Some(serde_json::from_reader(std::io::BufReader::new(
+                file,
+            ))?)),
+            Err(_) => Ok(None),
+        }
+    }
+}
+
+/// Returns the name of the crate that's being compiled.
+///
+fn current_crate() -> String {
+    std::env::var("CARGO_CRATE_NAME").unwrap_or_default()
+}
+
+/// Records that `type_name` is a transparent type that wraps `inner`, in the crate that's being
+/// compiled.
+///
+/// # Errors
+///
+/// This function will return an error if anything goes wrong when getting a lock on the registry,
+/// reading or writing the file that the crate's types are shared in, or parsing the file's JSON.
+///
+pub fn register_transparent_type(type_name: &Ident, inner: &Type) -> Result<(), Error> {
+    REGISTRY
+        .lock()?
+        .register(&current_crate(), type_name, inner)
+}
+
+/// If `path` names a transparent type (in the crate that's being compiled, or in another crate
+/// whose name it starts with), returns the type that it wraps.
+///
+/// # Errors
+///
+/// This function will return an error if anything goes wrong when getting a lock on the registry,
+/// reading the file that another crate's types are shared in, or parsing the file's JSON (or the
+/// type that was recorded).
+///
+pub fn resolve_transparent_type(path: &Path) -> Result<Option<Type>, Error> {
+    REGISTRY.lock()?.resolve(&current_crate(), path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::format_ident;
+
+    /// Returns a registry with its own directory, so that tests don't share registered types.
+    ///
+    fn registry(name: &str) -> Registry {
+        let map_dir =
+            std::env::temp_dir().join(format!("transparent_types_{}_{}", name, std::process::id()));
+        let _removed = std::fs::remove_dir_all(&map_dir);
+        Registry::new(map_dir)
+    }
+
+    #[test]
+    fn test_transparent_type_resolution() -> Result<(), Error> {
+        let mut registry = registry("resolution");
+        let inner: Type = syn::parse_quote!(Option<f64>);
+        registry.register("model", &format_ident!("Acres"), &inner)?;
+        assert_eq!(
+            registry.resolve("model", &syn::parse_quote!(Acres))?,
+            Some(inner.clone())
+        );
+        assert_eq!(
+            registry.resolve("model", &syn::parse_quote!(crate::units::Acres))?,
+            Some(inner.clone())
+        );
+        assert_eq!(
+            registry.resolve("model", &syn::parse_quote!(Hectares))?,
+            None
+        );
+        // Other crates only pick up the type by a path that starts with the crate's name.
+        assert_eq!(registry.resolve("app", &syn::parse_quote!(Acres))?, None);
+        assert_eq!(
+            registry.resolve("app", &syn::parse_quote!(model::Acres))?,
+            Some(inner)
+        );
+        std::fs::remove_dir_all(&registry.map_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_transparent_types_are_replaced() -> Result<(), Error> {
+        let mut registry = registry("replaced");
+        registry.register("model", &format_ident!("Acres"), &syn::parse_quote!(f64))?;
+        registry.register("model", &format_ident!("Acres"), &syn::parse_quote!(f32))?;
+        assert_eq!(
+            registry.resolve("model", &syn::parse_quote!(Acres))?,
+            Some(syn::parse_quote!(f32))
+        );
+
+        // A later build of the same crate replaces everything it registered before.
+        let mut rebuild = Registry::new(registry.map_dir.clone());
+        rebuild.register("model", &format_ident!("Hectares"), &syn::parse_quote!(f64))?;
+        assert_eq!(
+            rebuild.resolve("app", &syn::parse_quote!(model::Acres))?,
+            None
+        );
+        assert_eq!(
+            rebuild.resolve("app", &syn::parse_quote!(model::Hectares))?,
+            Some(syn::parse_quote!(f64))
+        );
+        std::fs::remove_dir_all(&registry.map_dir)?;
+        Ok(())
+    }
+}
@@ -8,26 +8,35 @@ use crate::{
     parsing::{FieldAttributes, WrappingType},
 };
 use heck::SnakeCase;
-use proc_macro2::TokenStream;
+use proc_macro2::{Literal, TokenStream};
 use proc_macro_error::{abort, OptionExt};
 use quote::{format_ident, quote};
-use syn::{spanned::Spanned, Ident, Type};
+use syn::{spanned::Spanned, GenericArgument, Ident, PathArguments, PathSegment, Type};
 
 const STRING: &str = "String";
 const STR: &str = "str";
 const DATETIME: &str = "NaiveDateTime";
+const TZ_DATETIME: &str = "DateTime";
+const FIXED_OFFSET: &str = "FixedOffset";
+const DATE: &str = "NaiveDate";
+const TIME: &str = "NaiveTime";
 const UUID: &str = "Uuid";
 const BOOL: &str = "bool";
 const U8: &str = "u8";
 const U16: &str = "u16";
 const U32: &str = "u32";
 const U64: &str = "u64";
+const U128: &str = "u128";
+const USIZE: &str = "usize";
 const I8: &str = "i8";
 const I16: &str = "i16";
 const I32: &str = "i32";
 const I64: &str = "i64";
+const I128: &str = "i128";
+const ISIZE: &str = "isize";
 const F32: &str = "f32";
 const F64: &str = "f64";
+const CHAR: &str = "char";
 
 /// Describes a Rust type that is exposed via FFI (as the type of a field, or the type returned by a
 /// function, or a function parameter, etc).
@@ -42,6 +51,26 @@ pub enum TypeIdentifier {
     /// A timestamp that's safe to expose across the FFI (see `ffi_core::datetime`).
     ///
     DateTime,
+    /// A `DateTime<Utc>`, which is exposed as a timestamp just like `DateTime`.
+    ///
+    UtcDateTime,
+    /// A `DateTime<FixedOffset>`, which is exposed as `CalendarComponents` (see
+    /// `ffi_core::datetime`) so that its offset is preserved.
+    ///
+    OffsetDateTime,
+    /// A `NaiveDate`, which is exposed as `CalendarComponents`.
+    ///
+    Date,
+    /// A `NaiveTime`, which is exposed as `CalendarComponents`.
+    ///
+    Time,
+    /// A `chrono::Duration` or a `std::time::Duration` (whichever the `Ident` refers to), which is
+    /// exposed as an `f64` number of seconds (see `ffi_core::datetime::TimeInterval`).
+    ///
+    /// Since other types share these names, durations are opt-in: fields need `#[ffi(duration)]`,
+    /// and function and impl signatures need `duration_types(...)`.
+    ///
+    Duration(Ident),
     /// A type that should be exposed as a raw value (like an i32, or a repr(C) enum).
     ///
     Raw(Ident),
@@ -51,20 +80,174 @@ pub enum TypeIdentifier {
     /// A Uuid.
     ///
     Uuid,
+    /// A tuple of the types described by each `TypeFFI`, which is exposed as an `FFITuple*` (see
+    /// `ffi_core::tuple`) of its elements' FFI types.
+    ///
+    Tuple(Vec<TypeFFI>),
 }
 
 impl From<Ident> for TypeIdentifier {
     fn from(type_path: Ident) -> Self {
         match &*type_path.to_string() {
             DATETIME => Self::DateTime,
+            DATE => Self::Date,
+            TIME => Self::Time,
             STRING | STR => Self::String,
             UUID => Self::Uuid,
-            BOOL | U8 | U16 | U32 | U64 | I8 | I16 | I32 | I64 | F32 | F64 => Self::Raw(type_path),
+            BOOL | U8 | U16 | U32 | U64 | U128 | USIZE | I8 | I16 | I32 | I64 | I128 | ISIZE
+            | F32 | F64 | CHAR => Self::Raw(type_path),
             _other => Self::Boxed(type_path),
         }
     }
 }
 
+impl From<&PathSegment> for TypeIdentifier {
+    /// Like `From<Ident>`, but also looks at the generic arguments of `segment` (if any), so that
+    /// `DateTime<FixedOffset>` can be distinguished from `DateTime<Utc>`.
+    ///
+    /// A bare `DateTime` (with no time zone argument) can't be identified, so it's rejected rather
+    /// than exposed as a timestamp in some assumed time zone.
+    ///
+    fn from(segment: &PathSegment) -> Self {
+        if segment.ident != TZ_DATETIME {
+            return Self::from(segment.ident.clone());
+        }
+        let time_zone = match &segment.arguments {
+            PathArguments::AngleBracketed(generic) => match generic.args.first() {
+                Some(GenericArgument::Type(Type::Path(path))) => path.path.segments.last(),
+                _ => None,
+            },
+            PathArguments::None | PathArguments::Parenthesized(_) => abort!(
+                segment.span(),
+                "`DateTime` is missing its time zone argument; use `DateTime<Utc>` or `DateTime<FixedOffset>`."
+            ),
+        };
+        match time_zone.map(|s| s.ident.to_string()) {
+            Some(time_zone) if time_zone == FIXED_OFFSET => Self::OffsetDateTime,
+            Some(time_zone) if time_zone == "Utc" => Self::UtcDateTime,
+            _ => abort!(
+                segment.span(),
+                "Only `DateTime<Utc>` and `DateTime<FixedOffset>` are supported."
+            ),
+        }
+    }
+}
+
+impl TypeIdentifier {
+    /// True if arguments of this type are converted with `ffi_core::datetime::CheckedFromFFI`,
+    /// because their FFI values may not describe a valid date or time (or Unicode scalar).
+    ///
+    fn has_checked_conversion(&self) -> bool {
+        match self {
+            Self::UtcDateTime | Self::OffsetDateTime | Self::Date | Self::Time => true,
+            Self::Raw(inner) => inner == CHAR,
+            _ => false,
+        }
+    }
+
+    /// The path to the `CheckedFromFFI::checked_from_ffi` impl for this type, which returns a
+    /// `Result` (so generated code follows it with `?`).
+    ///
+    fn checked_from_ffi(&self) -> TokenStream {
+        let native_type = self.native_type();
+        quote!(<#native_type as ffi_common::core::datetime::CheckedFromFFI>::checked_from_ffi)
+    }
+
+    /// The native Rust type that this identifies.
+    ///
+    fn native_type(&self) -> TokenStream {
+        match self {
+            Self::Boxed(inner) | Self::Raw(inner) | Self::Duration(inner) => quote!(#inner),
+            Self::DateTime => quote!(chrono::NaiveDateTime),
+            Self::UtcDateTime => quote!(chrono::DateTime<chrono::Utc>),
+            Self::OffsetDateTime => quote!(chrono::DateTime<chrono::FixedOffset>),
+            Self::Date => quote!(chrono::NaiveDate),
+            Self::Time => quote!(chrono::NaiveTime),
+            Self::String => quote!(String),
+            Self::Uuid => quote!(Uuid),
+            Self::Tuple(elements) => {
+                let elements = elements.iter().map(TypeFFI::native_type);
+                quote!((#(#elements),*))
+            }
+        }
+    }
+
+    /// The name of the type that this identifies in the consumer's language.
+    ///
+    fn consumer_type(&self) -> String {
+        match self {
+            Self::Boxed(inner) => inner.to_string(),
+            Self::Raw(inner) => crate::consumer_type_for(&inner.to_string(), false),
+            Self::DateTime | Self::UtcDateTime => "Date".to_string(),
+            Self::OffsetDateTime | Self::Date | Self::Time => "DateComponents".to_string(),
+            Self::Duration(_) => "TimeInterval".to_string(),
+            Self::Uuid => "UUID".to_string(),
+            Self::String => "String".to_string(),
+            Self::Tuple(elements) => format!(
+                "({})",
+                elements
+                    .iter()
+                    .map(|e| e.consumer_type(None))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+/// Describes a set or a map that a `TypeFFI`'s `native_type` is the element (or value) type of.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum CollectionType {
+    /// A `HashSet` or a `BTreeSet` (whichever the `Ident` refers to). Sets are exposed as an
+    /// `FFIArray*` of their elements, just like a `Vec`.
+    ///
+    Set(Ident),
+    /// A `HashMap` or a `BTreeMap` (whichever the `Ident` refers to) with keys of the type described
+    /// by the `TypeIdentifier`. Maps are exposed as an `FFIMap` (see `ffi_core::collections`) of
+    /// `FFIArray*`s of their keys and values.
+    ///
+    Map(Ident, TypeIdentifier),
+}
+
+/// Returns the path to the smart pointer type `pointer` (`Box`, `Rc`, or `Arc`), which may not be
+/// imported where it's used.
+///
+fn pointer_path(pointer: &Ident) -> TokenStream {
+    match &*pointer.to_string() {
+        "Rc" => quote!(std::rc::Rc),
+        "Arc" => quote!(std::sync::Arc),
+        _ => quote!(Box),
+    }
+}
+
+/// The path to the trait that allocates and releases instances of opaque types that are passed
+/// across the FFI (see `ffi_core::handle`).
+///
+fn opaque_handle() -> TokenStream {
+    quote!(ffi_common::core::handle::OpaqueHandle)
+}
+
+/// Returns the FFI-safe type that a raw `inner` type is exposed as. Most raw types are FFI-safe as
+/// they are, but some scalars (like `u128` and `char`) don't have a C equivalent, so they're
+/// exposed with a wrapper from `ffi_core::scalar`.
+///
+fn raw_ffi_type(inner: &Ident) -> Ident {
+    match &*inner.to_string() {
+        U128 => format_ident!("FFIu128"),
+        I128 => format_ident!("FFIi128"),
+        CHAR => format_ident!("FFIchar"),
+        _ => inner.clone(),
+    }
+}
+
+/// True if a raw `inner` type is exposed with a wrapper type instead of as itself (see
+/// `raw_ffi_type`).
+///
+pub(crate) fn raw_type_is_wrapped(inner: &Ident) -> bool {
+    raw_ffi_type(inner) != *inner
+}
+
 /// The context in which a type is being referenced. Sometimes the mutability of a reference, or
 /// even the type it's exposed as is different depending on whether it's being returned to the
 /// consumer or passed in as an argument to a Rust function.
@@ -80,10 +263,13 @@ pub enum Context {
 /// Describes the supported language-level generic wrappers around a `NativeType`, so that we can
 /// expose an `Option<Foo>` or even a `Result<Vec<Foo>>`.
 ///
-/// It's worth noting that these are only supported one level deep; we won't be able to expose a
-/// `Vec<Vec<Foo>>` without making some larger improvements to the way we parse types.
+/// The flags describe a single level of wrapping. When a type is wrapped in more than one level of
+/// `Option`s and `Vec`s (like a `Vec<Vec<Foo>>` or an `Option<Option<Foo>>`), `is_option` and
+/// `is_vec` describe the outermost wrapper, and `wrapped` describes the type inside of it. Sets and
+/// maps are still only supported one level deep; we won't be able to expose a
+/// `HashMap<String, Vec<Foo>>` without making some larger improvements to the way we parse types.
 ///
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 #[allow(clippy::struct_excessive_bools)]
 pub struct TypeFFI {
     /// The underlying type being exposed.
@@ -92,49 +278,141 @@ pub struct TypeFFI {
     /// True if `native_type` is wrapped in an `Option`, otherwise false.
     ///
     pub is_option: bool,
-    /// True if `native_type` is the type of the elements in a `Vec` or slice, otherwise false.
+    /// True if `native_type` is the type of the elements in a `Vec`, slice, fixed-size array, or set,
+    /// otherwise false.
     ///
     pub is_vec: bool,
+    /// If `native_type` is the type of the elements in a fixed-size array, the array's length.
+    /// Arrays of raw types are exposed inline in an `FFIFixedArray` (see `ffi_core::fixed_array`),
+    /// and arrays of other types are exposed just like a `Vec`.
+    ///
+    pub array_len: Option<usize>,
     /// True if `native_type` is the type of the `Success` variant of a `Result`, otherwise false.
     ///
     pub is_result: bool,
     /// True if `native_type` is wrapped in a `Cow`, otherwise false.
     ///
     pub is_cow: bool,
+    /// The smart pointer (`Box`, `Rc`, or `Arc`) that `native_type` is wrapped in, if any. Smart
+    /// pointers are exposed just like the type they point to, so they're only supported around other
+    /// FFI types and `str`.
+    ///
+    pub pointer: Option<Ident>,
+    /// The newtype with the `ffi(transparent)` attribute that `native_type` is wrapped in, if any.
+    /// Transparent types are exposed just like the type they wrap, so they're converted to and from
+    /// it with `ffi_core::transparent::Transparent`.
+    ///
+    pub transparent: Option<syn::Path>,
     /// True if we're dealing with a borrowed reference to `native_type`./
     ///
     pub is_borrow: bool,
+    /// The set or map that `native_type` is the element (or value) type of, if any.
+    ///
+    pub collection_type: Option<CollectionType>,
+    /// If this is an `Option` or a `Vec` of a type that's wrapped in another `Option` or `Vec`, the
+    /// type of its elements (which has the same `native_type`). Otherwise, `None`.
+    ///
+    pub wrapped: Option<Box<Self>>,
 }
 
 impl From<(TypeIdentifier, WrappingType)> for TypeFFI {
     fn from(data: (TypeIdentifier, WrappingType)) -> Self {
         let (native_type, wrapping_type) = data;
+        let (is_option, is_vec, collection_type) = match wrapping_type {
+            WrappingType::Nested(outer, elements) => {
+                let outer = Self::from((native_type.clone(), *outer));
+                return Self {
+                    wrapped: Some(Box::new(Self::from((native_type, *elements)))),
+                    ..outer
+                };
+            }
+            WrappingType::Option => (true, false, None),
+            WrappingType::Vec => (false, true, None),
+            WrappingType::OptionVec => (true, true, None),
+            WrappingType::Set(set) => (false, true, Some(CollectionType::Set(set))),
+            WrappingType::OptionSet(set) => (true, true, Some(CollectionType::Set(set))),
+            WrappingType::Map(map, key) => (
+                false,
+                false,
+                Some(CollectionType::Map(map, TypeIdentifier::from(&key))),
+            ),
+            WrappingType::None => (false, false, None),
+        };
         Self {
             native_type,
-            is_option: wrapping_type == WrappingType::Option
-                || wrapping_type == WrappingType::OptionVec,
-            is_vec: wrapping_type == WrappingType::Vec || wrapping_type == WrappingType::OptionVec,
+            is_option,
+            is_vec,
+            array_len: None,
             is_result: false,
             is_cow: false,
+            pointer: None,
+            transparent: None,
             is_borrow: false,
+            collection_type,
+            wrapped: None,
         }
     }
 }
 
 impl TypeFFI {
+    /// Wraps `conversion` (from `argument_into_rust`) if it can fail, so that a failure sets the last
+    /// error message and evaluates to `failure` (generally, an early `return`) instead.
+    ///
+    pub(crate) fn catch_argument_errors(
+        &self,
+        conversion: TokenStream,
+        failure: &TokenStream,
+    ) -> TokenStream {
+        if !self.has_fallible_argument() {
+            return conversion;
+        }
+        let native_type = self.native_type();
+        quote!(ffi_common::core::try_or_set_error!(
+            (|| -> Result<#native_type, String> {
+                let value: #native_type = #conversion;
+                Ok(value)
+            })(),
+            #failure
+        ))
+    }
+
     /// Generates a `TokenStream` for turning an argument of the FFI type represented by `self` into
-    /// a native Rust type.
+    /// a native Rust type. If `has_fallible_argument`, the conversion uses `?`, so it needs to be
+    /// wrapped with `catch_argument_errors`.
     ///
     #[must_use]
+    #[allow(clippy::too_many_lines)]
     pub fn argument_into_rust(
         &self,
         field_name: &TokenStream,
         has_custom_implementation: bool,
     ) -> TokenStream {
-        // All FFIArrayT types have a `From<FFIArrayT> for Vec<T>` impl, so we can treat them all
-        // the same for the sake of native Rust assignment.
+        if let Some(wrapped) = &self.wrapped {
+            return self.nested_argument_into_rust(wrapped, field_name, has_custom_implementation);
+        }
+        if self.array_len.is_some() {
+            return self.array_argument_into_rust(field_name, has_custom_implementation);
+        }
+        if self.pointer.is_some() {
+            return self.pointer_argument_into_rust(field_name);
+        }
+        if self.transparent.is_some() {
+            return self.transparent_argument_into_rust(field_name);
+        }
+        match &self.collection_type {
+            Some(CollectionType::Set(_)) => {
+                let elements = self.vec_argument_into_rust(field_name);
+                return if self.is_option {
+                    quote!(#elements.map(|v| v.into_iter().collect()))
+                } else {
+                    quote!(#elements.into_iter().collect())
+                };
+            }
+            Some(CollectionType::Map(..)) => return self.map_argument_into_rust(field_name),
+            None => (),
+        }
         if self.is_vec {
-            return quote!(#field_name.into());
+            return self.vec_argument_into_rust(field_name);
         }
 
         match self.native_type {
@@ -145,7 +423,11 @@ impl TypeFFI {
                 let (conversion_or_borrow, none) = if self.is_borrow {
                     (quote!(&*#field_name), quote!(&None))
                 } else {
-                    (quote!((*Box::from_raw(#field_name)).into()), quote!(None))
+                    let handle = opaque_handle();
+                    (
+                        quote!(#handle::from_handle(#field_name).into()),
+                        quote!(None),
+                    )
                 };
                 if self.is_option {
                     quote! {
@@ -165,7 +447,11 @@ impl TypeFFI {
                 let (conversion_or_borrow, none) = if self.is_borrow {
                     (quote!(Some(&*#field_name)), quote!(&None))
                 } else {
-                    (quote!(Some(*Box::from_raw(#field_name))), quote!(None))
+                    let handle = opaque_handle();
+                    (
+                        quote!(Some(#handle::from_handle(#field_name))),
+                        quote!(None),
+                    )
                 };
                 quote! {
                     if #field_name.is_null() {
@@ -179,7 +465,8 @@ impl TypeFFI {
                 let conversion_or_borrow = if self.is_borrow {
                     quote!(&*#field_name)
                 } else {
-                    quote!(*Box::from_raw(#field_name))
+                    let handle = opaque_handle();
+                    quote!(#handle::from_handle(#field_name))
                 };
                 quote!(#conversion_or_borrow)
             }
@@ -195,41 +482,99 @@ impl TypeFFI {
             TypeIdentifier::DateTime => {
                 quote!((&*Box::from_raw(#field_name)).into())
             }
-            TypeIdentifier::Raw(_) if self.is_option => {
+            TypeIdentifier::UtcDateTime if self.is_option => {
+                let checked = self.native_type.checked_from_ffi();
                 quote! {
                     if #field_name.is_null() {
                         None
                     } else {
-                        Some(*Box::from_raw(#field_name))
+                        Some(#checked(&*Box::from_raw(#field_name))?)
                     }
                 }
             }
-            TypeIdentifier::Raw(_) => {
-                quote!(#field_name)
+            TypeIdentifier::UtcDateTime => {
+                let checked = self.native_type.checked_from_ffi();
+                quote!(#checked(&*Box::from_raw(#field_name))?)
             }
-            TypeIdentifier::String if self.is_option => {
+            TypeIdentifier::OffsetDateTime | TypeIdentifier::Date | TypeIdentifier::Time
+                if self.is_option =>
+            {
+                let checked = self.native_type.checked_from_ffi();
                 quote! {
                     if #field_name.is_null() {
                         None
                     } else {
-                        Some(ffi_common::core::string::string_from_c(#field_name))
+                        Some(#checked(&ffi_common::core::allocator::import_box(#field_name))?)
                     }
                 }
             }
-            TypeIdentifier::String => {
-                quote!(ffi_common::core::string::string_from_c(#field_name))
+            TypeIdentifier::OffsetDateTime | TypeIdentifier::Date | TypeIdentifier::Time => {
+                let checked = self.native_type.checked_from_ffi();
+                quote!(#checked(&#field_name)?)
             }
-            TypeIdentifier::Uuid if self.is_option => {
+            TypeIdentifier::Duration(_) if self.is_option => {
                 quote! {
                     if #field_name.is_null() {
                         None
                     } else {
-                        Some(ffi_common::core::string::uuid_from_c(#field_name))
+                        Some(ffi_common::core::datetime::TimeInterval::from_time_interval(
+                            ffi_common::core::allocator::import_box(#field_name),
+                        ))
                     }
                 }
             }
-            TypeIdentifier::Uuid => {
-                quote!(ffi_common::core::string::uuid_from_c(#field_name))
+            TypeIdentifier::Duration(_) => {
+                quote!(ffi_common::core::datetime::TimeInterval::from_time_interval(#field_name))
+            }
+            TypeIdentifier::Raw(_) if self.native_type.has_checked_conversion() => {
+                let checked = self.native_type.checked_from_ffi();
+                if self.is_option {
+                    quote! {
+                        if #field_name.is_null() {
+                            None
+                        } else {
+                            Some(#checked(&ffi_common::core::allocator::import_box(#field_name))?)
+                        }
+                    }
+                } else {
+                    quote!(#checked(&#field_name)?)
+                }
+            }
+            TypeIdentifier::Raw(_) | TypeIdentifier::Uuid if self.is_option => {
+                quote! {
+                    if #field_name.is_null() {
+                        None
+                    } else {
+                        Some(ffi_common::core::allocator::import_box(#field_name).into())
+                    }
+                }
+            }
+            TypeIdentifier::Raw(_) | TypeIdentifier::Uuid => {
+                quote!(#field_name.into())
+            }
+            TypeIdentifier::String if self.is_option => {
+                quote! {
+                    if #field_name.is_null() {
+                        None
+                    } else {
+                        Some(ffi_common::core::string::string_from_c(#field_name))
+                    }
+                }
+            }
+            TypeIdentifier::String => {
+                quote!(ffi_common::core::string::string_from_c(#field_name))
+            }
+            TypeIdentifier::Tuple(ref elements) => {
+                let (tuple, fields) = tuple_struct(elements.len());
+                let bindings = tuple_bindings(elements.len());
+                let conversions = elements
+                    .iter()
+                    .zip(&bindings)
+                    .map(|(element, binding)| element.element_into_rust(binding, false));
+                quote!({
+                    let #tuple { #(#fields: #bindings),* } = #field_name;
+                    (#({ #conversions }),*)
+                })
             }
         }
     }
@@ -238,11 +583,41 @@ impl TypeFFI {
     /// an FFI type.
     ///
     #[must_use]
+    #[allow(clippy::too_many_lines)]
     pub fn rust_to_ffi_value(
         &self,
         accessor: &TokenStream,
         attributes: &FieldAttributes,
     ) -> TokenStream {
+        if let Some(wrapped) = &self.wrapped {
+            return self.nested_rust_to_ffi_value(wrapped, accessor, attributes);
+        }
+        if self.array_len.is_some() {
+            return self.array_to_ffi_value(accessor, attributes);
+        }
+        if self.pointer.is_some() {
+            return self.pointer_to_ffi_value(accessor, attributes);
+        }
+        if self.transparent.is_some() {
+            return self.transparent_to_ffi_value(accessor, attributes);
+        }
+        match &self.collection_type {
+            // Sets are copied into a `Vec` first, so that they can be exposed as an `FFIArray*`.
+            Some(CollectionType::Set(_)) => {
+                let element = self.native_type.native_type();
+                let copy = quote!(iter().cloned().collect::<Vec<#element>>());
+                return if self.is_option {
+                    quote!(#accessor.as_ref().map(|s| s.#copy).as_deref().into())
+                } else {
+                    quote!((&*#accessor.#copy).into())
+                };
+            }
+            Some(CollectionType::Map(..)) => {
+                let ffi_map = self.ffi_type(attributes.expose_as_ident(), Context::Return);
+                return quote!(#accessor.iter().collect::<#ffi_map>());
+            }
+            None => (),
+        }
         if self.is_vec {
             if self.is_option {
                 quote!(#accessor.as_deref().into())
@@ -251,7 +626,9 @@ impl TypeFFI {
             }
         } else {
             match &self.native_type {
-                TypeIdentifier::Boxed(_) => {
+                TypeIdentifier::Boxed(inner) => {
+                    let handle = opaque_handle();
+                    let inner = attributes.expose_as_ident().unwrap_or(inner);
                     if self.is_option {
                         let mut return_value = quote!(f.clone());
                         // If this field is exposed as a different type for FFI, convert it back to
@@ -261,7 +638,7 @@ impl TypeFFI {
                         }
                         quote!(
                             #accessor.as_ref().map_or(ptr::null(), |f| {
-                                Box::into_raw(Box::new(#return_value))
+                                <#inner as #handle>::into_handle(#return_value)
                             })
                         )
                     } else {
@@ -271,10 +648,10 @@ impl TypeFFI {
                         if attributes.expose_as.is_some() {
                             return_value = quote!(#return_value.into());
                         }
-                        quote!(Box::into_raw(Box::new(#return_value)))
+                        quote!(<#inner as #handle>::into_handle(#return_value))
                     }
                 }
-                TypeIdentifier::DateTime => {
+                TypeIdentifier::DateTime | TypeIdentifier::UtcDateTime => {
                     if self.is_option {
                         quote!(
                             #accessor.as_ref().map_or(ptr::null(), |f| {
@@ -285,21 +662,58 @@ impl TypeFFI {
                         quote!(Box::into_raw(Box::new((&#accessor).into())))
                     }
                 }
+                TypeIdentifier::OffsetDateTime | TypeIdentifier::Date | TypeIdentifier::Time => {
+                    if self.is_option {
+                        quote!(
+                            #accessor.as_ref().map_or(std::ptr::null(), |f| {
+                                ffi_common::core::datetime::option_CalendarComponents_init(
+                                    true,
+                                    f.into(),
+                                )
+                            })
+                        )
+                    } else {
+                        quote!((&#accessor).into())
+                    }
+                }
+                TypeIdentifier::Duration(_) => {
+                    let interval = quote!(ffi_common::core::datetime::TimeInterval::time_interval);
+                    if self.is_option {
+                        quote!(
+                            #accessor.as_ref().map_or(std::ptr::null(), |d| {
+                                ffi_common::core::option_f64_init(true, #interval(d))
+                            })
+                        )
+                    } else {
+                        quote!(#interval(&#accessor))
+                    }
+                }
                 TypeIdentifier::Raw(inner) => {
                     if self.is_option {
                         let boxer =
                             format_ident!("option_{}_init", inner.to_string().to_snake_case());
                         quote!(
                             match #accessor {
-                                Some(data) => #boxer(true, data),
-                                None => #boxer(false, #inner::default()),
+                                Some(data) => #boxer(true, data.into()),
+                                None => #boxer(false, #inner::default().into()),
                             }
                         )
                     } else {
                         quote!(#accessor.clone().into())
                     }
                 }
-                TypeIdentifier::String | TypeIdentifier::Uuid => {
+                TypeIdentifier::Uuid => {
+                    if self.is_option {
+                        quote!(
+                            #accessor.as_ref().map_or(std::ptr::null(), |id| {
+                                ffi_common::core::option_Uuid_init(true, (*id).into())
+                            })
+                        )
+                    } else {
+                        quote!(#accessor.into())
+                    }
+                }
+                TypeIdentifier::String => {
                     if self.is_option {
                         quote!(
                             #accessor.as_ref().map_or(ptr::null(), |s| {
@@ -310,7 +724,176 @@ impl TypeFFI {
                         quote!(ffi_common::core::ffi_string!(#accessor.to_string()))
                     }
                 }
+                TypeIdentifier::Tuple(elements) => {
+                    let (tuple, fields) = tuple_struct(elements.len());
+                    let conversions = elements.iter().enumerate().map(|(index, element)| {
+                        let index = syn::Index::from(index);
+                        element.rust_to_ffi_value(&quote!((t.#index)), &FieldAttributes::default())
+                    });
+                    quote!({
+                        let t = &#accessor;
+                        #tuple { #(#fields: #conversions),* }
+                    })
+                }
+            }
+        }
+    }
+
+    /// Generates a `TokenStream` for releasing a value of the FFI type represented by `self` (i.e.,
+    /// a value produced by `rust_to_ffi_value`) when it won't be handed to the consumer.
+    ///
+    /// `expose_as` should be the same type (if any) that was used to produce the value.
+    ///
+    #[must_use]
+    pub fn free_ffi_value(&self, accessor: &TokenStream, expose_as: Option<&Ident>) -> TokenStream {
+        if let Some(wrapped) = &self.wrapped {
+            let free = wrapped.free_ffi_value(&quote!(e), expose_as);
+            return if self.is_vec {
+                quote!(for e in Vec::from(#accessor) { #free })
+            } else {
+                quote!(if let Some(e) = #accessor.into_option() { #free })
+            };
+        }
+        // Inline arrays are passed by value, and only contain raw values, so there's nothing to
+        // release. Other arrays are released just like a `Vec`.
+        if self.is_inline_array() {
+            return quote!();
+        }
+        if let Some((keys, values)) = self.map_arrays() {
+            let free_keys = keys.free_ffi_value(&quote!(map.keys), None);
+            let free_values = values.free_ffi_value(&quote!(map.values), expose_as);
+            return quote! {
+                let map = #accessor;
+                #free_keys
+                #free_values
+            };
+        }
+        match &self.native_type {
+            TypeIdentifier::String if self.is_vec => {
+                quote!(ffi_common::core::string::ffi_array_string_free(#accessor);)
+            }
+            TypeIdentifier::String => {
+                quote!(ffi_common::core::string::free_rust_string(#accessor);)
+            }
+            TypeIdentifier::Uuid if self.is_vec => {
+                quote!(drop(Option::<Vec<Uuid>>::from(#accessor));)
+            }
+            TypeIdentifier::Uuid if self.is_option => {
+                quote!(ffi_common::core::option_Uuid_free(#accessor);)
+            }
+            TypeIdentifier::DateTime | TypeIdentifier::UtcDateTime if self.is_vec => {
+                quote!(drop(Option::<Vec<TimeStamp>>::from(#accessor));)
+            }
+            TypeIdentifier::DateTime | TypeIdentifier::UtcDateTime => {
+                quote!(ffi_common::core::datetime::time_stamp_free(#accessor as *mut TimeStamp);)
+            }
+            TypeIdentifier::Boxed(inner) | TypeIdentifier::Raw(inner) if self.is_vec => {
+                let inner = expose_as.unwrap_or(inner);
+                quote!(drop(Option::<Vec<#inner>>::from(#accessor));)
+            }
+            TypeIdentifier::Boxed(inner) => {
+                let inner = expose_as.unwrap_or(inner);
+                let handle = opaque_handle();
+                quote!(<#inner as #handle>::release_handle(#accessor);)
+            }
+            TypeIdentifier::Raw(inner) if self.is_option => {
+                let inner = expose_as.unwrap_or(inner);
+                let free_fn = format_ident!("option_{}_free", inner.to_string());
+                quote!(#free_fn(#accessor);)
+            }
+            TypeIdentifier::OffsetDateTime | TypeIdentifier::Date | TypeIdentifier::Time
+                if self.is_vec =>
+            {
+                quote!(drop(Option::<Vec<ffi_common::core::datetime::CalendarComponents>>::from(#accessor));)
+            }
+            TypeIdentifier::OffsetDateTime | TypeIdentifier::Date | TypeIdentifier::Time
+                if self.is_option =>
+            {
+                quote!(ffi_common::core::datetime::option_CalendarComponents_free(#accessor);)
+            }
+            TypeIdentifier::Duration(_) if self.is_vec => {
+                quote!(drop(Option::<Vec<f64>>::from(#accessor));)
+            }
+            TypeIdentifier::Duration(_) if self.is_option => {
+                quote!(ffi_common::core::option_f64_free(#accessor);)
+            }
+            TypeIdentifier::Tuple(elements) => {
+                let (tuple, fields) = tuple_struct(elements.len());
+                let bindings = tuple_bindings(elements.len());
+                let frees = elements
+                    .iter()
+                    .zip(&bindings)
+                    .map(|(element, binding)| element.free_ffi_value(&quote!(#binding), None));
+                quote! {
+                    #[allow(unused_variables)]
+                    let #tuple { #(#fields: #bindings),* } = #accessor;
+                    #(#frees)*
+                }
             }
+            // Raw values (and the value types that UUIDs, dates, times, and durations are exposed
+            // as) are passed by value, so there's nothing to release.
+            TypeIdentifier::Raw(_)
+            | TypeIdentifier::Uuid
+            | TypeIdentifier::OffsetDateTime
+            | TypeIdentifier::Date
+            | TypeIdentifier::Time
+            | TypeIdentifier::Duration(_) => quote!(),
+        }
+    }
+
+    /// Returns the FFI value to return in place of a value of this type when there isn't one (for
+    /// instance, when a function that returns a `Result` fails): the default value for raw types,
+    /// UUIDs, dates, times, durations, arrays, sets, maps, nested options, tuples, and fixed-size
+    /// arrays that are passed by value, or a null pointer for everything else.
+    ///
+    pub(crate) fn ffi_placeholder_value(&self) -> TokenStream {
+        if let Some(wrapped) = &self.wrapped {
+            return if self.is_vec {
+                quote!(ffi_common::core::nested::FFINestedArray::default())
+            } else {
+                let placeholder = wrapped.ffi_placeholder_value();
+                quote!(ffi_common::core::nested::FFINestedOption::none(#placeholder))
+            };
+        }
+        if let Some(len) = self.array_len {
+            if !self.is_inline_array() {
+                return Self {
+                    array_len: None,
+                    ..self.clone()
+                }
+                .ffi_placeholder_value();
+            }
+            let placeholder = self.array_element().ffi_placeholder_value();
+            let len = Literal::usize_unsuffixed(len);
+            return quote!(
+                ffi_common::core::fixed_array::FFIFixedArray::from([(); #len].map(|()| #placeholder))
+            );
+        }
+        // Arrays, sets, and maps are exposed by value, so use an empty one (or, for an optional
+        // array, one with a null pointer).
+        if self.is_vec || self.collection_type.is_some() {
+            let native_type = self.native_type();
+            return self.rust_to_ffi_value(
+                &quote!(<#native_type>::default()),
+                &FieldAttributes::default(),
+            );
+        }
+        if self.is_option {
+            return quote!(std::ptr::null());
+        }
+        match &self.native_type {
+            TypeIdentifier::OffsetDateTime | TypeIdentifier::Date | TypeIdentifier::Time => {
+                quote!(ffi_common::core::datetime::CalendarComponents::default())
+            }
+            TypeIdentifier::Duration(_) => quote!(0.0),
+            TypeIdentifier::Uuid => quote!(ffi_common::core::FFIUuid::default()),
+            TypeIdentifier::Raw(inner) => quote!(<#inner>::default().into()),
+            TypeIdentifier::Tuple(elements) => {
+                let (tuple, fields) = tuple_struct(elements.len());
+                let placeholders = elements.iter().map(Self::ffi_placeholder_value);
+                quote!(#tuple { #(#fields: #placeholders),* })
+            }
+            _ => quote!(std::ptr::null()),
         }
     }
 
@@ -320,15 +903,25 @@ impl TypeFFI {
     pub(crate) const fn argument_borrows_supported(&self) -> bool {
         // If it's not a borrow, or we're dealing with a collection type, it's not borrowed. We'll
         // probably add support for collection types eventually, but it's not essential yet.
-        if !self.is_borrow || self.is_vec {
+        if !self.is_borrow || self.is_vec || self.is_composite() {
             return false;
         }
         match self.native_type {
             // Boxed and DateTime types are always exposed via pointer, so they're fine to borrow.
-            TypeIdentifier::Boxed(_) | TypeIdentifier::DateTime => true,
-            // Raw types are passed through the FFI by value; there's no reason to borrow them.
-            // String/Uuid are certainly worth supporting borrows for, but we're not there yet.
-            TypeIdentifier::Raw(_) | TypeIdentifier::String | TypeIdentifier::Uuid => false,
+            TypeIdentifier::Boxed(_) | TypeIdentifier::DateTime | TypeIdentifier::UtcDateTime => {
+                true
+            }
+            // Raw types (and UUIDs, dates, times, and durations) are passed through the FFI by
+            // value; there's no reason to borrow them. Strings are certainly worth supporting
+            // borrows for, but we're not there yet.
+            TypeIdentifier::Raw(_)
+            | TypeIdentifier::OffsetDateTime
+            | TypeIdentifier::Date
+            | TypeIdentifier::Time
+            | TypeIdentifier::Duration(_)
+            | TypeIdentifier::String
+            | TypeIdentifier::Uuid
+            | TypeIdentifier::Tuple(_) => false,
         }
     }
 
@@ -349,31 +942,55 @@ impl TypeFFI {
             Context::Argument => quote!(*mut),
             Context::Return => quote!(*const),
         };
+        if let Some(wrapped) = &self.wrapped {
+            // Elements are always exposed as they would be returned, regardless of `context`, so
+            // that each kind of nested type only has one FFI type.
+            let element = wrapped.ffi_type(expose_as, Context::Return);
+            return if self.is_vec {
+                quote!(ffi_common::core::nested::FFINestedArray<#element>)
+            } else {
+                quote!(ffi_common::core::nested::FFINestedOption<#element>)
+            };
+        }
+        if let Some((keys, values)) = self.map_arrays() {
+            let keys = keys.ffi_array_type(None);
+            let values = values.ffi_array_type(expose_as);
+            return quote!(ffi_common::core::collections::FFIMap<#keys, #values>);
+        }
+        if let Some(len) = self.array_len.filter(|_| self.is_inline_array()) {
+            let element = self.array_element().ffi_type(expose_as, context);
+            let len = Literal::usize_unsuffixed(len);
+            return quote!(ffi_common::core::fixed_array::FFIFixedArray<#element, #len>);
+        }
+        if self.is_vec {
+            return self.ffi_array_type(expose_as);
+        }
         match &self.native_type {
             TypeIdentifier::Boxed(inner) => {
                 // Replace the inner type for FFI with whatever the `expose_as` told us to use.
                 let inner = expose_as.unwrap_or(inner);
-                if self.is_vec {
-                    let ident = format_ident!("FFIArray{}", inner);
-                    quote!(#ident)
+                quote!(#ptr_type #inner)
+            }
+            TypeIdentifier::DateTime | TypeIdentifier::UtcDateTime => quote!(#ptr_type TimeStamp),
+            TypeIdentifier::OffsetDateTime | TypeIdentifier::Date | TypeIdentifier::Time => {
+                let components = quote!(ffi_common::core::datetime::CalendarComponents);
+                if self.is_option {
+                    quote!(#ptr_type #components)
                 } else {
-                    quote!(#ptr_type #inner)
+                    components
                 }
             }
-            TypeIdentifier::DateTime => {
-                if self.is_vec {
-                    quote!(FFIArrayTimeStamp)
+            TypeIdentifier::Duration(_) => {
+                if self.is_option {
+                    quote!(#ptr_type f64)
                 } else {
-                    quote!(#ptr_type TimeStamp)
+                    quote!(f64)
                 }
             }
             TypeIdentifier::Raw(inner) => {
                 // Replace the inner type for FFI with whatever the `expose_as` told us to use.
-                let inner = expose_as.unwrap_or(inner);
-                if self.is_vec {
-                    let ident = format_ident!("FFIArray{}", inner.to_string());
-                    quote!(#ident)
-                } else if self.is_option {
+                let inner = expose_as.map_or_else(|| raw_ffi_type(inner), Clone::clone);
+                if self.is_option {
                     // Option types are behind a pointer, because embedding structs in parameter
                     // lists caused issues for Swift.
                     quote!(#ptr_type #inner)
@@ -381,18 +998,112 @@ impl TypeFFI {
                     quote!(#inner)
                 }
             }
-            TypeIdentifier::String | TypeIdentifier::Uuid => {
-                if self.is_vec {
-                    quote!(FFIArrayString)
+            TypeIdentifier::Uuid => {
+                let uuid = quote!(ffi_common::core::FFIUuid);
+                if self.is_option {
+                    quote!(#ptr_type #uuid)
                 } else {
-                    // Strings are always `*const`, unlike other reference types, because they're
-                    // managed by the caller (since there's already language support for
-                    // initializing a `String` from a view of foreign data, we don't need the
-                    // preliminary step of allocating the data in Rust, which means we don't need to
-                    // reclaim that memory here).
-                    quote!(*const std::os::raw::c_char)
+                    uuid
                 }
             }
+            TypeIdentifier::String => {
+                // Strings are always `*const`, unlike other reference types, because they're
+                // managed by the caller (since there's already language support for
+                // initializing a `String` from a view of foreign data, we don't need the
+                // preliminary step of allocating the data in Rust, which means we don't need to
+                // reclaim that memory here).
+                quote!(*const std::os::raw::c_char)
+            }
+            TypeIdentifier::Tuple(elements) => {
+                // Like nested types, elements are always exposed as they would be returned.
+                let (tuple, _) = tuple_struct(elements.len());
+                let elements = elements.iter().map(|e| e.ffi_type(None, Context::Return));
+                quote!(#tuple<#(#elements),*>)
+            }
+        }
+    }
+
+    /// Returns the `FFIArray*` type for a collection of `native_type`, regardless of whether `self`
+    /// is a `Vec`.
+    ///
+    #[must_use]
+    pub fn ffi_array_type(&self, expose_as: Option<&Ident>) -> TokenStream {
+        match &self.native_type {
+            TypeIdentifier::Boxed(inner) | TypeIdentifier::Raw(inner) => {
+                // Replace the inner type for FFI with whatever the `expose_as` told us to use.
+                let inner = expose_as.unwrap_or(inner);
+                let ident = format_ident!("FFIArray{}", inner.to_string());
+                quote!(#ident)
+            }
+            TypeIdentifier::DateTime | TypeIdentifier::UtcDateTime => quote!(FFIArrayTimeStamp),
+            TypeIdentifier::OffsetDateTime | TypeIdentifier::Date | TypeIdentifier::Time => {
+                quote!(ffi_common::core::datetime::FFIArrayCalendarComponents)
+            }
+            TypeIdentifier::Duration(_) => quote!(ffi_common::core::FFIArrayf64),
+            TypeIdentifier::Uuid => quote!(ffi_common::core::FFIArrayUuid),
+            TypeIdentifier::String => quote!(FFIArrayString),
+            // `Vec`s of tuples are exposed as nested types (see `wrapped`).
+            TypeIdentifier::Tuple(_) => {
+                proc_macro_error::abort_call_site!("Tuples can't be exposed in an `FFIArray`.")
+            }
+        }
+    }
+
+    /// The type that a batched function or getter returns one value of this type in for each
+    /// receiver (without the `Result`, since errors are returned separately): an `FFIArray*` of
+    /// them, or, if this is an `Option`, a `Vec`, or a tuple, an `FFINestedArray` of them.
+    ///
+    #[must_use]
+    pub fn batch_results(&self) -> Self {
+        let element = Self {
+            is_result: false,
+            is_borrow: false,
+            ..self.clone()
+        };
+        if element.is_option || element.is_vec || element.is_tuple() {
+            Self {
+                is_option: false,
+                is_vec: true,
+                wrapped: Some(Box::new(element.clone())),
+                ..element
+            }
+        } else {
+            Self {
+                is_vec: true,
+                ..element
+            }
+        }
+    }
+
+    /// If this type can be borrowed as a contiguous slice of raw values (i.e., it's a `Vec` (not a
+    /// set) of a type that's exposed as a raw value), returns the `FFISlice*` type for exposing a view into
+    /// it. Otherwise, returns `None`.
+    ///
+    #[must_use]
+    pub fn borrowed_slice_type(&self) -> Option<TokenStream> {
+        match &self.native_type {
+            // Types that are converted to a different FFI type can't be viewed in place.
+            TypeIdentifier::Raw(inner)
+                if self.is_vec && !self.is_composite() && &raw_ffi_type(inner) == inner =>
+            {
+                let ident = format_ident!("FFISlice{}", inner);
+                Some(quote!(#ident))
+            }
+            _ => None,
+        }
+    }
+
+    /// If this type can be borrowed as a pointer into the instance that owns it (i.e., it's a
+    /// single value (or an optional value) of another FFI type), returns the pointer type for
+    /// exposing it. Otherwise, returns `None`.
+    ///
+    #[must_use]
+    pub fn borrowed_pointer_type(&self) -> Option<TokenStream> {
+        match &self.native_type {
+            TypeIdentifier::Boxed(inner) if !self.is_vec && !self.is_composite() => {
+                Some(quote!(*const #inner))
+            }
+            _ => None,
         }
     }
 
@@ -400,22 +1111,30 @@ impl TypeFFI {
     ///
     #[must_use]
     pub fn consumer_type(&self, expose_as: Option<&Ident>) -> String {
-        let mut t = expose_as.map_or_else(
-            {
-                || match &self.native_type {
-                    TypeIdentifier::Boxed(inner) => inner.to_string(),
-                    TypeIdentifier::Raw(inner) => {
-                        crate::consumer_type_for(&inner.to_string(), false)
-                    }
-                    TypeIdentifier::DateTime => "Date".to_string(),
-                    TypeIdentifier::String | TypeIdentifier::Uuid => "String".to_string(),
-                }
-            },
+        if let Some(wrapped) = &self.wrapped {
+            let t = wrapped.consumer_type(expose_as);
+            return match (self.is_option, self.is_vec) {
+                (false, true) => format!("[{t}]"),
+                (true, true) => format!("[{t}]?"),
+                _ => format!("{t}?"),
+            };
+        }
+        // Transparent types are declared as an alias of the type they wrap.
+        let transparent = self
+            .transparent
+            .as_ref()
+            .and_then(|transparent| transparent.segments.last())
+            .map(|segment| &segment.ident);
+        let mut t = expose_as.or(transparent).map_or_else(
+            || self.native_type.consumer_type(),
             std::string::ToString::to_string,
         );
 
-        if self.is_vec {
-            t = format!("[{}]", t);
+        match &self.collection_type {
+            Some(CollectionType::Set(_)) => t = format!("Set<{t}>"),
+            Some(CollectionType::Map(_, key)) => t = format!("[{}: {t}]", key.consumer_type()),
+            None if self.is_vec => t = format!("[{t}]"),
+            None => (),
         }
 
         if self.is_option {
@@ -430,15 +1149,48 @@ impl TypeFFI {
     ///
     #[must_use]
     pub fn native_type(&self) -> TokenStream {
-        let t = match &self.native_type {
-            TypeIdentifier::Boxed(inner) | TypeIdentifier::Raw(inner) => quote!(#inner),
-            TypeIdentifier::DateTime => quote!(datetime),
-            TypeIdentifier::String => quote!(String),
-            TypeIdentifier::Uuid => quote!(Uuid),
-        };
-        let t = if self.is_vec {
+        if let Some(wrapped) = &self.wrapped {
+            // Like sets and maps, borrowed nested types are converted to owned ones, then passed by
+            // reference.
+            let t = wrapped.native_type();
+            return match (self.is_option, self.is_vec) {
+                (false, true) => quote!(Vec::<#t>),
+                (true, true) => quote!(Option::<Vec::<#t>>),
+                _ => quote!(Option::<#t>),
+            };
+        }
+        let t = self.native_type.native_type();
+        if self.is_tuple() {
+            // Borrowed tuples are converted to owned ones, too.
+            return t;
+        }
+        // Borrowed transparent types are converted from the type they wrap, so they're owned, too.
+        let t = self
+            .transparent
+            .as_ref()
+            .map_or(t, |transparent| quote!(#transparent));
+        // So are borrowed smart pointers.
+        let t = self.pointer.as_ref().map_or(t, |pointer| {
+            let pointer = pointer_path(pointer);
+            let target = self.pointer_target();
+            quote!(#pointer<#target>)
+        });
+        let t = if let Some(collection_type) = &self.collection_type {
+            // Borrowed sets and maps are converted to owned ones, then passed by reference.
+            match collection_type {
+                CollectionType::Set(set) => quote!(#set::<#t>),
+                CollectionType::Map(map, key) => {
+                    let key = key.native_type();
+                    quote!(#map::<#key, #t>)
+                }
+            }
+        } else if let Some(len) = self.array_len {
+            // Like sets and maps, borrowed arrays are converted to owned ones.
+            let len = Literal::usize_unsuffixed(len);
+            quote!([#t; #len])
+        } else if self.is_vec {
             quote!(Vec::<#t>)
-        } else if self.is_borrow {
+        } else if self.is_borrow && self.pointer.is_none() && self.transparent.is_none() {
             if self.native_type == TypeIdentifier::String {
                 quote!(&str)
             } else {
@@ -454,6 +1206,608 @@ impl TypeFFI {
         }
     }
 
+    /// If this is a map, returns `Vec`s of its key and value types, which describe the arrays that
+    /// its keys and values are exposed in. Otherwise, returns `None`.
+    ///
+    fn map_arrays(&self) -> Option<(Self, Self)> {
+        match &self.collection_type {
+            Some(CollectionType::Map(_, key)) => Some((
+                Self::from((key.clone(), WrappingType::Vec)),
+                Self::from((self.native_type.clone(), WrappingType::Vec)),
+            )),
+            _ => None,
+        }
+    }
+
+    /// True if this is a set, a map, a nested `Option` or `Vec` (see `wrapped`), a tuple, a
+    /// fixed-size array, or a smart pointer, which are converted to and from their FFI types as a
+    /// whole, rather than by the rules for a single `Option` or `Vec`.
+    ///
+    #[must_use]
+    pub const fn is_composite(&self) -> bool {
+        self.collection_type.is_some()
+            || self.wrapped.is_some()
+            || self.array_len.is_some()
+            || self.pointer.is_some()
+            || self.transparent.is_some()
+            || matches!(self.native_type, TypeIdentifier::Tuple(_))
+    }
+
+    /// True if this is a tuple (as opposed to an `Option` or a `Vec` of tuples).
+    ///
+    pub(crate) const fn is_tuple(&self) -> bool {
+        self.wrapped.is_none() && matches!(self.native_type, TypeIdentifier::Tuple(_))
+    }
+
+    /// True if converting an argument of this type into Rust can fail, because it contains a
+    /// fixed-size array that's exposed as an `FFIArray*` (whose length is only checked at runtime),
+    /// or a date or time (including a map key) that may not be valid (see
+    /// `ffi_core::datetime::CheckedFromFFI`). `argument_into_rust` uses `?` for these, so callers
+    /// need to wrap its conversion with `catch_argument_errors`.
+    ///
+    pub(crate) fn has_fallible_argument(&self) -> bool {
+        (self.array_len.is_some() && !self.is_inline_array())
+            || self.native_type.has_checked_conversion()
+            || matches!(&self.collection_type, Some(CollectionType::Map(_, key)) if key.has_checked_conversion())
+            || self
+                .wrapped
+                .iter()
+                .any(|wrapped| wrapped.has_fallible_argument())
+            || matches!(&self.native_type, TypeIdentifier::Tuple(elements) if elements.iter().any(Self::has_fallible_argument))
+    }
+
+    /// True if this is a (non-optional) fixed-size array of a raw type, which is exposed inline in
+    /// an `FFIFixedArray`.
+    ///
+    pub(crate) const fn is_inline_array(&self) -> bool {
+        self.array_len.is_some()
+            && !self.is_option
+            && matches!(self.native_type, TypeIdentifier::Raw(_))
+    }
+
+    /// True if the consumer converts this type with the `init(cloning:)` and `intoNative()` that
+    /// are declared on its FFI type (see `consumer_generic_conformances`), rather than with the
+    /// `clone()` and `fromRust` that are declared on its consumer type. Tuples can't be extended in
+    /// Swift, and the length of an inline array isn't part of its consumer type.
+    ///
+    pub(crate) const fn converts_with_ffi_type(&self) -> bool {
+        self.is_tuple() || self.is_inline_array()
+    }
+
+    /// The type of a single element of this fixed-size array.
+    ///
+    fn array_element(&self) -> Self {
+        Self {
+            is_option: false,
+            is_vec: false,
+            array_len: None,
+            is_result: false,
+            is_cow: false,
+            is_borrow: false,
+            ..self.clone()
+        }
+    }
+
+    /// Like `argument_into_rust`, for a fixed-size array. Inline arrays are converted element by
+    /// element, and other arrays are converted to a `Vec` first, then checked for the right length.
+    ///
+    fn array_argument_into_rust(
+        &self,
+        field_name: &TokenStream,
+        has_custom_implementation: bool,
+    ) -> TokenStream {
+        let into_array = quote!(ffi_common::core::fixed_array::into_fixed_array);
+        if self.is_inline_array() {
+            let element = self.array_element();
+            let conversion = element.argument_into_rust(&quote!(e), has_custom_implementation);
+            if !element.has_fallible_argument() {
+                return quote!(#field_name.elements.map(|e| #conversion));
+            }
+            // Collect the elements' results, so that the first failure is propagated with `?`.
+            let element_type = element.native_type();
+            return quote!(#into_array(
+                #field_name
+                    .elements
+                    .into_iter()
+                    .map(|e| -> Result<#element_type, String> { Ok(#conversion) })
+                    .collect::<Result<Vec<_>, _>>()?
+            )?);
+        }
+        let elements = self.vec_argument_into_rust(field_name);
+        if self.is_option {
+            quote!(#elements.map(#into_array).transpose()?)
+        } else {
+            quote!(#into_array(#elements)?)
+        }
+    }
+
+    /// Like `argument_into_rust`, for the `FFIArray*` of a `Vec`, a set, or a fixed-size array
+    /// that isn't inline (or an `Option` of one), which is converted into a `Vec` (or an `Option`
+    /// of one).
+    ///
+    fn vec_argument_into_rust(&self, field_name: &TokenStream) -> TokenStream {
+        let element = self.native_type.native_type();
+        if self.native_type.has_checked_conversion() {
+            let datetime = quote!(ffi_common::core::datetime);
+            return if self.is_option {
+                quote!(#datetime::checked_option_vec_from_ffi::<#element, _>(#field_name)?)
+            } else {
+                quote!(#datetime::checked_vec_from_ffi::<#element, _>(#field_name)?)
+            };
+        }
+        // All FFIArrayT types have a `From<FFIArrayT> for Vec<T>` impl, so we can treat them all
+        // the same for the sake of native Rust assignment.
+        if self.is_option {
+            quote!(Option::<Vec<#element>>::from(#field_name))
+        } else {
+            quote!(Vec::<#element>::from(#field_name))
+        }
+    }
+
+    /// Like `argument_into_rust`, for a map. `FFIMap` can be converted into any supported map type
+    /// with `into()`, unless its keys or values need to be checked (see `vec_argument_into_rust`),
+    /// in which case both of its arrays are converted (and released) before either result is
+    /// checked.
+    ///
+    fn map_argument_into_rust(&self, field_name: &TokenStream) -> TokenStream {
+        let (keys, values) = self.map_arrays().expect_or_abort("Not a map.");
+        if !keys.native_type.has_checked_conversion()
+            && !values.native_type.has_checked_conversion()
+        {
+            return quote!(#field_name.into());
+        }
+        let into_vec = |array: &Self, accessor: TokenStream| {
+            let element = array.native_type.native_type();
+            if array.native_type.has_checked_conversion() {
+                quote!(ffi_common::core::datetime::checked_vec_from_ffi::<#element, _>(#accessor))
+            } else {
+                quote!(Ok::<_, String>(Vec::<#element>::from(#accessor)))
+            }
+        };
+        let keys = into_vec(&keys, quote!(map.keys));
+        let values = into_vec(&values, quote!(map.values));
+        quote!({
+            let map = #field_name;
+            let (keys, values) = (#keys, #values);
+            keys?.into_iter().zip(values?).collect()
+        })
+    }
+
+    /// Like `rust_to_ffi_value`, for a fixed-size array. Arrays that aren't inline are exposed as a
+    /// slice of their elements, just like a `Vec`.
+    ///
+    fn array_to_ffi_value(
+        &self,
+        accessor: &TokenStream,
+        attributes: &FieldAttributes,
+    ) -> TokenStream {
+        if self.is_inline_array() {
+            let conversion = self
+                .array_element()
+                .rust_to_ffi_value(&quote!(e), attributes);
+            return quote!(
+                ffi_common::core::fixed_array::FFIFixedArray::from(
+                    #accessor.clone().map(|e| #conversion)
+                )
+            );
+        }
+        if self.is_option {
+            quote!(#accessor.as_ref().map(|a| &a[..]).into())
+        } else {
+            quote!((&#accessor[..]).into())
+        }
+    }
+
+    /// True if this is a smart pointer, or contains one (as the element of a nested type or a
+    /// tuple).
+    ///
+    pub(crate) fn has_pointer(&self) -> bool {
+        self.pointer.is_some()
+            || self.wrapped.iter().any(|wrapped| wrapped.has_pointer())
+            || matches!(&self.native_type, TypeIdentifier::Tuple(elements) if elements.iter().any(Self::has_pointer))
+    }
+
+    /// The type that this smart pointer points to: `native_type`, or `str` for a string.
+    ///
+    fn pointer_target(&self) -> TokenStream {
+        match &self.native_type {
+            TypeIdentifier::String => quote!(str),
+            other => other.native_type(),
+        }
+    }
+
+    /// If `self` is an `Arc` (or an `Option` or a `Vec` of them) around an opaque type, returns that
+    /// type. Those instances are exposed through `OpaqueHandle::share_handle`, so that an
+    /// `ffi(shared)` type can share an instance with the consumer instead of copying it.
+    ///
+    fn shared_pointee(&self) -> Option<&Ident> {
+        match (&self.native_type, &self.pointer) {
+            (TypeIdentifier::Boxed(inner), Some(pointer)) if pointer == "Arc" => Some(inner),
+            _ => None,
+        }
+    }
+
+    /// Like `argument_into_rust`, for a smart pointer (or an `Option` or a `Vec` of them). The
+    /// pointee is converted just like it would be on its own, then moved into a new pointer.
+    ///
+    fn pointer_argument_into_rust(&self, field_name: &TokenStream) -> TokenStream {
+        let pointer = pointer_path(
+            self.pointer
+                .as_ref()
+                .expect_or_abort("Not a smart pointer."),
+        );
+        let handle = opaque_handle();
+        // A borrowed pointer is still owned by the caller, so convert a new reference to it (or, if
+        // its type isn't shared, a copy of it) instead.
+        let field_name = &match &self.native_type {
+            TypeIdentifier::Boxed(inner) if self.is_borrow && !self.is_option && !self.is_vec => {
+                quote!(<#inner as #handle>::retain_handle(#field_name))
+            }
+            _ => field_name.clone(),
+        };
+        if let Some(inner) = self.shared_pointee().filter(|_| !self.is_vec) {
+            let arc = quote!(<#inner as #handle>::arc_from_handle(#field_name));
+            return if self.is_option {
+                quote!(if #field_name.is_null() { None } else { Some(#arc) })
+            } else {
+                arc
+            };
+        }
+        if self.is_vec {
+            let elements = self.vec_argument_into_rust(field_name);
+            let collect = quote!(into_iter().map(#pointer::from).collect());
+            return if self.is_option {
+                quote!(#elements.map(|v| v.#collect))
+            } else {
+                quote!(#elements.#collect)
+            };
+        }
+        let conversion = Self {
+            pointer: None,
+            is_borrow: false,
+            ..self.clone()
+        }
+        .argument_into_rust(field_name, false);
+        if self.is_option {
+            quote!((#conversion).map(#pointer::from))
+        } else {
+            quote!(#pointer::from(#conversion))
+        }
+    }
+
+    /// Like `rust_to_ffi_value`, for a smart pointer (or an `Option` or a `Vec` of them). The
+    /// pointee is converted just like it would be on its own; `Vec`s are copied into a `Vec` of
+    /// pointees first, so that they can be exposed as an `FFIArray*`.
+    ///
+    fn pointer_to_ffi_value(
+        &self,
+        accessor: &TokenStream,
+        attributes: &FieldAttributes,
+    ) -> TokenStream {
+        let target = self.pointer_target();
+        if let Some(inner) = self.shared_pointee() {
+            let handle = opaque_handle();
+            let share = quote!(<#inner as #handle>::share_handle);
+            return if self.is_vec {
+                let array = self.ffi_array_type(None);
+                let share_all = quote!(#array::from(v.iter().map(#share).collect::<Vec<_>>()));
+                if self.is_option {
+                    quote!(#accessor.as_ref().map_or(#array::from(None), |v| #share_all))
+                } else {
+                    quote!({
+                        let v = &#accessor;
+                        #share_all
+                    })
+                }
+            } else if self.is_option {
+                quote!(#accessor.as_ref().map_or(std::ptr::null(), #share))
+            } else {
+                quote!(#share(&#accessor))
+            };
+        }
+        if self.is_vec {
+            let element = self.native_type.native_type();
+            let copy = quote!(iter().map(|p| <#target>::to_owned(p)).collect::<Vec<#element>>());
+            return if self.is_option {
+                quote!(#accessor.as_ref().map(|v| v.#copy).as_deref().into())
+            } else {
+                quote!((&*#accessor.#copy).into())
+            };
+        }
+        let conversion = Self {
+            pointer: None,
+            is_option: false,
+            ..self.clone()
+        }
+        .rust_to_ffi_value(&quote!((*p)), attributes);
+        // Smart pointers to FFI types and strings are always exposed as pointers, so `None` is
+        // exposed as a null pointer.
+        if self.is_option {
+            quote!(#accessor.as_deref().map_or(std::ptr::null(), |p: &#target| #conversion))
+        } else {
+            quote!({
+                let p: &#target = &#accessor;
+                #conversion
+            })
+        }
+    }
+
+    /// Like `argument_into_rust`, for a transparent type (or an `Option` or a `Vec` of them). The
+    /// argument is converted into the type that's wrapped, then wrapped in the transparent type.
+    ///
+    fn transparent_argument_into_rust(&self, field_name: &TokenStream) -> TokenStream {
+        let transparent = self
+            .transparent
+            .as_ref()
+            .expect_or_abort("Not a transparent type.");
+        let from_inner =
+            quote!(<#transparent as ffi_common::core::transparent::Transparent>::from_inner);
+        let inner = Self {
+            transparent: None,
+            is_borrow: false,
+            ..self.clone()
+        };
+        let conversion = inner.argument_into_rust(field_name, false);
+        let inner_type = inner.native_type();
+        let wrap = quote!(into_iter().map(#from_inner).collect());
+        match (self.is_option, self.is_vec) {
+            (true, true) => quote!({
+                let inner: #inner_type = #conversion;
+                inner.map(|v| v.#wrap)
+            }),
+            (false, true) => quote!({
+                let inner: #inner_type = #conversion;
+                inner.#wrap
+            }),
+            (true, false) => quote!({
+                let inner: #inner_type = #conversion;
+                inner.map(#from_inner)
+            }),
+            (false, false) => quote!(#from_inner(#conversion)),
+        }
+    }
+
+    /// Like `rust_to_ffi_value`, for a transparent type (or an `Option` or a `Vec` of them). The
+    /// wrapped value is copied out of the transparent type, then converted just like it would be on
+    /// its own.
+    ///
+    fn transparent_to_ffi_value(
+        &self,
+        accessor: &TokenStream,
+        attributes: &FieldAttributes,
+    ) -> TokenStream {
+        let transparent = self
+            .transparent
+            .as_ref()
+            .expect_or_abort("Not a transparent type.");
+        let inner = Self {
+            transparent: None,
+            is_borrow: false,
+            ..self.clone()
+        };
+        let inner_type = inner.native_type();
+        let unwrap = |e: TokenStream| quote!(Clone::clone(<#transparent as ffi_common::core::transparent::Transparent>::inner(#e)));
+        let unwrap_element = unwrap(quote!(e));
+        let unwrapped = match (self.is_option, self.is_vec) {
+            (true, true) => {
+                quote!(#accessor.as_ref().map(|v| v.iter().map(|e| #unwrap_element).collect()))
+            }
+            (false, true) => quote!(#accessor.iter().map(|e| #unwrap_element).collect()),
+            (true, false) => quote!(#accessor.as_ref().map(|e| #unwrap_element)),
+            (false, false) => unwrap(quote!(&#accessor)),
+        };
+        let conversion = inner.rust_to_ffi_value(&quote!(inner), attributes);
+        quote!({
+            let inner: #inner_type = #unwrapped;
+            #conversion
+        })
+    }
+
+    /// Generates a `TokenStream` for turning `binding`, the FFI value of an element of a nested
+    /// type or a tuple of this type, into a native Rust type.
+    ///
+    fn element_into_rust(&self, binding: &Ident, has_custom_implementation: bool) -> TokenStream {
+        // Elements are exposed as they would be returned (see `ffi_type`), so pointers need to be
+        // made mutable again before we take ownership of them.
+        let cast = if self.ffi_type(None, Context::Argument).to_string()
+            == self.ffi_type(None, Context::Return).to_string()
+        {
+            quote!()
+        } else {
+            quote!(let #binding = #binding.cast_mut();)
+        };
+        let conversion = self.argument_into_rust(&quote!(#binding), has_custom_implementation);
+        quote!(#cast #conversion)
+    }
+
+    /// Like `argument_into_rust`, for an `Option` or a `Vec` of `wrapped`. Each element is converted
+    /// with `wrapped`'s own conversion.
+    ///
+    fn nested_argument_into_rust(
+        &self,
+        wrapped: &Self,
+        field_name: &TokenStream,
+        has_custom_implementation: bool,
+    ) -> TokenStream {
+        let element_type = wrapped.native_type();
+        let conversion = wrapped.element_into_rust(&format_ident!("e"), has_custom_implementation);
+        if wrapped.has_fallible_argument() {
+            // Collect the elements' results, so that the first failure is propagated with `?`.
+            let convert = quote!(|e| -> Result<#element_type, String> { Ok({ #conversion }) });
+            return match (self.is_option, self.is_vec) {
+                (false, true) => quote!(
+                    Vec::from(#field_name).into_iter().map(#convert).collect::<Result<Vec<_>, _>>()?
+                ),
+                (true, true) => quote!(
+                    Option::<Vec<_>>::from(#field_name)
+                        .map(|v| v.into_iter().map(#convert).collect::<Result<Vec<_>, _>>())
+                        .transpose()?
+                ),
+                _ => quote!(#field_name.into_option().map(#convert).transpose()?),
+            };
+        }
+        let convert = quote!(|e| -> #element_type { #conversion });
+        match (self.is_option, self.is_vec) {
+            (false, true) => {
+                quote!(Vec::from(#field_name).into_iter().map(#convert).collect::<Vec<_>>())
+            }
+            (true, true) => quote!(
+                Option::<Vec<_>>::from(#field_name)
+                    .map(|v| v.into_iter().map(#convert).collect::<Vec<_>>())
+            ),
+            _ => quote!(#field_name.into_option().map(#convert)),
+        }
+    }
+
+    /// Like `rust_to_ffi_value`, for an `Option` or a `Vec` of `wrapped`. Each element is converted
+    /// with `wrapped`'s own conversion.
+    ///
+    fn nested_rust_to_ffi_value(
+        &self,
+        wrapped: &Self,
+        accessor: &TokenStream,
+        attributes: &FieldAttributes,
+    ) -> TokenStream {
+        let element_type = wrapped.ffi_type(attributes.expose_as_ident(), Context::Return);
+        let conversion = wrapped.rust_to_ffi_value(&quote!((*e)), attributes);
+        let convert = quote!(|e| -> #element_type { #conversion });
+        match (self.is_option, self.is_vec) {
+            (false, true) => quote!(
+                ffi_common::core::nested::FFINestedArray::from(
+                    #accessor.iter().map(#convert).collect::<Vec<_>>()
+                )
+            ),
+            (true, true) => quote!(
+                ffi_common::core::nested::FFINestedArray::from(
+                    #accessor.as_ref().map(|v| v.iter().map(#convert).collect::<Vec<_>>())
+                )
+            ),
+            _ => {
+                let placeholder = wrapped.ffi_placeholder_value();
+                quote!(
+                    #accessor.as_ref().map(#convert).map_or_else(
+                        || ffi_common::core::nested::FFINestedOption::none(#placeholder),
+                        ffi_common::core::nested::FFINestedOption::some,
+                    )
+                )
+            }
+        }
+    }
+
+    /// Returns the consumer name and the consumer's conformance for each of the generic FFI types
+    /// (`FFIMap`, `FFINestedArray`, and `FFINestedOption`) that this type is exposed with.
+    ///
+    /// `cbindgen` generates a separate C struct for each variant of a generic type, and the same
+    /// variant may be used by any number of types, so each conformance should be written to its
+    /// own file, named after the C struct (see `consumer::write_generic_conformances`).
+    ///
+    #[must_use]
+    pub fn consumer_generic_conformances(
+        &self,
+        expose_as: Option<&Ident>,
+    ) -> Vec<(String, String)> {
+        if self.map_arrays().is_some() {
+            let name = consumer_ffi_type_name(&self.ffi_type(expose_as, Context::Return));
+            let conformance = format!("extension {name}: FFIMapData {{}}");
+            return vec![(name, conformance)];
+        }
+        if let TypeIdentifier::Tuple(elements) = &self.native_type {
+            if self.wrapped.is_none() {
+                return tuple_conformances(&self.ffi_type(None, Context::Return), elements);
+            }
+        }
+        if let Some(len) = self.array_len.filter(|_| self.is_inline_array()) {
+            let ffi_type = self.ffi_type(expose_as, Context::Return);
+            return vec![fixed_array_conformance(
+                &ffi_type,
+                &self.array_element(),
+                len,
+                expose_as,
+            )];
+        }
+        let Some(wrapped) = &self.wrapped else {
+            return Vec::new();
+        };
+        let name = consumer_ffi_type_name(&self.ffi_type(expose_as, Context::Return));
+        let element = wrapped.consumer_type(expose_as);
+        let element_clone = |value: &str| wrapped.consumer_element_clone(value, expose_as);
+        let element_from_rust = |value: &str| wrapped.consumer_from_rust(value, expose_as);
+        let conformance = if self.is_vec {
+            format!(
+                "// MARK: - FFINestedArray
+extension {name}: FFINestedArrayData {{}}
+
+public extension Array where Element == {element} {{
+    func clone() -> {name} {{
+        {name}.from(map {{ {clone} }})
+    }}
+
+    func borrowReference() -> {name} {{
+        clone()
+    }}
+
+    static func fromRust(_ foreignObject: {name}) -> Self {{
+        foreignObject.takeElements().map {{ {from_rust} }}
+    }}
+}}
+
+public extension Optional where Wrapped == [{element}] {{
+    func clone() -> {name} {{
+        switch self {{
+        case let .some(value):
+            return value.clone()
+        case .none:
+            return {name}()
+        }}
+    }}
+
+    func borrowReference() -> {name} {{
+        clone()
+    }}
+
+    static func fromRust(_ foreignObject: {name}) -> Self {{
+        guard foreignObject.ptr != nil else {{ return .none }}
+        return Wrapped.fromRust(foreignObject)
+    }}
+}}",
+                name = name,
+                element = element,
+                clone = element_clone("$0"),
+                from_rust = element_from_rust("$0"),
+            )
+        } else {
+            format!(
+                "// MARK: - FFINestedOption
+public extension Optional where Wrapped == {element} {{
+    func clone() -> {name} {{
+        switch self {{
+        case let .some(value):
+            return {name}(is_some: true, value: {clone})
+        case .none:
+            return {name}()
+        }}
+    }}
+
+    func borrowReference() -> {name} {{
+        clone()
+    }}
+
+    static func fromRust(_ foreignObject: {name}) -> Self {{
+        guard foreignObject.is_some else {{ return .none }}
+        return .some({from_rust})
+    }}
+}}",
+                name = name,
+                element = element,
+                clone = element_clone("value"),
+                from_rust = element_from_rust("foreignObject.value"),
+            )
+        };
+        let mut conformances = wrapped.consumer_generic_conformances(expose_as);
+        conformances.push((name, conformance));
+        conformances
+    }
+
     /// Returns a tuple containing 1) the conversion operation to perform for this type on the
     /// consumer side, 2) a closing parenthesis, and 3) the signature for returning this type from a
     /// consumer function.
@@ -464,15 +1818,296 @@ impl TypeFFI {
             (
                 "handle(result: ".to_string(),
                 ")".to_string(),
-                format!("-> Result<{}, RustError>", ty),
+                format!("-> Result<{ty}, RustError>"),
+            )
+        } else if self.converts_with_ffi_type() {
+            (
+                String::new(),
+                ".intoNative()".to_string(),
+                format!("-> {ty}"),
             )
         } else {
             (
-                format!("{}.fromRust(", ty),
+                format!("{}.fromRust(", self.consumer_type_expression(None)),
                 ")".to_string(),
-                format!("-> {}", ty),
+                format!("-> {ty}"),
+            )
+        }
+    }
+
+    /// Returns this type's consumer type in a form that can be used in an expression (like
+    /// `Foo.fromRust(...)`).
+    ///
+    fn consumer_type_expression(&self, expose_as: Option<&Ident>) -> String {
+        let ty = self.consumer_type(expose_as);
+        // Swift doesn't accept the shorthand for an optional tuple (like `(Int, Int)?`) here.
+        match ty.strip_suffix('?') {
+            Some(tuple) if tuple.starts_with('(') => format!("Optional<{tuple}>"),
+            _ => ty,
+        }
+    }
+
+    /// Returns the consumer's expression for taking ownership of `foreign_value` (a value of this
+    /// type's FFI type) as this type's consumer type.
+    ///
+    pub(crate) fn consumer_from_rust(
+        &self,
+        foreign_value: &str,
+        expose_as: Option<&Ident>,
+    ) -> String {
+        if self.converts_with_ffi_type() {
+            format!("{foreign_value}.intoNative()")
+        } else {
+            format!(
+                "{}.fromRust({foreign_value})",
+                self.consumer_type_expression(expose_as)
+            )
+        }
+    }
+
+    /// Returns the consumer's expression for reading `foreign_value` (a value of this type's FFI
+    /// type that's owned by `arena`, a `RustArena`) as this type's consumer type, without taking
+    /// ownership of it. Strings are copied, and instances of other FFI types borrow their pointer
+    /// and retain the arena.
+    ///
+    /// Only strings and other FFI types (and `Option`s and `Vec`s of them) can be read from an
+    /// arena. For any other type, this returns `None`, and the consumer should take ownership of
+    /// the value as usual.
+    ///
+    pub(crate) fn consumer_from_arena(
+        &self,
+        foreign_value: &str,
+        arena: &str,
+        expose_as: Option<&Ident>,
+    ) -> Option<String> {
+        if !matches!(
+            self.native_type,
+            TypeIdentifier::String | TypeIdentifier::Boxed(_)
+        ) || self.is_composite()
+            || self.is_result
+            || self.is_borrow
+        {
+            return None;
+        }
+        Some(format!(
+            "{}.fromRust(inArena: {}, arena: {})",
+            self.consumer_type_expression(expose_as),
+            foreign_value,
+            arena
+        ))
+    }
+
+    /// Returns the consumer's expression for cloning `value` (a value of this type's consumer type)
+    /// into this type's FFI type.
+    ///
+    pub(crate) fn consumer_clone(&self, value: &str, expose_as: Option<&Ident>) -> String {
+        if self.converts_with_ffi_type() {
+            format!(
+                "{}(cloning: {})",
+                consumer_ffi_type_name(&self.ffi_type(expose_as, Context::Return)),
+                value
             )
+        } else {
+            format!("{value}.clone()")
+        }
+    }
+
+    /// Like `consumer_clone`, for an element of a nested type or a tuple, which is exposed as it
+    /// would be returned (see `ffi_type`).
+    ///
+    fn consumer_element_clone(&self, value: &str, expose_as: Option<&Ident>) -> String {
+        match self.native_type {
+            // Optional values that are passed by value are boxed in a mutable pointer when they're
+            // cloned, but elements are behind a `const` pointer.
+            TypeIdentifier::Raw(_)
+            | TypeIdentifier::Uuid
+            | TypeIdentifier::OffsetDateTime
+            | TypeIdentifier::Date
+            | TypeIdentifier::Time
+            | TypeIdentifier::Duration(_)
+                if self.is_option && !self.is_vec && self.wrapped.is_none() =>
+            {
+                format!("UnsafePointer({value}.clone())")
+            }
+            _ => self.consumer_clone(value, expose_as),
+        }
+    }
+}
+
+/// Returns the `FFITuple*` struct (see `ffi_core::tuple`) for a tuple of `len` elements, along with
+/// the names of its fields.
+///
+fn tuple_struct(len: usize) -> (TokenStream, Vec<Ident>) {
+    let ident = format_ident!("FFITuple{}", len);
+    (
+        quote!(ffi_common::core::tuple::#ident),
+        (0..len).map(|index| format_ident!("_{}", index)).collect(),
+    )
+}
+
+/// Returns the names to bind each of the `len` elements of an `FFITuple*` to when destructuring it.
+///
+fn tuple_bindings(len: usize) -> Vec<Ident> {
+    (0..len).map(|index| format_ident!("e{}", index)).collect()
+}
+
+/// Returns the consumer conformances for a tuple of `elements`, which is exposed as `ffi_type`,
+/// along with the conformances for any generic FFI types that its elements are exposed with (see
+/// `TypeFFI::consumer_generic_conformances`).
+///
+fn tuple_conformances(ffi_type: &TokenStream, elements: &[TypeFFI]) -> Vec<(String, String)> {
+    let name = consumer_ffi_type_name(ffi_type);
+    let consumer_type = TypeIdentifier::Tuple(elements.to_vec()).consumer_type();
+    let clones = elements
+        .iter()
+        .enumerate()
+        .map(|(index, e)| {
+            format!(
+                "_{index}: {}",
+                e.consumer_element_clone(&format!("tuple.{index}"), None)
+            )
+        })
+        .collect::<Vec<String>>()
+        .join(", ");
+    let conversions = elements
+        .iter()
+        .enumerate()
+        .map(|(index, e)| e.consumer_from_rust(&format!("_{index}"), None))
+        .collect::<Vec<String>>()
+        .join(", ");
+    let conformance = format!(
+        "// MARK: - {tuple}
+public extension {name} {{
+    /// Clones each element of `tuple` into a new `{name}`.
+    init(cloning tuple: {consumer_type}) {{
+        self.init({clones})
+    }}
+
+    /// Takes ownership of each element of this tuple.
+    func intoNative() -> {consumer_type} {{
+        ({conversions})
+    }}
+}}",
+        tuple = format_ident!("FFITuple{}", elements.len()),
+        name = name,
+        consumer_type = consumer_type,
+        clones = clones,
+        conversions = conversions,
+    );
+    let mut conformances: Vec<(String, String)> = elements
+        .iter()
+        .flat_map(|e| e.consumer_generic_conformances(None))
+        .collect();
+    conformances.push((name, conformance));
+    conformances
+}
+
+/// Returns the consumer name and the consumer's conformance for an inline array of `len` `element`s,
+/// which is exposed as `ffi_type`. Swift imports the array inside of the C struct as a tuple, so
+/// the conformance copies the elements in and out of it as raw bytes.
+///
+fn fixed_array_conformance(
+    ffi_type: &TokenStream,
+    element: &TypeFFI,
+    len: usize,
+    expose_as: Option<&Ident>,
+) -> (String, String) {
+    let name = consumer_ffi_type_name(ffi_type);
+    let element_type = element.consumer_type(expose_as);
+    let element_ffi_type = crate::consumer_type_for(
+        &element.ffi_type(expose_as, Context::Return).to_string(),
+        false,
+    );
+    let conformance = format!(
+        "// MARK: - FFIFixedArray
+public extension {name} {{
+    /// Clones each element of `array`, which must have exactly {len} elements, into a new `{name}`.
+    init(cloning array: [{element_type}]) {{
+        precondition(array.count == {len}, \"Expected {len} elements, but found \\(array.count).\")
+        var elements = Self().elements
+        withUnsafeMutableBytes(of: &elements) {{ buffer in
+            array.map {{ {clone} }}.withUnsafeBytes {{ buffer.copyMemory(from: $0) }}
+        }}
+        self.init(elements: elements)
+    }}
+
+    /// Takes ownership of each element of this array.
+    func intoNative() -> [{element_type}] {{
+        withUnsafeBytes(of: elements) {{ buffer in
+            buffer.bindMemory(to: {element_ffi_type}.self).map {{ {from_rust} }}
+        }}
+    }}
+}}",
+        name = name,
+        len = len,
+        element_type = element_type,
+        element_ffi_type = element_ffi_type,
+        clone = element.consumer_clone("$0", expose_as),
+        from_rust = element.consumer_from_rust("$0", expose_as),
+    );
+    (name, conformance)
+}
+
+/// Returns the name of the C struct that `cbindgen` generates for `ffi_type`. C doesn't have
+/// generics, so each variant of a generic type (like `FFIMap<FFIArrayString, FFIArrayi32>`) gets its
+/// own struct, named after its type arguments (like `FFIMap_FFIArrayString__FFIArrayi32`).
+///
+fn consumer_ffi_type_name(ffi_type: &TokenStream) -> String {
+    let ty = syn::parse2::<Type>(ffi_type.clone())
+        .unwrap_or_else(|err| abort!(ffi_type.span(), "Unable to parse FFI type: {}", err));
+    mangled_type_name(&ty, true)
+}
+
+/// Mangles `ty` the way `cbindgen` does (see `consumer_ffi_type_name`). Type arguments are
+/// separated by underscores, and the closing angle bracket is left off of the `last` type argument
+/// of a type.
+///
+fn mangled_type_name(ty: &Type, last: bool) -> String {
+    match ty {
+        Type::Path(path) => {
+            // The type may be qualified, but C only knows its name.
+            let segment = path
+                .path
+                .segments
+                .last()
+                .expect_or_abort("Type path has zero segments.");
+            let mut name = segment.ident.to_string();
+            if let PathArguments::AngleBracketed(generic) = &segment.arguments {
+                let arguments: Vec<&GenericArgument> = generic
+                    .args
+                    .iter()
+                    .filter(|argument| {
+                        matches!(
+                            argument,
+                            GenericArgument::Type(_) | GenericArgument::Const(_)
+                        )
+                    })
+                    .collect();
+                for (index, argument) in arguments.iter().enumerate() {
+                    name.push_str(if index == 0 { "_" } else { "__" });
+                    match argument {
+                        GenericArgument::Type(ty) => name
+                            .push_str(&mangled_type_name(ty, last && index == arguments.len() - 1)),
+                        // Constant arguments (like the length of an `FFIFixedArray`) are mangled
+                        // as they're written.
+                        other => name.push_str(&quote!(#other).to_string()),
+                    }
+                }
+                if !last {
+                    name.push_str("___");
+                }
+            }
+            name
+        }
+        Type::Ptr(ptr) => {
+            let prefix = if ptr.mutability.is_some() {
+                "____"
+            } else {
+                "_____"
+            };
+            format!("{}{}", prefix, mangled_type_name(&ptr.elem, last))
         }
+        _ => abort!(ty.span(), "Unsupported FFI type."),
     }
 }
 
@@ -484,7 +2119,7 @@ impl From<(&Type, bool)> for TypeFFI {
         let (ffi_type, required) = value;
         match ffi_type {
             Type::Path(type_path) => {
-                let (ident, wrapping_type) = parsing::separate_wrapping_type_from_inner_type(
+                let (segment, wrapping_type) = parsing::separate_wrapping_type_from_inner_segment(
                     type_path
                         .path
                         .segments
@@ -492,7 +2127,7 @@ impl From<(&Type, bool)> for TypeFFI {
                         .expect_or_abort("msg")
                         .clone(),
                 );
-                Self::from((TypeIdentifier::from(ident), wrapping_type))
+                Self::from((TypeIdentifier::from(&segment), wrapping_type))
             }
             Type::Ptr(p) => {
                 if let Type::Path(path) = p.elem.as_ref() {
@@ -518,9 +2153,14 @@ impl From<(&Type, bool)> for TypeFFI {
                         native_type,
                         is_option,
                         is_vec: false,
+                        array_len: None,
                         is_result: false,
                         is_cow: false,
+                        pointer: None,
+                        transparent: None,
                         is_borrow: false,
+                        collection_type: None,
+                        wrapped: None,
                     }
                 } else {
                     abort!(p.span(), "No segment in {:?}?", p);
@@ -0,0 +1,110 @@
+import Foundation
+
+// MARK: - Arenas
+/// Owns an `FFIArena`, and frees it (along with every value that was returned into it) when it's
+/// deinitialized.
+///
+/// Values are read out of the arena with `fromRust(inArena:arena:)`, which doesn't take ownership
+/// of them: strings are copied, and instances of other FFI types borrow their pointer and retain
+/// the arena, so the arena stays alive for as long as any of them do.
+public final class RustArena {
+    private static let currentKey = "ffi_common.RustArena.current"
+
+    public let pointer: OpaquePointer?
+
+    public init() {
+        pointer = ffi_arena_init()
+    }
+
+    deinit {
+        ffi_arena_free(pointer)
+    }
+
+    /// The innermost arena opened with `withRustArena` on the current thread, if any. While there
+    /// is one, generated getters and methods that have an `_in_arena` variant call it, and read
+    /// their results from this arena.
+    public internal(set) static var current: RustArena? {
+        get { Thread.current.threadDictionary[currentKey] as? RustArena }
+        set { Thread.current.threadDictionary[currentKey] = newValue }
+    }
+}
+
+/// Opens a `RustArena` for the duration of `body`, and makes it the current arena on this thread.
+///
+/// While `body` runs, generated getters and methods with an `_in_arena` variant return their
+/// results into the arena, so they're released with a single `ffi_arena_free` instead of piece by
+/// piece. The arena can also be passed to `_in_arena` FFI functions directly, as `arena.pointer`.
+public func withRustArena<R>(_ body: (RustArena) throws -> R) rethrows -> R {
+    let arena = RustArena()
+    let previous = RustArena.current
+    RustArena.current = arena
+    defer { RustArena.current = previous }
+    return try body(arena)
+}
+
+// MARK: - Arena conversions
+public extension BorrowableNativeData {
+    /// Wraps `foreignObject`, which is owned by `arena`, without taking ownership of it.
+    static func fromRust(inArena foreignObject: ForeignType, arena: RustArena) -> Self {
+        fromRust(borrowing: foreignObject, owner: arena)
+    }
+}
+
+public extension Optional where Wrapped: BorrowableNativeData, Wrapped.ForeignType == OpaquePointer? {
+    static func fromRust(inArena foreignObject: OpaquePointer?, arena: RustArena) -> Self {
+        fromRust(borrowing: foreignObject, owner: arena)
+    }
+}
+
+public extension Collection where
+    Element: BorrowableNativeData & NativeArrayData,
+    Element.FFIArrayType.Value == Element.ForeignType
+{
+    /// Wraps each element of `foreignObject`, which is owned by `arena`, without taking ownership
+    /// of the array or its elements.
+    static func fromRust(inArena foreignObject: Element.FFIArrayType, arena: RustArena) -> [Element] {
+        (0..<Int(foreignObject.len)).map { Element.fromRust(inArena: foreignObject.ptr[$0], arena: arena) }
+    }
+}
+
+public extension Optional where
+    Wrapped: Collection,
+    Wrapped.Element: BorrowableNativeData & NativeArrayData,
+    Wrapped.Element.FFIArrayType.Value == Wrapped.Element.ForeignType
+{
+    static func fromRust(inArena foreignObject: Wrapped.Element.FFIArrayType, arena: RustArena) -> [Wrapped.Element]? {
+        guard foreignObject.ptr != nil else { return .none }
+        return Wrapped.fromRust(inArena: foreignObject, arena: arena)
+    }
+}
+
+public extension String {
+    /// Copies `foreignObject`, which is owned by an arena, without freeing it.
+    static func fromRust(inArena foreignObject: UnsafePointer<CChar>?, arena: RustArena) -> String {
+        String(cString: foreignObject!)
+    }
+}
+
+public extension Optional where Wrapped == String {
+    static func fromRust(inArena foreignObject: UnsafePointer<CChar>?, arena: RustArena) -> Self {
+        guard let foreignObject = foreignObject else {
+            return nil
+        }
+        return String(cString: foreignObject)
+    }
+}
+
+public extension Array where Element == String {
+    static func fromRust(inArena foreignObject: FFIArrayString, arena: RustArena) -> Self {
+        (0..<Int(foreignObject.len)).map { String(cString: foreignObject.ptr[$0]!) }
+    }
+}
+
+public extension Optional where Wrapped == [String] {
+    static func fromRust(inArena foreignObject: FFIArrayString, arena: RustArena) -> Self {
+        guard foreignObject.ptr != nil else {
+            return .none
+        }
+        return Wrapped.fromRust(inArena: foreignObject, arena: arena)
+    }
+}
@@ -0,0 +1,59 @@
+import Foundation
+
+// MARK: - Batched calls
+/// Calls `body` with a buffer of borrowed pointers to the Rust instances behind each element of
+/// `collection`, and the number of pointers in that buffer. This is for calling batched FFI
+/// functions, which read from many instances in a single call.
+///
+/// `collection` is kept alive until `body` returns, so the pointers stay valid for the duration of
+/// the call.
+public func withBatchReceivers<C: Collection, R>(
+    _ collection: C,
+    _ body: (UnsafePointer<OpaquePointer?>?, UInt) throws -> R
+) rethrows -> R where C.Element: NativeData, C.Element.ForeignType == OpaquePointer? {
+    let receivers = collection.map { $0.borrowReference() }
+    return try withExtendedLifetime(collection) {
+        try receivers.withUnsafeBufferPointer { buffer in
+            try body(buffer.baseAddress, UInt(buffer.count))
+        }
+    }
+}
+
+/// Pairs each of the `values` returned by a batched FFI function with the error at the same index
+/// in `errors` (where a null error means that the call succeeded for that element), converting
+/// successful values with `element`. Both arrays are freed afterward.
+///
+/// `element` takes ownership of each value it's called with, but it's only called for successes.
+public func batchResults<Values: FFIArray, T>(
+    values: Values,
+    errors: FFIArrayString,
+    element: (Values.Value) -> T
+) -> [Result<T, RustError>] {
+    let results = (0..<Int(values.len)).map { index -> Result<T, RustError> in
+        if let message = errors.ptr?[index] {
+            return .failure(RustError(errorMessage: String(cString: message)))
+        }
+        return .success(element(values.ptr[index]))
+    }
+    Values.free(values)
+    FFIArrayString.free(errors)
+    return results
+}
+
+/// Like `batchResults(values:errors:element:)`, for the `FFINestedArray_*` that a batched FFI
+/// function returns when its results are `Optional`s or `Array`s. The values of failures are empty
+/// (a null pointer or an empty array), so there's nothing to release for them.
+public func batchResults<Values: FFINestedArrayData, T>(
+    values: Values,
+    errors: FFIArrayString,
+    element: (Values.Element) -> T
+) -> [Result<T, RustError>] {
+    let results = values.takeElements().enumerated().map { index, value -> Result<T, RustError> in
+        if let message = errors.ptr?[index] {
+            return .failure(RustError(errorMessage: String(cString: message)))
+        }
+        return .success(element(value))
+    }
+    FFIArrayString.free(errors)
+    return results
+}
@@ -0,0 +1,79 @@
+import Foundation
+
+// MARK: - FFI Protocols
+/// Describes the structure of all `FFISlice*` types, which are non-owning views into memory that
+/// belongs to a Rust instance. `FFISlice*` types need to implement this.
+public protocol FFISlice: FFIData {
+    var ptr: UnsafePointer<Value>! { get }
+    var len: UInt { get }
+}
+
+// MARK: - Borrowed buffers
+/// A read-only, zero-copy view into a buffer owned by a Rust instance.
+///
+/// `owner` (the native wrapper around the Rust instance) is retained for as long as this buffer is
+/// alive, so the memory it points to stays valid. Copy the elements (e.g. with `Array(buffer)`) if
+/// you need them to outlive the owner.
+public struct BorrowedBuffer<Element>: RandomAccessCollection {
+    public let buffer: UnsafeBufferPointer<Element>
+    private let owner: AnyObject
+
+    public init<Slice: FFISlice>(borrowing slice: Slice, owner: AnyObject) where Slice.Value == Element {
+        buffer = UnsafeBufferPointer(start: slice.ptr, count: Int(slice.len))
+        self.owner = owner
+    }
+
+    /// Returns `nil` if `slice` represents the `None` variant of an `Option<Vec<T>>`.
+    public init?<Slice: FFISlice>(borrowingIfPresent slice: Slice, owner: AnyObject) where Slice.Value == Element {
+        guard slice.ptr != nil else {
+            return nil
+        }
+        self.init(borrowing: slice, owner: owner)
+    }
+
+    public var startIndex: Int {
+        buffer.startIndex
+    }
+
+    public var endIndex: Int {
+        buffer.endIndex
+    }
+
+    public subscript(position: Int) -> Element {
+        buffer[position]
+    }
+
+    /// Calls `body` with the underlying buffer, guaranteeing that the owner is alive for the
+    /// duration of the call.
+    public func withUnsafeBufferPointer<R>(_ body: (UnsafeBufferPointer<Element>) throws -> R) rethrows -> R {
+        try withExtendedLifetime(owner) {
+            try body(buffer)
+        }
+    }
+}
+
+public extension Data {
+    /// Wraps the bytes in `slice` without copying them. `owner` is retained until this `Data` (and
+    /// any copies sharing its storage) are released.
+    init<Slice: FFISlice>(borrowing slice: Slice, owner: AnyObject) where Slice.Value == UInt8 {
+        guard let ptr = slice.ptr, slice.len > 0 else {
+            self.init()
+            return
+        }
+        self.init(
+            bytesNoCopy: UnsafeMutableRawPointer(mutating: ptr),
+            count: Int(slice.len),
+            deallocator: .custom { _, _ in
+                withExtendedLifetime(owner) {}
+            }
+        )
+    }
+
+    /// Returns `nil` if `slice` represents the `None` variant of an `Option<Vec<u8>>`.
+    init?<Slice: FFISlice>(borrowingIfPresent slice: Slice, owner: AnyObject) where Slice.Value == UInt8 {
+        guard slice.ptr != nil else {
+            return nil
+        }
+        self.init(borrowing: slice, owner: owner)
+    }
+}
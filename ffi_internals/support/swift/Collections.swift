@@ -0,0 +1,57 @@
+/// Describes the structure of the `FFIMap_*` types, which are the C representations of Rust's
+/// generic `FFIMap<Keys, Values>` for each combination of `FFIArray*` types that it's used with.
+/// Each of those types declares its conformance in its own file.
+public protocol FFIMapData {
+    associatedtype Keys: FFIArray
+    associatedtype Values: FFIArray
+
+    var keys: Keys { get }
+    var values: Values { get }
+
+    init(keys: Keys, values: Values)
+}
+
+/// This lets us do `[NativeKey: NativeValue].fromRust(instanceOfFFIMap)` and
+/// `[instanceOfNativeKey: instanceOfNativeValue].clone()` whenever the keys and values are
+/// `NativeArrayData`.
+public extension Dictionary where
+    Key: NativeArrayData,
+    Key.FFIArrayType.Value == Key.ForeignType,
+    Value: NativeArrayData,
+    Value.FFIArrayType.Value == Value.ForeignType
+{
+    func clone<Map: FFIMapData>() -> Map where Map.Keys == Key.FFIArrayType, Map.Values == Value.FFIArrayType {
+        // `keys` and `values` are always in the same order.
+        Map(keys: keys.clone(), values: values.clone())
+    }
+
+    func borrowReference<Map: FFIMapData>() -> Map where Map.Keys == Key.FFIArrayType, Map.Values == Value.FFIArrayType {
+        clone()
+    }
+
+    static func fromRust<Map: FFIMapData>(_ foreignObject: Map) -> Self where Map.Keys == Key.FFIArrayType, Map.Values == Value.FFIArrayType {
+        let keys = [Key].fromRust(foreignObject.keys)
+        let values = [Value].fromRust(foreignObject.values)
+        return Dictionary(uniqueKeysWithValues: zip(keys, values))
+    }
+}
+
+/// Sets are exposed as arrays, so `clone()` is provided by the `Collection` extension; this lets us
+/// do `Set<NativeFoo>.fromRust(instanceOfFFIArrayFoo)`.
+public extension Set where Element: NativeArrayData, Element.FFIArrayType.Value == Element.ForeignType {
+    static func fromRust(_ foreignObject: Element.FFIArrayType) -> Self {
+        Self([Element].fromRust(foreignObject))
+    }
+}
+
+/// This lets us do `Set<NativeFoo>?.fromRust(instanceOfFFIArrayFooThatMightBeNil)`.
+public extension Optional {
+    static func fromRust<Element>(_ foreignObject: Element.FFIArrayType) -> Self where
+        Wrapped == Set<Element>,
+        Element: NativeArrayData,
+        Element.FFIArrayType.Value == Element.ForeignType
+    {
+        guard foreignObject.ptr != nil else { return .none }
+        return Wrapped.fromRust(foreignObject)
+    }
+}
@@ -2,7 +2,7 @@ extension FFIArrayTimeStamp: FFIArray {
     public typealias Value = OpaquePointer?
 
     public static func from(ptr: UnsafePointer<Value>?, len: Int) -> Self {
-        ffi_array_TimeStamp_init(ptr, len)
+        ffi_array_TimeStamp_init_consuming(ptr, len)
     }
 
     public static func free(_ array: Self) {
@@ -0,0 +1,99 @@
+extension FFIArrayCalendarComponents: FFIArray {
+    public typealias Value = CalendarComponents
+
+    public static func from(ptr: UnsafePointer<Value>?, len: Int) -> Self {
+        ffi_array_CalendarComponents_init(ptr, len)
+    }
+
+    public static func free(_ array: Self) {
+        ffi_array_CalendarComponents_free(array)
+    }
+}
+
+public extension Optional where Wrapped == DateComponents {
+    func clone() -> UnsafeMutablePointer<CalendarComponents>? {
+        switch self {
+        case let .some(value):
+            return UnsafeMutablePointer(mutating: option_CalendarComponents_init(true, value.clone()))
+        case .none:
+            return nil
+        }
+    }
+
+    func borrowReference() -> UnsafeMutablePointer<CalendarComponents>? {
+        return clone()
+    }
+
+    static func fromRust(_ ptr: UnsafePointer<CalendarComponents>?) -> Self {
+        guard let ptr = ptr else {
+            return .none
+        }
+        let value = Wrapped.fromRust(ptr.pointee)
+        free(ptr)
+        return value
+    }
+
+    static func free(_ option: UnsafePointer<CalendarComponents>?) {
+        option_CalendarComponents_free(option)
+    }
+}
+
+/// `DateComponents` are exchanged with Rust's `NaiveDate`, `NaiveTime`, and `DateTime<FixedOffset>`
+/// as `CalendarComponents`. Only the date (year, month, and day), time (hour, minute, second, and
+/// nanosecond), and time zone are carried across; the date is only sent if all of its components
+/// are set, and a missing time component is treated as zero.
+extension DateComponents: NativeData {
+    public typealias ForeignType = CalendarComponents
+
+    private static let calendar = Calendar(identifier: .gregorian)
+
+    public func clone() -> ForeignType {
+        let hasDate = year != nil && month != nil && day != nil
+        let hasTime = hour != nil || minute != nil || second != nil || nanosecond != nil
+        // The offset depends on the date for time zones with daylight saving time.
+        let offset = timeZone.map { timeZone in
+            timeZone.secondsFromGMT(for: Self.calendar.date(from: self) ?? Date())
+        }
+        return CalendarComponents(
+            has_date: hasDate,
+            year: Int32(year ?? 0),
+            month: UInt32(month ?? 0),
+            day: UInt32(day ?? 0),
+            has_time: hasTime,
+            hour: UInt32(hour ?? 0),
+            minute: UInt32(minute ?? 0),
+            second: UInt32(second ?? 0),
+            nanosecond: UInt32(nanosecond ?? 0),
+            has_offset: offset != nil,
+            offset_secs: Int32(offset ?? 0)
+        )
+    }
+
+    public func borrowReference() -> ForeignType {
+        return clone()
+    }
+
+    public static func fromRust(_ foreignObject: ForeignType) -> Self {
+        var components = DateComponents()
+        components.calendar = calendar
+        if foreignObject.has_date {
+            components.year = Int(foreignObject.year)
+            components.month = Int(foreignObject.month)
+            components.day = Int(foreignObject.day)
+        }
+        if foreignObject.has_time {
+            components.hour = Int(foreignObject.hour)
+            components.minute = Int(foreignObject.minute)
+            components.second = Int(foreignObject.second)
+            components.nanosecond = Int(foreignObject.nanosecond)
+        }
+        if foreignObject.has_offset {
+            components.timeZone = TimeZone(secondsFromGMT: Int(foreignObject.offset_secs))
+        }
+        return components
+    }
+}
+
+extension DateComponents: NativeArrayData {
+    public typealias FFIArrayType = FFIArrayCalendarComponents
+}
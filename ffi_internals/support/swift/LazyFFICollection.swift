@@ -0,0 +1,36 @@
+import Foundation
+
+// MARK: - Lazy collections
+/// A read-only collection of elements that belong to a Rust instance, which only fetches (and
+/// clones) an element when it's accessed.
+///
+/// `owner` (the native wrapper around the Rust instance) is retained for as long as this collection
+/// is alive, so `element` can safely read from it. Elements aren't cached, so accessing the same
+/// index twice will fetch it twice; copy the elements (e.g. with `Array(collection)`) if you need
+/// to access them repeatedly.
+public struct LazyFFICollection<Element>: RandomAccessCollection {
+    public let count: Int
+    private let owner: AnyObject
+    private let element: (UInt) -> Element
+
+    public init(count: UInt, owner: AnyObject, element: @escaping (UInt) -> Element) {
+        self.count = Int(count)
+        self.owner = owner
+        self.element = element
+    }
+
+    public var startIndex: Int {
+        0
+    }
+
+    public var endIndex: Int {
+        count
+    }
+
+    public subscript(position: Int) -> Element {
+        precondition(indices.contains(position), "Index out of range")
+        return withExtendedLifetime(owner) {
+            element(UInt(position))
+        }
+    }
+}
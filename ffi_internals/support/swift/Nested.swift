@@ -0,0 +1,45 @@
+/// Describes the structure of the `FFINestedArray_*` types, which are the C representations of
+/// Rust's generic `FFINestedArray<T>` for each array or optional FFI type that it's used with (e.g.
+/// `FFINestedArray_FFIArrayf64` for a `Vec<Vec<f64>>`). Each of those types declares its
+/// conformance in its own file.
+public protocol FFINestedArrayData {
+    associatedtype Element
+
+    var ptr: UnsafePointer<Element>! { get }
+    var len: UInt { get }
+    var cap: UInt { get }
+
+    init(ptr: UnsafePointer<Element>!, len: UInt, cap: UInt)
+}
+
+public extension FFINestedArrayData {
+    /// Moves `elements` (which must already have been cloned into Rust memory) into a new array
+    /// whose buffer is owned by Rust.
+    static func from(_ elements: [Element]) -> Self {
+        let ptr = elements.withUnsafeBufferPointer { buffer in
+            ffi_nested_array_init(
+                UnsafeRawPointer(buffer.baseAddress),
+                UInt(buffer.count),
+                UInt(MemoryLayout<Element>.stride),
+                UInt(MemoryLayout<Element>.alignment)
+            )
+        }
+        return Self(ptr: ptr?.assumingMemoryBound(to: Element.self), len: UInt(elements.count), cap: UInt(elements.count))
+    }
+
+    /// Takes ownership of each of the elements of an array that was returned by Rust, and releases
+    /// the array's buffer. The elements must be taken over (e.g. with `fromRust`) or released.
+    func takeElements() -> [Element] {
+        defer {
+            ffi_nested_array_free(
+                UnsafeRawPointer(ptr),
+                len,
+                cap,
+                UInt(MemoryLayout<Element>.stride),
+                UInt(MemoryLayout<Element>.alignment)
+            )
+        }
+        guard let ptr = ptr else { return [] }
+        return Array(UnsafeBufferPointer(start: ptr, count: Int(len)))
+    }
+}
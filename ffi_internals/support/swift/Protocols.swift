@@ -12,6 +12,8 @@ public protocol FFIArray: FFIData {
     var len: UInt { get }
     var cap: UInt { get }
 
+    /// For arrays of opaque types, this takes ownership of the elements that `ptr` points to, so
+    /// they must not be used or freed afterward.
     static func from(ptr: UnsafePointer<Value>?, len: Int) -> Self
 
     static func free(_ array: Self)
@@ -27,11 +29,18 @@ public protocol NativeData {
     static func fromRust(_ foreignObject: ForeignType) -> Self
 }
 
+/// Describes types that can wrap a pointer that's borrowed from (i.e., points into) another Rust
+/// instance. These wrappers retain `owner` to keep the pointer valid, and never free it.
+public protocol BorrowableNativeData: NativeData {
+    static func fromRust(borrowing foreignObject: ForeignType, owner: AnyObject) -> Self
+}
+
 public protocol NativeArrayData: NativeData {
     associatedtype FFIArrayType: FFIArray
 }
 
 public extension NativeArrayData where FFIArrayType.Value == ForeignType {
+    /// Clones each element of `collection` and moves the clones into a new `FFIArrayType`.
     static func ffiArrayInit<T: Collection>(_ collection: T) -> FFIArrayType where T.Element == Self {
         let ffiArray = collection.map { $0.clone() }
         let len = ffiArray.count
@@ -66,6 +75,17 @@ public extension Optional where
     }
 }
 
+/// This lets us do `NativeFoo?.fromRust(borrowing: pointerThatMightBeNil, owner: self)` for
+/// borrowed optional fields.
+public extension Optional where Wrapped: BorrowableNativeData, Wrapped.ForeignType == OpaquePointer? {
+    static func fromRust(borrowing ptr: OpaquePointer?, owner: AnyObject) -> Self {
+        guard let ptr = ptr else {
+            return .none
+        }
+        return Wrapped.fromRust(borrowing: ptr, owner: owner)
+    }
+}
+
 /// This lets us do `[NativeFoo].fromRust(instanceOfFFIArrayFoo)` and 
 /// `[instanceOfNativeFoo].clone()` whenever `NativeFoo` is `FFIArray` and `FFIArrayFoo` is
 /// `FFIArray` (both of which are trivial to generate for pretty much any type).
@@ -9,7 +9,7 @@ public struct RustError: Error {
 
     public let errorMessage: String
 
-    private init(errorMessage: String) {
+    internal init(errorMessage: String) {
         self.errorMessage = errorMessage
     }
 
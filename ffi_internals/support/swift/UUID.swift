@@ -0,0 +1,61 @@
+extension FFIArrayUuid: FFIArray {
+    public typealias Value = FFIUuid
+
+    public static func from(ptr: UnsafePointer<Value>?, len: Int) -> Self {
+        ffi_array_Uuid_init(ptr, len)
+    }
+
+    public static func free(_ array: Self) {
+        ffi_array_Uuid_free(array)
+    }
+}
+
+public extension Optional where Wrapped == UUID {
+    func clone() -> UnsafeMutablePointer<FFIUuid>? {
+        switch self {
+        case let .some(value):
+            return UnsafeMutablePointer(mutating: option_Uuid_init(true, value.clone()))
+        case .none:
+            return nil
+        }
+    }
+
+    func borrowReference() -> UnsafeMutablePointer<FFIUuid>? {
+        return clone()
+    }
+
+    static func fromRust(_ ptr: UnsafePointer<FFIUuid>?) -> Self {
+        guard let ptr = ptr else {
+            return .none
+        }
+        let value = Wrapped.fromRust(ptr.pointee)
+        free(ptr)
+        return value
+    }
+
+    static func free(_ option: UnsafePointer<FFIUuid>?) {
+        option_Uuid_free(option)
+    }
+}
+
+/// `FFIUuid` has the same layout as `uuid_t`, so UUIDs are passed across the FFI by value, without
+/// formatting or parsing a string.
+extension UUID: NativeData {
+    public typealias ForeignType = FFIUuid
+
+    public func clone() -> ForeignType {
+        return FFIUuid(_0: uuid)
+    }
+
+    public func borrowReference() -> ForeignType {
+        return clone()
+    }
+
+    public static func fromRust(_ foreignObject: ForeignType) -> Self {
+        return UUID(uuid: foreignObject._0)
+    }
+}
+
+extension UUID: NativeArrayData {
+    public typealias FFIArrayType = FFIArrayUuid
+}